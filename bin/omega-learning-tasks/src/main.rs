@@ -1,25 +1,25 @@
 use csv::Writer;
-use itertools::{Either, Itertools};
 use rayon::prelude::*;
-use std::{collections::HashMap, env, fs, path::PathBuf, time::Duration};
+use std::{collections::HashMap, env, fs, io::BufReader, path::PathBuf, time::Duration};
 use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
 use automata::automaton::{BuchiCondition, DBA, DPA, MinEvenParityCondition, Semantics};
 use automata::core::alphabet::{Alphabet, CharAlphabet};
-use automata::core::word::{OmegaWord, ReducedOmegaWord};
-use automata::core::{Color, Void, math, upw};
+use automata::core::word::ReducedOmegaWord;
+use automata::core::{Color, Void};
 use automata::representation::CollectTs;
 use automata::ts::Deterministic;
 use automata::ts::run::InfiniteObserver;
 use automata::{
     DTS, TransitionSystem,
-    automaton::InfiniteWordAutomaton,
+    automaton::{InfiniteWordAutomaton, WithInitial},
     hoa::WriteHoa,
-    random::{generate_random_dba, generate_random_dpa, generate_random_omega_words},
+    random::{generate_random_dba, generate_random_dpa},
 };
 use automata_learning::passive::{
     OmegaSample,
-    sprout::{SproutError, sprout},
+    sample::SampleGenerator,
+    sprout::{ConsistencyCheck, SproutError, SproutOptions, sprout, sprout_with_options},
 };
 use tracing::{info, warn};
 
@@ -45,6 +45,7 @@ fn main() {
         let test_size = 10000;
         let num_sets = 5;
         let lambda = 0.95;
+        let seed = 0;
         generate_tasks(
             automata_sizes,
             automata_per_size,
@@ -52,17 +53,22 @@ fn main() {
             test_size,
             num_sets,
             lambda,
+            seed,
         );
     }
     if args.contains(&"sprout".to_string()) {
         info!("Running sprout learner on all tasks");
         run_sprout();
     }
+    if args.contains(&"bench".to_string()) {
+        info!("Benchmarking consistency checks of sprout on all tasks");
+        run_bench(Duration::from_secs(60));
+    }
     info!("Done");
 }
 
-pub fn run_sprout() {
-    // load task directories
+/// Collect the directories of all learning tasks.
+pub fn load_task_dirs() -> Vec<PathBuf> {
     let mut task_dirs = vec![];
     let entries = fs::read_dir("data/tasks").expect("No learning tasks available");
 
@@ -75,6 +81,11 @@ pub fn run_sprout() {
             warn!("Couldn't get file type for {:?}", entry.path());
         }
     }
+    task_dirs
+}
+
+pub fn run_sprout() {
+    let task_dirs = load_task_dirs();
     task_dirs
         .clone()
         // .into_iter()
@@ -112,6 +123,7 @@ pub fn run_sprout() {
                     Err(SproutError::Threshold {
                         thres: _thres,
                         aut: learned,
+                        ..
                     }) => {
                         let elapsed = time.elapsed();
                         info!(
@@ -125,7 +137,11 @@ pub fn run_sprout() {
                         );
                         export_sprout_result(dir, &learned, elapsed);
                     }
-                    Err(SproutError::Timeout { aut: partial }) => {
+                    Err(
+                        SproutError::Timeout { aut: partial, .. }
+                        | SproutError::StepLimit { aut: partial, .. }
+                        | SproutError::Cancelled { aut: partial, .. },
+                    ) => {
                         let elapsed = time.elapsed();
                         info!(
                             "exceeded timeout on task {i} with partial ts of size {}: {:?}",
@@ -158,6 +174,7 @@ pub fn run_sprout() {
                     Err(SproutError::Threshold {
                         thres: _thres,
                         aut: learned,
+                        ..
                     }) => {
                         let elapsed = time.elapsed();
                         info!(
@@ -171,7 +188,11 @@ pub fn run_sprout() {
                         );
                         export_sprout_result(dir, &learned, elapsed);
                     }
-                    Err(SproutError::Timeout { aut: partial }) => {
+                    Err(
+                        SproutError::Timeout { aut: partial, .. }
+                        | SproutError::StepLimit { aut: partial, .. }
+                        | SproutError::Cancelled { aut: partial, .. },
+                    ) => {
                         let elapsed = time.elapsed();
                         info!(
                             "exceeded timeout on task {i} with partial ts of size {}: {:?}",
@@ -185,24 +206,79 @@ pub fn run_sprout() {
         });
 }
 
-/// Generate a sample of ultimately periodic words by loading the training set from
-/// the learning task located in the given dircetory.
-pub fn load_sample(dir: PathBuf) -> OmegaSample {
-    let mut rdr = csv::Reader::from_path(dir.join("train.csv")).expect("No training set found");
-    let (pos_words, neg_words): (Vec<_>, Vec<_>) = rdr.deserialize().partition_map(|result| {
-        let (spoke, cycle, class): (String, String, bool) =
-            result.expect("Failed to read training set");
-        let word = upw!(spoke, cycle);
-        if class {
-            Either::Left(word)
+/// Run sprout on all tasks, once with incremental consistency checks and once with checks
+/// on the whole sample, and export the running times to `data/bench.csv`. The tasks are
+/// run sequentially, each run is aborted after `timeout`.
+pub fn run_bench(timeout: Duration) {
+    let mut task_dirs = load_task_dirs();
+    task_dirs.sort();
+
+    let mut wtr = Writer::from_path("data/bench.csv").expect("creating file failed");
+    wtr.write_record([
+        "task",
+        "outcome",
+        "aut_size",
+        "full_ms",
+        "incremental_ms",
+        "speedup",
+    ])
+    .unwrap();
+    for dir in task_dirs {
+        let name = dir.file_name().unwrap().to_string_lossy().to_string();
+        let sample = load_sample(dir.clone());
+        let ((full, _, _), (incremental, outcome, size)) = if name.contains("dba") {
+            (
+                bench_sprout(sample.clone(), BuchiCondition, false, timeout),
+                bench_sprout(sample, BuchiCondition, true, timeout),
+            )
         } else {
-            Either::Right(word)
-        }
-    });
+            (
+                bench_sprout(sample.clone(), MinEvenParityCondition, false, timeout),
+                bench_sprout(sample, MinEvenParityCondition, true, timeout),
+            )
+        };
+        let speedup = full.as_secs_f64() / incremental.as_secs_f64();
+        info!(
+            "task {name}: full {} ms, incremental {} ms, speedup {speedup:.2}",
+            full.as_millis(),
+            incremental.as_millis()
+        );
+        wtr.write_record([
+            name,
+            outcome.to_string(),
+            size.to_string(),
+            full.as_millis().to_string(),
+            incremental.as_millis().to_string(),
+            format!("{speedup:.2}"),
+        ])
+        .unwrap();
+    }
+    wtr.flush().unwrap();
+}
 
-    // todo: check for size of alphabet
-    let alphabet = CharAlphabet::of_size(2);
-    OmegaSample::new_omega_from_pos_neg(alphabet, pos_words, neg_words)
+/// Time a single run of sprout on the given sample. Returns the elapsed time, the outcome
+/// and the size of the learned automaton or of the partial transition system.
+pub fn bench_sprout<A: ConsistencyCheck<WithInitial<DTS>>>(
+    sample: OmegaSample,
+    acc_type: A,
+    incremental: bool,
+    timeout: Duration,
+) -> (Duration, &'static str, usize) {
+    let options = SproutOptions::default()
+        .with_timeout(Some(timeout))
+        .with_incremental(incremental);
+    let time = std::time::Instant::now();
+    let (outcome, size) = match sprout_with_options(sample, acc_type, options) {
+        Ok(learned) => ("learned", learned.size()),
+        Err(err @ SproutError::Threshold { .. }) => ("threshold", err.partial().size()),
+        Err(err) => ("aborted", err.partial().size()),
+    };
+    (time.elapsed(), outcome, size)
+}
+
+/// Load the training set of the learning task located in the given directory.
+pub fn load_sample(dir: PathBuf) -> OmegaSample {
+    load_set(&dir, "train.csv")
 }
 
 /// generate set of learning tasks for DBA and DPA.
@@ -213,12 +289,25 @@ pub fn generate_tasks(
     test_size: usize,
     num_sets: usize,
     lambda: f64,
+    seed: u64,
 ) {
     // set parameters
     let num_symbols = 2;
+    let alphabet = CharAlphabet::of_size(num_symbols);
+    let mut generator = SampleGenerator::new(seed);
+    // draws a training and a test set of distinct ultimately periodic words, whose spoke and
+    // cycle lengths are drawn uniformly
+    let mut generate_set = |len_spoke: usize, len_cycle: usize, train_size: usize| {
+        let mut train = generator.words::<_, ReducedOmegaWord<char>>(
+            &alphabet,
+            &(0..=len_spoke, 1..=len_cycle),
+            train_size + test_size,
+        );
+        let test = train.split_off(train_size);
+        (train, test)
+    };
     let num_prios = 5;
     fs::create_dir_all("data/automata").unwrap();
-    fs::create_dir_all("data/sets").unwrap();
 
     // generate DBAs
     info!("generating DBAs");
@@ -254,23 +343,15 @@ pub fn generate_tasks(
         for &train_size in train_sizes.iter() {
             let mut sets_of_size_dba = vec![];
             let mut sets_of_size_dpa = vec![];
-            for i in 0..num_sets {
+            for _ in 0..num_sets {
                 // DBA sets
                 let len_spoke = std::cmp::max(8, aut_size);
                 let len_cycle = std::cmp::max(8, aut_size);
-                let (train, test) =
-                    generate_set(num_symbols, len_spoke, len_cycle, train_size, test_size);
-                export_set(set_name(aut_size, train_size, i, true, "dba"), &train);
-                export_set(set_name(aut_size, train_size, i, false, "dba"), &test);
-                sets_of_size_dba.push((train, test));
+                sets_of_size_dba.push(generate_set(len_spoke, len_cycle, train_size));
                 // DPA sets
                 let len_spoke = 2 * ((aut_size as f64).log2().ceil() as usize) - 1;
                 let len_cycle = (2 * aut_size - len_spoke) * len_spoke;
-                let (train, test) =
-                    generate_set(num_symbols, len_spoke, len_cycle, train_size, test_size);
-                export_set(set_name(aut_size, train_size, i, true, "dpa"), &train);
-                export_set(set_name(aut_size, train_size, i, false, "dpa"), &test);
-                sets_of_size_dpa.push((train, test));
+                sets_of_size_dpa.push(generate_set(len_spoke, len_cycle, train_size));
             }
             sets_dba.insert((aut_size, train_size), sets_of_size_dba);
             sets_dpa.insert((aut_size, train_size), sets_of_size_dpa);
@@ -283,8 +364,14 @@ pub fn generate_tasks(
         for (aut_index, dba) in dbas[&aut_size].iter().enumerate() {
             for &train_size in train_sizes.iter() {
                 for (set_index, (tr, te)) in sets_dba[&(aut_size, train_size)].iter().enumerate() {
-                    let train = label_set(dba, tr);
-                    let test = label_set(dba, te);
+                    let train =
+                        OmegaSample::labelled_by(dba.alphabet().clone(), tr.iter().cloned(), |w| {
+                            dba.accepts(w)
+                        });
+                    let test =
+                        OmegaSample::labelled_by(dba.alphabet().clone(), te.iter().cloned(), |w| {
+                            dba.accepts(w)
+                        });
                     // export as learning task
                     export_task(
                         task_name(
@@ -309,8 +396,14 @@ pub fn generate_tasks(
         for (aut_index, dpa) in dpas[&aut_size].iter().enumerate() {
             for &train_size in train_sizes.iter() {
                 for (set_index, (tr, te)) in sets_dpa[&(aut_size, train_size)].iter().enumerate() {
-                    let train = label_set(dpa, tr);
-                    let test = label_set(dpa, te);
+                    let train =
+                        OmegaSample::labelled_by(dpa.alphabet().clone(), tr.iter().cloned(), |w| {
+                            dpa.accepts(w)
+                        });
+                    let test =
+                        OmegaSample::labelled_by(dpa.alphabet().clone(), te.iter().cloned(), |w| {
+                            dpa.accepts(w)
+                        });
                     // export as learning task
                     export_task(
                         task_name(
@@ -371,46 +464,6 @@ pub fn generate_dpa(num_symbols: usize, aut_size: usize, num_prios: u8, lambda:
     dpa
 }
 
-/// Generate a training set, test set pair of random ultimately periodic words.
-/// The length of spoke and cycle are drawn uniformly and the used alphabet is of size `num_symbols`.
-pub fn generate_set(
-    num_symbols: usize,
-    len_spoke: usize,
-    len_cycle: usize,
-    train_size: usize,
-    test_size: usize,
-) -> (
-    math::Set<ReducedOmegaWord<char>>,
-    math::Set<ReducedOmegaWord<char>>,
-) {
-    let alphabet = CharAlphabet::of_size(num_symbols);
-    let mut training_set = generate_random_omega_words(
-        &alphabet,
-        0,
-        len_spoke,
-        1,
-        len_cycle,
-        train_size + test_size,
-    );
-    let test_set = training_set.split_off(train_size);
-    (training_set, test_set)
-}
-
-/// Label a `set` of [`ReducedOmegaWord`]s with the result of the given automaton.
-pub fn label_set<Z, C>(
-    aut: &InfiniteWordAutomaton<CharAlphabet, Z, Void, C, true>,
-    set: &math::Set<ReducedOmegaWord<char>>,
-) -> Vec<(ReducedOmegaWord<char>, bool)>
-where
-    Z: Semantics<DTS<CharAlphabet, Void, C>, true, Output = bool>,
-    Z::Observer: InfiniteObserver<DTS<CharAlphabet, Void, C>>,
-    C: Color,
-{
-    set.into_iter()
-        .map(|w| (w.clone(), aut.accepts(w)))
-        .collect()
-}
-
 /// Write the given automaton to the given `path` in HOA format
 pub fn export_automaton<AUT: WriteHoa>(file: String, aut: &AUT) {
     fs::write(file, aut.to_hoa()).expect("Unable to write file");
@@ -421,71 +474,36 @@ pub fn aut_name(aut_size: usize, aut_index: usize, acc_type: String) -> String {
     format!("data/automata/{acc_type}__aut_size={aut_size}__{aut_index:0>2}.hoa")
 }
 
-/// Write the given set to the given `path` as csv
-pub fn export_set(file: String, set: &math::Set<ReducedOmegaWord<char>>) {
-    let mut wtr = Writer::from_path(file).expect("creating file failed");
-    for w in set.iter() {
-        wtr.write_record(&[
-            w.spoke().iter().collect::<String>(),
-            w.cycle().iter().collect(),
-        ])
-        .unwrap();
-    }
-    wtr.flush().unwrap();
+/// Load labelled set from csv, the alphabet is that of the target automaton of the task.
+pub fn load_set(path: &std::path::Path, file: &str) -> OmegaSample {
+    let file = fs::File::open(path.join(file)).expect("No training set found");
+    OmegaSample::try_from_csv_read(BufReader::new(file), load_alphabet(path))
+        .expect("Failed to read training set")
 }
 
-/// Load labelled set from csv. Split into positively and negatively labelled words
-pub fn load_set(
-    path: &std::path::Path,
-    file: String,
-) -> (Vec<ReducedOmegaWord<char>>, Vec<ReducedOmegaWord<char>>) {
-    let mut rdr = csv::Reader::from_path(path.join(file)).expect("No training set found");
-    rdr.deserialize().partition_map(|result| {
-        let (spoke, cycle, class): (String, String, bool) =
-            result.expect("Failed to read training set");
-        let word = upw!(spoke, cycle);
-        if class {
-            Either::Left(word)
-        } else {
-            Either::Right(word)
-        }
-    })
+/// Load the alphabet of the target automaton of the learning task located in the given
+/// directory. Its size is recorded in the settings of the task, see [`export_settings`].
+pub fn load_alphabet(path: &std::path::Path) -> CharAlphabet {
+    let settings = fs::read_to_string(path.join("settings.txt")).expect("No settings found");
+    let num_symbols = settings
+        .lines()
+        .find_map(|line| line.strip_prefix("num_symbols,"))
+        .and_then(|n| n.trim().parse().ok())
+        .expect("Settings do not contain the number of symbols");
+    CharAlphabet::of_size(num_symbols)
 }
 
-/// Give filename for a set of omega words
-pub fn set_name(
-    aut_size: usize,
-    set_size: usize,
-    set_index: usize,
-    train: bool,
-    acc_type: &str,
-) -> String {
-    let class = if train { "train" } else { "test" };
-    format!(
-        "data/sets/word_set__{acc_type}__aut_size={aut_size}__sample_size={set_size}__{set_index:0>2}_{class}.csv"
-    )
-}
-
-pub fn export_labelled_set(file: String, set: &[(ReducedOmegaWord<char>, bool)]) {
-    let mut wtr = Writer::from_path(file).expect("creating file failed");
-    wtr.write_record(["spoke", "cycle", "acceptance"]).unwrap();
-    for (w, r) in set.iter() {
-        wtr.write_record([
-            w.spoke().iter().collect(),
-            w.cycle().iter().collect(),
-            format!("{r:?}"),
-        ])
-        .unwrap();
-    }
-    wtr.flush().unwrap();
+pub fn export_labelled_set(file: String, set: &OmegaSample) {
+    let mut file = fs::File::create(file).expect("creating file failed");
+    set.write_csv(&mut file).expect("writing file failed");
 }
 
 /// Write the given omega automata learning task to the given `path` in HOA format
 pub fn export_task<AUT: WriteHoa>(
     name: String,
     aut: &AUT,
-    train: &[(ReducedOmegaWord<char>, bool)],
-    test: &[(ReducedOmegaWord<char>, bool)],
+    train: &OmegaSample,
+    test: &OmegaSample,
 ) {
     // remove old results if they exist
     let _ = fs::remove_dir_all(format!("data/tasks/{name}"));
@@ -499,8 +517,8 @@ pub fn export_task<AUT: WriteHoa>(
         name,
         aut.alphabet().size(),
         aut.size(),
-        train.len(),
-        test.len(),
+        train.count_words(),
+        test.count_words(),
     );
 }
 
@@ -564,20 +582,26 @@ pub fn export_sprout_result<Z, C>(
 {
     let start = std::time::Instant::now();
     // load test set
-    let (test_pos, test_neg) = load_set(task_dir, "test.csv".to_string());
-    let pos_count = test_pos.len();
-    let neg_count = test_neg.len();
+    let test = load_set(task_dir, "test.csv");
+    let pos_count = test.count_positive_words();
+    let neg_count = test.count_negative_words();
     let test_size = pos_count + neg_count;
     // score test set
-    let mut scored_pos = label_set(learned, &test_pos.into_iter().collect());
-    let scored_neg = label_set(learned, &test_neg.into_iter().collect());
-    let pos_correct = scored_pos.iter().filter(|(_, b)| *b).count();
-    let neg_correct = scored_neg.iter().filter(|(_, b)| !b).count();
+    let scored = OmegaSample::labelled_by(learned.alphabet().clone(), test.words().cloned(), |w| {
+        learned.accepts(w)
+    });
+    let pos_correct = test
+        .positive_words()
+        .filter(|w| scored.classify(*w) == Some(true))
+        .count();
+    let neg_correct = test
+        .negative_words()
+        .filter(|w| scored.classify(*w) == Some(false))
+        .count();
     let total_correct = pos_correct + neg_correct;
 
     let path_str = task_dir.to_str().unwrap();
-    scored_pos.extend(scored_neg);
-    export_labelled_set(format!("{}/test_learned.csv", path_str), &scored_pos);
+    export_labelled_set(format!("{}/test_learned.csv", path_str), &scored);
 
     // export %correct, %pos/neg correct, aut size in result file
     let mut wtr = Writer::from_path(task_dir.join("result.csv")).expect("creating file failed");
@@ -6,6 +6,9 @@ pub use simple::*;
 mod propositional;
 pub use propositional::*;
 
+mod homomorphism;
+pub use homomorphism::Homomorphism;
+
 use crate::show::Show;
 
 /// A symbol of an alphabet, which is also the type of the symbols in a word. We consider different types
@@ -154,6 +157,27 @@ impl<A: Alphabet> Alphabet for &A {
     }
 }
 
+/// Alphabets whose symbols can be written as text and read back. This is used for parsing words
+/// over the alphabet, see [`crate::word::ReducedOmegaWord::try_from_str_over`].
+pub trait SymbolSyntax: Alphabet {
+    /// Parses a (possibly empty) sequence of symbols from `input`. Returns a description of the
+    /// problem if `input` is malformed.
+    fn parse_symbols(&self, input: &str) -> Result<Vec<Self::Symbol>, String>;
+
+    /// Writes the given symbols in a form that [`Self::parse_symbols`] reads back.
+    fn show_symbols(&self, symbols: &[Self::Symbol]) -> String;
+}
+
+impl<A: SymbolSyntax> SymbolSyntax for &A {
+    fn parse_symbols(&self, input: &str) -> Result<Vec<Self::Symbol>, String> {
+        A::parse_symbols(self, input)
+    }
+
+    fn show_symbols(&self, symbols: &[Self::Symbol]) -> String {
+        A::show_symbols(self, symbols)
+    }
+}
+
 /// Computes all elements of the free monoid over a set of given symbols.
 /// In other words, it builds all finite words in length-lexicographic order
 /// meaning words are computed in increasing length and sorted alphabetically.
@@ -0,0 +1,141 @@
+use itertools::Itertools;
+
+use crate::math;
+use crate::show::Show;
+use crate::word::FiniteWord;
+
+use super::Alphabet;
+
+/// Represents a homomorphism between the free monoids over two [`Alphabet`]s. Such a
+/// homomorphism is uniquely determined by the finite word that each symbol of the
+/// `domain` alphabet is mapped to. These images are words over the `codomain` alphabet.
+///
+/// # Example
+/// ```
+/// use automata_core::alphabet::{CharAlphabet, Homomorphism};
+///
+/// let h = Homomorphism::new(
+///     CharAlphabet::of_size(2),
+///     CharAlphabet::of_size(3),
+///     [('a', "ab"), ('b', "")],
+/// );
+/// assert_eq!(h.apply("aba"), vec!['a', 'b', 'a', 'b']);
+/// assert!(!h.is_non_erasing());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Homomorphism<A: Alphabet, B: Alphabet> {
+    domain: A,
+    codomain: B,
+    images: math::OrderedMap<A::Symbol, Vec<B::Symbol>>,
+}
+
+impl<A: Alphabet, B: Alphabet> Homomorphism<A, B> {
+    /// Creates a new homomorphism from `domain` to `codomain`, where each symbol of `domain` is
+    /// mapped to the word that is given for it in `images`.
+    ///
+    /// Panics if some symbol of `domain` has no image, or if an image contains a symbol that
+    /// does not belong to `codomain`.
+    pub fn new<W, I>(domain: A, codomain: B, images: I) -> Self
+    where
+        W: FiniteWord<Symbol = B::Symbol>,
+        I: IntoIterator<Item = (A::Symbol, W)>,
+    {
+        let images: math::OrderedMap<_, _> = images
+            .into_iter()
+            .map(|(sym, word)| (sym, word.collect_vec()))
+            .collect();
+        for sym in domain.universe() {
+            let Some(image) = images.get(&sym) else {
+                panic!("symbol {} has no image", sym.show());
+            };
+            if let Some(b) = image.iter().find(|b| !codomain.contains(**b)) {
+                panic!(
+                    "image of {} contains symbol {} which is not in the codomain",
+                    sym.show(),
+                    b.show()
+                );
+            }
+        }
+        Self {
+            domain,
+            codomain,
+            images,
+        }
+    }
+
+    /// Creates a letter-to-letter homomorphism, which maps every symbol of `domain` to the
+    /// single symbol of `codomain` that is given by `f`.
+    pub fn letter_to_letter<F>(domain: A, codomain: B, f: F) -> Self
+    where
+        F: Fn(A::Symbol) -> B::Symbol,
+    {
+        let images = domain
+            .universe()
+            .map(|sym| (sym, vec![f(sym)]))
+            .collect_vec();
+        Self::new(domain, codomain, images)
+    }
+
+    /// Returns a reference to the alphabet from which `self` maps.
+    pub fn domain(&self) -> &A {
+        &self.domain
+    }
+
+    /// Returns a reference to the alphabet into which `self` maps.
+    pub fn codomain(&self) -> &B {
+        &self.codomain
+    }
+
+    /// Returns the image of the given symbol. Panics if the symbol does not belong to the domain.
+    pub fn image(&self, sym: A::Symbol) -> &[B::Symbol] {
+        self.images
+            .get(&sym)
+            .unwrap_or_else(|| panic!("symbol {} is not in the domain", sym.show()))
+    }
+
+    /// Applies `self` to the given finite word, which is done by concatenating the images of
+    /// its symbols.
+    pub fn apply<W: FiniteWord<Symbol = A::Symbol>>(&self, word: W) -> Vec<B::Symbol> {
+        word.symbols()
+            .flat_map(|sym| self.image(sym).iter().copied())
+            .collect()
+    }
+
+    /// Returns true if and only if no symbol is mapped to the empty word.
+    pub fn is_non_erasing(&self) -> bool {
+        self.images.values().all(|image| !image.is_empty())
+    }
+
+    /// Returns true if and only if every symbol is mapped to a word of length exactly one.
+    pub fn is_letter_to_letter(&self) -> bool {
+        self.images.values().all(|image| image.len() == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Homomorphism;
+    use crate::alphabet::CharAlphabet;
+
+    #[test]
+    fn homomorphism_application() {
+        let h = Homomorphism::letter_to_letter(
+            CharAlphabet::of_size(3),
+            CharAlphabet::of_size(2),
+            |c| if c == 'c' { 'b' } else { 'a' },
+        );
+        assert!(h.is_letter_to_letter());
+        assert_eq!(h.apply("abc"), vec!['a', 'a', 'b']);
+        assert_eq!(h.apply(""), vec![]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn homomorphism_missing_image() {
+        Homomorphism::new(
+            CharAlphabet::of_size(2),
+            CharAlphabet::of_size(2),
+            [('a', "b")],
+        );
+    }
+}
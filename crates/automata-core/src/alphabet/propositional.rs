@@ -8,14 +8,14 @@ use std::{
 };
 
 use biodivine_lib_bdd::{
-    Bdd, BddPartialValuation, BddSatisfyingValuations, BddValuation, BddVariableSet,
+    Bdd, BddPartialValuation, BddSatisfyingValuations, BddValuation, BddVariable, BddVariableSet,
 };
 use itertools::Itertools;
 use tracing::trace;
 
 use crate::{math::OrderedSet, show::Show};
 
-use super::{Alphabet, CharAlphabet, Expression, Matcher};
+use super::{Alphabet, CharAlphabet, Expression, Matcher, SymbolSyntax};
 
 pub trait RawSymbolRepr: std::fmt::Debug + Hash + Eq + Ord + Copy {
     fn as_usize(&self) -> usize;
@@ -186,6 +186,52 @@ impl<RawTy: RawSymbolRepr> PropExpression<RawTy> {
         }
         .map(|e: PropSymbol<RawTy>| e.as_char())
     }
+
+    /// Existentially quantifies the atomic propositions with the given indices and subsequently
+    /// removes them, meaning the remaining atomic propositions are renumbered consecutively
+    /// while preserving their relative order.
+    pub fn project(&self, aps: &[usize]) -> Self {
+        assert!(aps.iter().all(|ap| *ap < self.num_aps as usize));
+        let quantified = self.bdd.exists(
+            &aps.iter()
+                .map(|ap| BddVariable::from_index(*ap))
+                .collect_vec(),
+        );
+        let mut renaming = vec![None; self.num_aps as usize];
+        for (new, old) in (0..self.num_aps as usize)
+            .filter(|ap| !aps.contains(ap))
+            .enumerate()
+        {
+            renaming[old] = Some(new);
+        }
+        let num_aps = renaming.iter().flatten().count() as u8;
+        Self::from_parts(num_aps, rename_variables(&quantified, num_aps, &renaming))
+    }
+
+    /// Adds `count` fresh atomic propositions, which are appended after the existing ones. The
+    /// resulting expression does not constrain the values of the fresh atomic propositions.
+    pub fn extend(&self, count: u8) -> Self {
+        let num_aps = self.num_aps + count;
+        let renaming = (0..self.num_aps as usize).map(Some).collect_vec();
+        Self::from_parts(num_aps, rename_variables(&self.bdd, num_aps, &renaming))
+    }
+}
+
+/// Transfers the given [`Bdd`] into a [`BddVariableSet`] with `num_vars` variables, where the variable with
+/// index `i` becomes the one with index `renaming[i]`. The bdd may not depend on any variable that is mapped
+/// to `None`.
+fn rename_variables(bdd: &Bdd, num_vars: u8, renaming: &[Option<usize>]) -> Bdd {
+    let vars = BddVariableSet::new_anonymous(num_vars as u16);
+    let clauses = bdd
+        .sat_clauses()
+        .map(|clause| {
+            BddPartialValuation::from_values_iter(clause.to_values().into_iter().map(|(v, b)| {
+                let renamed = renaming[v.to_index()].expect("bdd depends on removed variable");
+                (BddVariable::from_index(renamed), b)
+            }))
+        })
+        .collect_vec();
+    vars.mk_dnf(&clauses)
 }
 
 impl<RawTy: RawSymbolRepr> std::ops::BitAnd for PropExpression<RawTy> {
@@ -396,6 +442,43 @@ impl<RawTy: RawSymbolRepr> PropAlphabet<RawTy> {
         &self.aps
     }
 
+    /// Returns the index of the atomic proposition with the given name, if it exists.
+    pub fn ap_index(&self, name: &str) -> Option<usize> {
+        self.aps.iter().position(|ap| ap == name)
+    }
+
+    /// Builds the alphabet that is obtained by removing the atomic propositions with the given
+    /// indices. Panics if this would remove all atomic propositions.
+    pub fn project_aps(&self, aps: &[usize]) -> Self {
+        Self::new(
+            self.aps
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !aps.contains(i))
+                .map(|(_, ap)| ap.clone())
+                .collect(),
+        )
+    }
+
+    /// Builds the alphabet that is obtained by appending atomic propositions with the given names.
+    /// Panics if one of the names is already in use.
+    pub fn extend_aps<I>(&self, apnames: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let mut aps = self.aps.clone();
+        for name in apnames {
+            let name = name.to_string();
+            assert!(
+                !aps.contains(&name),
+                "atomic proposition {name} already exists"
+            );
+            aps.push(name);
+        }
+        Self::new(aps)
+    }
+
     /// Attempts to build an instance of `Self` from the given pointer to a [`CharAlphabet`]. This
     /// only works (for the moment) if the number of symbols in the given alphabet is an exact
     /// power of two.
@@ -464,6 +547,108 @@ impl<RawTy: RawSymbolRepr> Alphabet for PropAlphabet<RawTy> {
     }
 }
 
+impl<RawTy: RawSymbolRepr> PropAlphabet<RawTy> {
+    /// Builds the symbol in which the atomic proposition with index `i` has the value
+    /// `valuation[i]`.
+    fn symbol_from_valuation(&self, valuation: &[bool]) -> PropSymbol<RawTy> {
+        assert_eq!(valuation.len(), self.aps.len());
+        // the first atomic proposition corresponds to the most significant bit
+        PropSymbol::from_bools(valuation.iter().rev().copied().collect())
+    }
+
+    /// Parses a valuation of the form `{p,!q}`, without the braces. Atomic propositions that
+    /// are not mentioned are false.
+    fn parse_valuation(&self, literals: &str) -> Result<PropSymbol<RawTy>, String> {
+        let mut valuation = vec![None; self.aps.len()];
+        for literal in literals.split(',').map(str::trim).filter(|l| !l.is_empty()) {
+            let (name, value) = match literal.strip_prefix('!') {
+                Some(name) => (name.trim(), false),
+                None => (literal, true),
+            };
+            let ap = self
+                .ap_index(name)
+                .ok_or_else(|| format!("unknown atomic proposition `{name}`"))?;
+            if valuation[ap].replace(value).is_some() {
+                return Err(format!("atomic proposition `{name}` is assigned twice"));
+            }
+        }
+        Ok(self.symbol_from_valuation(
+            &valuation
+                .into_iter()
+                .map(|value| value.unwrap_or(false))
+                .collect_vec(),
+        ))
+    }
+}
+
+/// A symbol is either written as a valuation in braces, listing the atomic propositions that
+/// hold and those that do not (prefixed with `!`), e.g. `{p,!q}`, or as a bit-vector like `10`
+/// with one bit per atomic proposition. Atomic propositions that a valuation does not mention
+/// are false. Whitespace between symbols is ignored.
+impl<RawTy: RawSymbolRepr> SymbolSyntax for PropAlphabet<RawTy> {
+    fn parse_symbols(&self, input: &str) -> Result<Vec<Self::Symbol>, String> {
+        let mut symbols = vec![];
+        let mut rest = input.trim_start();
+        while let Some(first) = rest.chars().next() {
+            match first {
+                '{' => {
+                    let (literals, remainder) = rest[1..]
+                        .split_once('}')
+                        .ok_or_else(|| format!("unclosed valuation `{rest}`"))?;
+                    symbols.push(self.parse_valuation(literals)?);
+                    rest = remainder;
+                }
+                '0' | '1' if self.aps.is_empty() => {
+                    return Err(format!(
+                        "bit-vector `{rest}` is longer than the number of atomic propositions, \
+                         the only valuation is `{{}}`"
+                    ));
+                }
+                '0' | '1' => {
+                    let bits = rest
+                        .chars()
+                        .take(self.aps.len())
+                        .map(|c| match c {
+                            '0' => Ok(false),
+                            '1' => Ok(true),
+                            _ => Err(format!("unexpected `{c}` in bit-vector")),
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if bits.len() < self.aps.len() {
+                        return Err(format!(
+                            "bit-vector `{rest}` is shorter than the number of atomic propositions"
+                        ));
+                    }
+                    symbols.push(self.symbol_from_valuation(&bits));
+                    rest = &rest[bits.len()..];
+                }
+                c => {
+                    return Err(format!(
+                        "unexpected `{c}`, expected a valuation or bit-vector"
+                    ));
+                }
+            }
+            rest = rest.trim_start();
+        }
+        Ok(symbols)
+    }
+
+    fn show_symbols(&self, symbols: &[Self::Symbol]) -> String {
+        symbols
+            .iter()
+            .map(|sym| {
+                let literals = sym
+                    .as_bools()
+                    .into_iter()
+                    .zip(&self.aps)
+                    .map(|(value, ap)| if value { ap.clone() } else { format!("!{ap}") })
+                    .join(",");
+                format!("{{{literals}}}")
+            })
+            .join("")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,4 +670,30 @@ mod tests {
         assert!(PropSymbol::from_bools(vec![false, true, true]).matches(&pe));
         assert!(!PropSymbol::from_bools(vec![false, false, false]).matches(&pe));
     }
+
+    #[test]
+    fn prop_expression_projection() {
+        let pe = PropExpression::<u32>::new(3, "x_0 & !x_1 & x_2");
+
+        let projected = pe.project(&[1]);
+        assert_eq!(projected, PropExpression::new(2, "x_0 & x_1"));
+        assert_eq!(pe.project(&[0, 2]), PropExpression::new(1, "!x_0"));
+
+        let extended = projected.extend(1);
+        assert_eq!(extended, PropExpression::new(3, "x_0 & x_1"));
+        assert_eq!(extended.symbols().count(), 2);
+    }
+
+    #[test]
+    fn parse_symbols_without_aps() {
+        // `PropAlphabet::new` rejects this, but parsing must not rely on it
+        let alphabet = PropAlphabet::<u32> {
+            aps: vec![],
+            universal: PropExpression::universal(0),
+            expressions: Default::default(),
+        };
+        assert_eq!(alphabet.parse_symbols("{}{}").map(|w| w.len()), Ok(2));
+        assert!(alphabet.parse_symbols("0").is_err());
+        assert!(alphabet.parse_symbols("{} 1").is_err());
+    }
 }
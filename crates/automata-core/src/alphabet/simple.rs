@@ -6,7 +6,7 @@ use itertools::Itertools;
 
 use crate::show::Show;
 
-use super::{Alphabet, Expression, Matcher, Symbol};
+use super::{Alphabet, Expression, Matcher, Symbol, SymbolSyntax};
 
 pub trait SimpleAlphabet: Alphabet
 where
@@ -67,6 +67,34 @@ impl CharAlphabet {
     pub fn new(symbols: Vec<char>) -> Self {
         Self(symbols)
     }
+
+    /// Returns the alphabet that consists of the symbols of `self` followed by those symbols of
+    /// `other` that do not occur in `self`.
+    pub fn union(&self, other: &Self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .chain(other.0.iter().filter(|c| !self.0.contains(c)))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Returns the alphabet that consists of the symbols of `self` which also occur in `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter(|c| other.0.contains(c))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Returns true if and only if every symbol of `self` also occurs in `other`.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        self.0.iter().all(|c| other.0.contains(c))
+    }
 }
 
 impl Matcher<Self> for char {
@@ -157,6 +185,129 @@ impl SimpleAlphabet for CharAlphabet {
     }
 }
 
+/// Symbols are written one after another, whitespace between them is ignored.
+impl SymbolSyntax for CharAlphabet {
+    fn parse_symbols(&self, input: &str) -> Result<Vec<Self::Symbol>, String> {
+        input
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| {
+                if self.contains(c) {
+                    Ok(c)
+                } else {
+                    Err(format!("symbol `{c}` is not part of the alphabet"))
+                }
+            })
+            .collect()
+    }
+
+    fn show_symbols(&self, symbols: &[Self::Symbol]) -> String {
+        symbols.iter().collect()
+    }
+}
+
+/// Represents an alphabet whose symbols have names, which may consist of multiple characters.
+/// A symbol is the index of its name, so words over the alphabet are just sequences of
+/// indices. When they are parsed or shown, the symbols are given by their names, separated by
+/// whitespace.
+///
+/// # Example
+/// ```
+/// use automata_core::alphabet::{Alphabet, NamedAlphabet, SymbolSyntax};
+///
+/// let alphabet = NamedAlphabet::new(vec!["req".to_string(), "grant".to_string()]);
+/// assert_eq!(alphabet.size(), 2);
+/// assert_eq!(alphabet.parse_symbols("req req grant"), Ok(vec![0, 0, 1]));
+/// assert_eq!(alphabet.show_symbols(&[1, 0]), "grant req");
+/// ```
+#[derive(Clone, Hash, PartialEq, Eq, Debug, PartialOrd, Ord)]
+pub struct NamedAlphabet(Vec<String>);
+
+impl NamedAlphabet {
+    /// Creates a new [`NamedAlphabet`] from the given names. Panics if a name is used twice, or
+    /// if it is empty or contains whitespace.
+    pub fn new(names: Vec<String>) -> Self {
+        assert!(names.iter().all_unique(), "symbol names must be unique");
+        assert!(
+            names
+                .iter()
+                .all(|name| !name.is_empty() && !name.contains(char::is_whitespace)),
+            "symbol names must be non-empty and may not contain whitespace"
+        );
+        Self(names)
+    }
+
+    /// Returns the names of the symbols, the position of a name is its symbol.
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Returns the name of the given symbol. Panics if the symbol does not exist.
+    pub fn name(&self, symbol: usize) -> &str {
+        &self.0[symbol]
+    }
+
+    /// Returns the symbol with the given name, if it exists.
+    pub fn symbol(&self, name: &str) -> Option<usize> {
+        self.0.iter().position(|n| n == name)
+    }
+}
+
+impl Alphabet for NamedAlphabet {
+    type Symbol = usize;
+
+    type Expression = usize;
+
+    type Universe<'this>
+        = std::ops::Range<usize>
+    where
+        Self: 'this;
+
+    fn size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn overlapping(&self, left: &Self::Expression, right: &Self::Expression) -> bool {
+        left == right
+    }
+
+    fn universe(&self) -> Self::Universe<'_> {
+        0..self.0.len()
+    }
+
+    fn contains(&self, symbol: Self::Symbol) -> bool {
+        symbol < self.0.len()
+    }
+
+    fn search_edge<X>(
+        map: &crate::math::OrderedMap<Self::Expression, X>,
+        sym: Self::Symbol,
+    ) -> Option<(&Self::Expression, &X)> {
+        map.get_key_value(&sym)
+    }
+
+    fn make_expression(&self, symbol: Self::Symbol) -> Self::Expression {
+        assert!(self.contains(symbol), "symbol does not exist");
+        symbol
+    }
+}
+
+impl SymbolSyntax for NamedAlphabet {
+    fn parse_symbols(&self, input: &str) -> Result<Vec<Self::Symbol>, String> {
+        input
+            .split_whitespace()
+            .map(|name| {
+                self.symbol(name)
+                    .ok_or_else(|| format!("symbol `{name}` is not part of the alphabet"))
+            })
+            .collect()
+    }
+
+    fn show_symbols(&self, symbols: &[Self::Symbol]) -> String {
+        symbols.iter().map(|sym| self.name(*sym)).join(" ")
+    }
+}
+
 /// An alphabet of fixed arity, uses const generics. This is more seen as a test
 /// since the performance gains (at least for simple operations like runs) is
 /// negligible.
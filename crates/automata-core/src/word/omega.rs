@@ -2,7 +2,10 @@ use itertools::Itertools;
 use std::{fmt::Debug, marker::PhantomData};
 use thiserror::Error;
 
-use crate::{alphabet::Symbol, show::Show};
+use crate::{
+    alphabet::{Symbol, SymbolSyntax},
+    show::Show,
+};
 
 use super::{FiniteWord, Word};
 
@@ -485,6 +488,82 @@ impl ReducedOmegaWord<char> {
     }
 }
 
+impl<S: Symbol> ReducedOmegaWord<S> {
+    /// Tries to parse a word over the given `alphabet` from `value`. Like for
+    /// [`ReducedOmegaWord::try_from_str`], the word is given either as its cycle or as its
+    /// spoke and cycle separated by a comma, but the symbols are read with
+    /// [`SymbolSyntax::parse_symbols`]. Commas within braces, like in valuations of a
+    /// [`crate::alphabet::PropAlphabet`], do not separate spoke and cycle.
+    ///
+    /// # Example
+    /// ```
+    /// use automata_core::alphabet::NamedAlphabet;
+    /// use automata_core::word::{FiniteWord, OmegaWord, ReducedOmegaWord};
+    ///
+    /// let alphabet = NamedAlphabet::new(vec!["req".to_string(), "grant".to_string()]);
+    /// let word = ReducedOmegaWord::try_from_str_over("req, grant grant", &alphabet).unwrap();
+    /// assert_eq!(word.spoke().collect_vec(), vec![0]);
+    /// assert_eq!(word.cycle().collect_vec(), vec![1]);
+    /// assert_eq!(word.show_over(&alphabet), "req, grant");
+    /// ```
+    pub fn try_from_str_over<A: SymbolSyntax<Symbol = S>>(
+        value: &str,
+        alphabet: &A,
+    ) -> Result<Self, ReducedParseError> {
+        let mut depth = 0usize;
+        let separators = value
+            .char_indices()
+            .filter(|(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                *c == ',' && depth == 0
+            })
+            .map(|(i, _)| i)
+            .collect_vec();
+        let parse = |input: &str| {
+            alphabet
+                .parse_symbols(input)
+                .map_err(ReducedParseError::MalformedSymbol)
+        };
+
+        match separators[..] {
+            [] => {
+                let cycle = parse(value)?;
+                if cycle.is_empty() {
+                    return Err(ReducedParseError::Empty);
+                }
+                Ok(Self::periodic(cycle))
+            }
+            [i] => {
+                let spoke = parse(&value[..i])?;
+                let cycle = parse(&value[i + 1..])?;
+                if cycle.is_empty() {
+                    return Err(ReducedParseError::EmptyLoop);
+                }
+                Ok(Self::ultimately_periodic(spoke, cycle))
+            }
+            _ => Err(ReducedParseError::TooManyCommas),
+        }
+    }
+
+    /// Writes the word using [`SymbolSyntax::show_symbols`] of the given `alphabet`, such that
+    /// [`ReducedOmegaWord::try_from_str_over`] reads it back.
+    pub fn show_over<A: SymbolSyntax<Symbol = S>>(&self, alphabet: &A) -> String {
+        let cycle = alphabet.show_symbols(&self.word[self.loop_index..]);
+        if self.loop_index == 0 {
+            cycle
+        } else {
+            format!(
+                "{}, {cycle}",
+                alphabet.show_symbols(&self.word[..self.loop_index])
+            )
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Epsilon<S>(PhantomData<S>);
 
@@ -567,6 +646,9 @@ pub enum ReducedParseError {
     /// The word contains too many commas, when it should contain at most one.
     #[error("too many commas (more than one) in the omega word")]
     TooManyCommas,
+    /// The symbols of the word could not be parsed.
+    #[error("malformed symbols: {0}")]
+    MalformedSymbol(String),
 }
 
 impl<S: Show> Debug for ReducedOmegaWord<S> {
@@ -602,7 +684,13 @@ impl<S: Show> Debug for PeriodicOmegaWord<S> {
 
 #[cfg(test)]
 mod tests {
-    use crate::word::{FiniteWord, OmegaWord, ReducedOmegaWord, omega::deduplicate};
+    use itertools::Itertools;
+
+    use crate::alphabet::{CharAlphabet, Matcher, PropAlphabet, PropExpression};
+    use crate::show::Show;
+    use crate::word::{
+        FiniteWord, OmegaWord, ReducedOmegaWord, ReducedParseError, omega::deduplicate,
+    };
 
     use super::deduplicate_inplace;
 
@@ -616,6 +704,36 @@ mod tests {
         assert_eq!(nupw.word, vec!['a', 'b']);
     }
 
+    #[test]
+    fn parse_reduced_over_alphabets() {
+        let alphabet = CharAlphabet::of_size(2);
+        let word = ReducedOmegaWord::try_from_str_over("ab, bb b", &alphabet).unwrap();
+        assert_eq!(word, ReducedOmegaWord::ultimately_periodic("ab", "b"));
+        assert_eq!(
+            ReducedOmegaWord::try_from_str_over("ac", &alphabet),
+            Err(ReducedParseError::MalformedSymbol(
+                "symbol `c` is not part of the alphabet".to_string()
+            ))
+        );
+        assert_eq!(
+            ReducedOmegaWord::try_from_str_over("a, ", &alphabet),
+            Err(ReducedParseError::EmptyLoop)
+        );
+
+        let alphabet = PropAlphabet::<u32>::from_apnames(["p", "q"]);
+        let word = ReducedOmegaWord::try_from_str_over("{p,!q} 01, {q}{}", &alphabet).unwrap();
+        let symbols = word.raw_word().iter().map(|sym| sym.show()).collect_vec();
+        assert_eq!(symbols, vec!["10", "01", "01", "00"]);
+        assert!(word.raw_word()[0].matches(&PropExpression::new(2, "x_0 & !x_1")));
+        assert_eq!(word.show_over(&alphabet), "{p,!q}{!p,q}, {!p,q}{!p,!q}");
+        assert_eq!(
+            ReducedOmegaWord::try_from_str_over(&word.show_over(&alphabet), &alphabet),
+            Ok(word)
+        );
+        assert!(ReducedOmegaWord::try_from_str_over("{p,r}", &alphabet).is_err());
+        assert!(ReducedOmegaWord::try_from_str_over("1", &alphabet).is_err());
+    }
+
     #[test]
     fn deduplication() {
         let input = vec![1, 2, 3, 1, 2, 3];
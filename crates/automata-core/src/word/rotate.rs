@@ -50,11 +50,11 @@ pub trait Rotate: Word {
 
 impl<S: Symbol> Rotate for PeriodicOmegaWord<S> {
     fn rotate_left(&mut self, number: usize) {
-        let mid = number.rem(number);
+        let mid = number.rem(self.representation().len());
         self.representation_mut().rotate_left(mid)
     }
     fn rotate_right(&mut self, number: usize) {
-        let mid = number.rem(number);
+        let mid = number.rem(self.representation().len());
         self.representation_mut().rotate_right(mid)
     }
     fn rotations(&self) -> Rotations<S> {
@@ -84,3 +84,25 @@ impl<S: Symbol> Iterator for Rotations<S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Rotate;
+    use crate::word::PeriodicOmegaWord;
+
+    #[test]
+    fn rotate_periodic_word() {
+        let word = PeriodicOmegaWord::new("abc");
+        assert_eq!(word.cloned_rotate_left(1), PeriodicOmegaWord::new("bca"));
+        assert_eq!(word.cloned_rotate_left(5), PeriodicOmegaWord::new("cab"));
+        assert_eq!(word.cloned_rotate_right(1), PeriodicOmegaWord::new("cab"));
+        assert_eq!(
+            word.rotations().collect::<Vec<_>>(),
+            vec![
+                vec!['a', 'b', 'c'],
+                vec!['b', 'c', 'a'],
+                vec!['c', 'a', 'b']
+            ]
+        );
+    }
+}
@@ -1,6 +1,39 @@
 mod lstar;
 pub use lstar::*;
 
+mod counterexample;
+pub use counterexample::*;
+
+mod discrimination;
+pub use discrimination::*;
+
+mod lsharp;
+pub use lsharp::*;
+
+mod sul;
+pub use sul::*;
+
+mod equivalence;
+pub use equivalence::*;
+
+mod conformance;
+pub use conformance::*;
+
+mod middleware;
+pub use middleware::*;
+
+mod parallel;
+pub use parallel::*;
+
+mod omega;
+pub use omega::*;
+
+mod fdfa;
+pub use fdfa::*;
+
+mod omega_lstar;
+pub use omega_lstar::*;
+
 pub(crate) mod oracle;
 pub use oracle::*;
 
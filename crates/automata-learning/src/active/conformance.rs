@@ -0,0 +1,356 @@
+use automata::TransitionSystem;
+use automata::automaton::IntoMealyMachine;
+use automata::core::alphabet::Alphabet;
+use automata::core::alphabet::Expression;
+use automata::core::word::FiniteWord;
+use automata::core::{Color, math};
+use automata::ts::{StateIndex, SymbolOf};
+use itertools::Itertools;
+use tracing::debug;
+
+use super::{Counterexample, EquivalenceOracle, Hypothesis};
+
+type Word<H> = Vec<SymbolOf<H>>;
+
+/// Computes a word on which the states `p` and `q` of `hypothesis` produce different outputs, or
+/// `None` if they are equivalent. If the hypothesis produces an output on the empty word, as for
+/// Moore machines and DFAs, the empty word is returned when it separates the states. Otherwise,
+/// the hypothesis is viewed as a Mealy machine whose edges are colored with the output that is
+/// produced when taking them, so that the witness can be obtained from
+/// [`IntoMealyMachine::witness_inequivalence`] of the machines starting in `p` and in `q`.
+pub fn separating_word<H: Hypothesis>(
+    hypothesis: &H,
+    p: StateIndex<H>,
+    q: StateIndex<H>,
+) -> Option<Word<H>> {
+    let empty: Word<H> = vec![];
+    if H::mandatory_experiments(hypothesis.alphabet())
+        .into_iter()
+        .any(|e| e.is_empty())
+        && hypothesis.output_from(&empty, p) != hypothesis.output_from(&empty, q)
+    {
+        return Some(empty);
+    }
+    let outputs = hypothesis.map_edge_colors_full(|source, expression, _, _| {
+        let a = expression
+            .symbols()
+            .next()
+            .expect("edges of a hypothesis are labeled with symbols");
+        hypothesis.output_from([a], source)
+    });
+    IntoMealyMachine::from_parts(outputs.clone(), p)
+        .witness_inequivalence(&IntoMealyMachine::from_parts(outputs, q))
+}
+
+/// Returns the access words of all reachable states of `hypothesis` in length-lexicographic
+/// order, which form a state cover.
+fn state_cover<H: Hypothesis>(hypothesis: &H) -> Vec<(Word<H>, StateIndex<H>)> {
+    hypothesis
+        .minimal_representatives_iter()
+        .map(|mr| mr.decompose())
+        .collect()
+}
+
+/// Computes a separating word for every unordered pair of distinct reachable states. Panics if
+/// two states are equivalent, i.e. if `hypothesis` is not minimal.
+fn separating_words<H: Hypothesis>(
+    hypothesis: &H,
+) -> math::OrderedMap<(StateIndex<H>, StateIndex<H>), Word<H>> {
+    let states = state_cover(hypothesis)
+        .into_iter()
+        .map(|(_, q)| q)
+        .sorted()
+        .collect_vec();
+    states
+        .iter()
+        .tuple_combinations()
+        .map(|(p, q)| {
+            let word = separating_word(hypothesis, *p, *q)
+                .expect("conformance testing requires a minimal hypothesis");
+            ((*p, *q), word)
+        })
+        .collect()
+}
+
+/// Computes a characterizing set of `hypothesis`, which contains a separating word for every
+/// pair of distinct reachable states. Panics if `hypothesis` is not minimal.
+pub fn characterizing_set<H: Hypothesis>(hypothesis: &H) -> Vec<Word<H>> {
+    separating_words(hypothesis)
+        .into_values()
+        .collect::<math::Set<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Computes harmonized state identifiers for all reachable states of `hypothesis`. The identifier
+/// of a state `q` contains for every other state `p` a word that separates `p` from `q`, and the
+/// same word is used in the identifier of `p`. Panics if `hypothesis` is not minimal.
+pub fn state_identifiers<H: Hypothesis>(
+    hypothesis: &H,
+) -> math::OrderedMap<StateIndex<H>, Vec<Word<H>>> {
+    let mut identifiers: math::OrderedMap<_, math::Set<_>> = state_cover(hypothesis)
+        .into_iter()
+        .map(|(_, q)| (q, math::Set::default()))
+        .collect();
+    for ((p, q), word) in separating_words(hypothesis) {
+        identifiers.get_mut(&p).unwrap().insert(word.clone());
+        identifiers.get_mut(&q).unwrap().insert(word);
+    }
+    identifiers
+        .into_iter()
+        .map(|(q, words)| (q, words.into_iter().collect()))
+        .collect()
+}
+
+/// Returns all words of length at most `length`, including the empty word.
+fn words_up_to<S: Copy>(symbols: &[S], length: usize) -> Vec<Vec<S>> {
+    (0..=length)
+        .flat_map(|n| {
+            std::iter::repeat_n(symbols.iter().copied(), n)
+                .multi_cartesian_product()
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// The method that is used for deriving a test suite from a hypothesis. All methods produce
+/// complete test suites: if the system under test has at most as many states as the hypothesis
+/// plus the number of extra states, then it is equivalent to the hypothesis if and only if it
+/// produces the same outputs on all prefixes of all words in the test suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConformanceMethod {
+    /// The W-method of Chow and Vasilevskii, which appends a characterizing set to every word
+    /// of the transition cover, extended by all words of length at most the number of extra
+    /// states.
+    W,
+    /// The Wp-method of Fujiwara et al., which only uses the full characterizing set for the
+    /// state cover. All other words of the transition cover are followed by the identifier of
+    /// the state that is reached.
+    Wp,
+    /// The HSI-method of Petrenko et al., which appends the harmonized state identifier of the
+    /// reached state to every word of the transition cover.
+    Hsi,
+}
+
+impl ConformanceMethod {
+    /// Computes the test suite for `hypothesis`, which is complete for systems with at most
+    /// `extra_states` more states than `hypothesis`. Panics if `hypothesis` is not minimal.
+    pub fn test_suite<H: Hypothesis>(&self, hypothesis: &H, extra_states: usize) -> Vec<Word<H>> {
+        let symbols = hypothesis.alphabet().universe().collect_vec();
+        let middle = words_up_to(&symbols, extra_states);
+        let cover = state_cover(hypothesis);
+        let transition_cover = cover
+            .iter()
+            .flat_map(|(access, q)| {
+                symbols.iter().map(move |a| {
+                    let mut word = access.clone();
+                    word.push(*a);
+                    (word, hypothesis.successor_index(*q, *a).unwrap())
+                })
+            })
+            .collect_vec();
+        let full = characterizing_set(hypothesis);
+        let identifiers = state_identifiers(hypothesis);
+
+        let mut suite = math::Set::default();
+        // appends the infixes to `prefix`, followed by either the given fixed suffixes or by the
+        // identifier of the state that is reached
+        let mut extend = |prefix: &Word<H>, state: StateIndex<H>, fixed: Option<&Vec<Word<H>>>| {
+            for infix in &middle {
+                let reached = hypothesis
+                    .reached_state_index_from(state, infix)
+                    .expect("hypothesis must be complete");
+                let suffixes = fixed.unwrap_or(&identifiers[&reached]);
+                let base = prefix.iter().chain(infix).copied().collect_vec();
+                if suffixes.is_empty() {
+                    suite.insert(base.clone());
+                }
+                for suffix in suffixes {
+                    suite.insert(base.iter().chain(suffix).copied().collect_vec());
+                }
+            }
+        };
+        match self {
+            ConformanceMethod::W => {
+                for (word, q) in cover.iter().chain(&transition_cover) {
+                    extend(word, *q, Some(&full));
+                }
+            }
+            ConformanceMethod::Wp => {
+                for (word, q) in &cover {
+                    extend(word, *q, Some(&full));
+                }
+                for (word, q) in &transition_cover {
+                    extend(word, *q, None);
+                }
+            }
+            ConformanceMethod::Hsi => {
+                for (word, q) in cover.iter().chain(&transition_cover) {
+                    extend(word, *q, None);
+                }
+            }
+        }
+        suite.into_iter().filter(|word| !word.is_empty()).collect()
+    }
+}
+
+/// An [`EquivalenceOracle`] that derives a test suite from the hypothesis with one of the
+/// [`ConformanceMethod`]s and tests all prefixes of the words in it. If the target has at most
+/// `extra_states` more states than the hypothesis, a counterexample is found whenever one exists.
+#[derive(Debug, Clone, Copy)]
+pub struct ConformanceTesting {
+    method: ConformanceMethod,
+    extra_states: usize,
+}
+
+impl ConformanceTesting {
+    /// Creates a new instance that uses the given method and number of extra states.
+    pub fn new(method: ConformanceMethod, extra_states: usize) -> Self {
+        Self {
+            method,
+            extra_states,
+        }
+    }
+}
+
+impl<A: Alphabet, X: Color> EquivalenceOracle<A, X> for ConformanceTesting {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        let suite = self.method.test_suite(hypothesis, self.extra_states);
+        debug!("testing suite of {} words", suite.len());
+        let mut tested = math::Set::default();
+        for word in suite {
+            for i in 1..=word.len() {
+                if !tested.insert(word[..i].to_vec()) {
+                    continue;
+                }
+                let expected = query(&word[..i]);
+                if hypothesis.output(&word[..i]) != expected {
+                    return Some((word[..i].to_vec(), expected));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ConformanceMethod, ConformanceTesting, characterizing_set, separating_word,
+        state_identifiers,
+    };
+    use crate::active::{BlackBoxOracle, DFAOracle, LSharp, LStar, MealyOracle, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::{DFA, MealyMachine};
+    use automata::core::math;
+    use automata::random::generate_random_mealy;
+    use automata::ts::TSBuilder;
+
+    fn machine() -> MealyMachine {
+        TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 0, 1),
+                (0, 'b', 0, 0),
+                (1, 'a', 0, 2),
+                (1, 'b', 0, 0),
+                (2, 'a', 1, 0),
+                (2, 'b', 0, 0),
+            ])
+            .into_mealy(0)
+    }
+
+    #[test]
+    fn separating_words() {
+        let mm = machine();
+        assert_eq!(separating_word(&mm, 1, 2), Some(vec!['a']));
+        assert_eq!(separating_word(&mm, 0, 1), Some(vec!['a', 'a']));
+        assert_eq!(separating_word(&mm, 0, 0), None);
+
+        // for a DFA, the output of a word is the color of the state it reaches
+        let dfa = DFA::builder()
+            .with_state_colors([true, false, false])
+            .with_edges([
+                (0, 'a', 1),
+                (1, 'a', 2),
+                (2, 'a', 0),
+                (0, 'b', 0),
+                (1, 'b', 1),
+                (2, 'b', 2),
+            ])
+            .into_dfa(0);
+        assert_eq!(separating_word(&dfa, 0, 1), Some(vec![]));
+        assert_eq!(separating_word(&dfa, 1, 2), Some(vec!['a']));
+
+        let w = characterizing_set(&mm);
+        assert_eq!(w, vec![vec!['a', 'a'], vec!['a']]);
+        let identifiers = state_identifiers(&mm);
+        assert_eq!(identifiers[&0], vec![vec!['a', 'a'], vec!['a']]);
+        assert_eq!(identifiers[&2], vec![vec!['a']]);
+    }
+
+    #[test]
+    fn separated_by_empty_word() {
+        // the minimal DFA for the language that only contains the empty word, whose states can
+        // only be told apart by their outputs on the empty word
+        let dfa = DFA::builder()
+            .with_state_colors([true, false])
+            .with_edges([(0, 'a', 1), (1, 'a', 1)])
+            .into_dfa(0);
+        assert_eq!(separating_word(&dfa, 0, 1), Some(vec![]));
+        assert_eq!(characterizing_set(&dfa), vec![vec![]]);
+        for method in [
+            ConformanceMethod::W,
+            ConformanceMethod::Wp,
+            ConformanceMethod::Hsi,
+        ] {
+            let testing = ConformanceTesting::new(method, 1);
+            let oracle = BlackBoxOracle::new(DFAOracle::new(dfa.clone()), testing);
+            assert!(oracle.equivalence(&dfa).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_suites_are_subsets() {
+        let mm = machine();
+        for extra_states in 0..3 {
+            let w: math::Set<_> = ConformanceMethod::W
+                .test_suite(&mm, extra_states)
+                .into_iter()
+                .collect();
+            for method in [ConformanceMethod::Wp, ConformanceMethod::Hsi] {
+                let suite = method.test_suite(&mm, extra_states);
+                assert!(suite.len() <= w.len());
+                assert!(suite.iter().all(|word| w.contains(word)));
+            }
+        }
+    }
+
+    #[test]
+    fn conformance_testing_is_complete() {
+        for _ in 0..10 {
+            let target = generate_random_mealy(2, 3, 6);
+            for method in [
+                ConformanceMethod::W,
+                ConformanceMethod::Wp,
+                ConformanceMethod::Hsi,
+            ] {
+                // hypotheses have at least one state, so the number of extra states is bounded
+                let testing = ConformanceTesting::new(method, target.size() - 1);
+                let oracle = BlackBoxOracle::new(MealyOracle::new(&target), testing);
+                let mm: MealyMachine = LStar::new(oracle.alphabet().clone(), oracle).infer();
+                assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+
+                let oracle = BlackBoxOracle::new(MealyOracle::new(&target), testing);
+                let mm = LSharp::new(oracle.alphabet().clone(), oracle).infer();
+                assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+            }
+        }
+    }
+}
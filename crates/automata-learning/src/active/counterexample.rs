@@ -0,0 +1,172 @@
+use automata::core::Color;
+use automata::core::alphabet::Symbol;
+use tracing::trace;
+
+/// The information that a [`CounterexampleHandler`] may use to decide how a counterexample is
+/// incorporated into the observation table. Membership queries that are posed through
+/// [`Self::query`] are cached and counted by the learner.
+pub trait CounterexampleContext<S: Symbol, X: Color> {
+    /// Poses a membership query for the given word.
+    fn query(&self, word: &[S]) -> X;
+    /// Returns the access word of the state that the current hypothesis reaches on `word`.
+    fn access_word(&self, word: &[S]) -> Vec<S>;
+    /// Returns `true` if there is a row for `word` in the observation table, i.e. if it is
+    /// an access word or a one-letter extension of one.
+    fn is_row(&self, word: &[S]) -> bool;
+}
+
+/// Describes how the observation table is refined in response to a counterexample.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refinement<S> {
+    /// Words that should be added to the access words of the table.
+    pub rows: Vec<Vec<S>>,
+    /// Words that should be added to the experiments of the table.
+    pub experiments: Vec<Vec<S>>,
+}
+
+impl<S> Default for Refinement<S> {
+    fn default() -> Self {
+        Self {
+            rows: vec![],
+            experiments: vec![],
+        }
+    }
+}
+
+/// A strategy for processing the counterexamples that an L*-style learner receives from an
+/// equivalence query.
+pub trait CounterexampleHandler<S: Symbol, X: Color> {
+    /// Computes the refinement for the given `counterexample`, on which the current hypothesis
+    /// produces `hypothesis_output` instead of the output of the target.
+    fn handle(
+        &self,
+        counterexample: &[S],
+        hypothesis_output: &X,
+        context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S>;
+}
+
+impl<S: Symbol, X: Color, H: CounterexampleHandler<S, X> + ?Sized> CounterexampleHandler<S, X>
+    for Box<H>
+{
+    fn handle(
+        &self,
+        counterexample: &[S],
+        hypothesis_output: &X,
+        context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S> {
+        H::handle(self, counterexample, hypothesis_output, context)
+    }
+}
+
+/// The strategy of Angluin's original L* algorithm, which adds all prefixes of the
+/// counterexample as access words.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Angluin;
+
+impl<S: Symbol, X: Color> CounterexampleHandler<S, X> for Angluin {
+    fn handle(
+        &self,
+        counterexample: &[S],
+        _hypothesis_output: &X,
+        _context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S> {
+        Refinement {
+            rows: (1..=counterexample.len())
+                .map(|i| counterexample[..i].to_vec())
+                .collect(),
+            experiments: vec![],
+        }
+    }
+}
+
+/// The strategy of Maler and Pnueli, which adds all non-empty suffixes of the counterexample
+/// as experiments. This keeps the access words consistent at all times.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MalerPnueli;
+
+impl<S: Symbol, X: Color> CounterexampleHandler<S, X> for MalerPnueli {
+    fn handle(
+        &self,
+        counterexample: &[S],
+        _hypothesis_output: &X,
+        _context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S> {
+        Refinement {
+            rows: vec![],
+            experiments: (0..counterexample.len())
+                .map(|i| counterexample[i..].to_vec())
+                .collect(),
+        }
+    }
+}
+
+/// The strategy of Rivest and Schapire, which uses a binary search to find a single suffix of
+/// the counterexample that distinguishes two states, which the hypothesis identifies. This needs
+/// a logarithmic number of membership queries in the length of the counterexample.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RivestSchapire;
+
+impl<S: Symbol, X: Color> CounterexampleHandler<S, X> for RivestSchapire {
+    fn handle(
+        &self,
+        counterexample: &[S],
+        hypothesis_output: &X,
+        context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S> {
+        // alpha(i) is the output on the access word of the state reached by the first i symbols,
+        // followed by the remaining symbols. alpha(0) is the output of the target, while
+        // alpha(len) is the output of the hypothesis.
+        let alpha = |i: usize| {
+            if i == counterexample.len() {
+                return hypothesis_output.clone();
+            }
+            let mut word = context.access_word(&counterexample[..i]);
+            word.extend_from_slice(&counterexample[i..]);
+            context.query(&word)
+        };
+
+        let (mut low, mut high) = (0, counterexample.len());
+        let high_output = hypothesis_output.clone();
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if alpha(mid) != high_output {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        trace!("found distinguishing suffix at position {high}");
+        Refinement {
+            rows: vec![],
+            experiments: vec![counterexample[high..].to_vec()],
+        }
+    }
+}
+
+/// The strategy of Shahbaz and Groz, which removes the longest prefix of the counterexample that
+/// already has a row in the observation table and adds all non-empty suffixes of the remainder
+/// as experiments.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ShahbazGroz;
+
+impl<S: Symbol, X: Color> CounterexampleHandler<S, X> for ShahbazGroz {
+    fn handle(
+        &self,
+        counterexample: &[S],
+        _hypothesis_output: &X,
+        context: &dyn CounterexampleContext<S, X>,
+    ) -> Refinement<S> {
+        let prefix = (0..counterexample.len())
+            .rev()
+            .find(|i| context.is_row(&counterexample[..*i]))
+            .unwrap_or(0);
+        let remainder = &counterexample[prefix..];
+        Refinement {
+            rows: vec![],
+            experiments: (0..remainder.len())
+                .map(|i| remainder[i..].to_vec())
+                .collect(),
+        }
+    }
+}
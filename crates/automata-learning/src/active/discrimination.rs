@@ -0,0 +1,726 @@
+use std::cell::RefCell;
+
+use automata::DTS;
+use automata::core::alphabet::Alphabet;
+use automata::core::math;
+use automata::core::word::FiniteWord;
+use automata::ts::{ForAlphabet, Sproutable, SymbolOf};
+use itertools::Itertools;
+use tracing::{debug, info, trace};
+
+use super::{Experiments, Hypothesis, oracle::Oracle};
+
+type Word<D> = Vec<SymbolOf<D>>;
+
+#[derive(Debug, Clone)]
+enum Node<S, X> {
+    /// A leaf, which corresponds to the state with the given index.
+    Leaf(usize),
+    /// An inner node, whose children are sorted by the output of the discriminator. Only [`Ttt`]
+    /// uses temporary discriminators, which are eventually replaced by final ones.
+    Inner {
+        discriminator: Vec<S>,
+        children: math::Map<X, usize>,
+        temporary: bool,
+    },
+}
+
+/// The learners that are built on top of a [`DiscriminationTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Variant {
+    KearnsVazirani,
+    KvShortened,
+    Ttt,
+}
+
+/// The part that is shared between [`KearnsVazirani`], [`KvShortened`] and [`Ttt`]. It maintains a discrimination
+/// tree, whose leaves are the states of the hypothesis and whose inner nodes are labeled with
+/// discriminators, i.e. suffixes that separate the access words in different subtrees.
+struct DiscriminationTree<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet>> {
+    alphabet: D::Alphabet,
+    symbols: Vec<SymbolOf<D>>,
+    oracle: T,
+    // all queries that have been posed so far, together with their output
+    queries: RefCell<math::OrderedMap<Word<D>, D::Output>>,
+    equivalence_queries: usize,
+    nodes: Vec<Node<SymbolOf<D>, D::Output>>,
+    // the parent of each node, which is `None` for the root and for nodes that were discarded
+    parents: Vec<Option<usize>>,
+    // the access word of each state, these form a spanning tree
+    access: Vec<Word<D>>,
+    // the leaf that corresponds to each state
+    leaves: Vec<usize>,
+    // the node that each transition points to, transitions that point to an inner node have to
+    // be sifted further before a hypothesis can be built
+    targets: Vec<Vec<usize>>,
+    experiments: Experiments<D>,
+    variant: Variant,
+}
+
+impl<D, T> DiscriminationTree<D, T>
+where
+    D: Hypothesis,
+    T: Oracle<Alphabet = D::Alphabet, Output = D::Output>,
+{
+    fn new(alphabet: D::Alphabet, oracle: T, variant: Variant) -> Self {
+        let symbols: Vec<_> = alphabet.universe().collect();
+        Self {
+            experiments: D::mandatory_experiments(&alphabet).into_iter().collect(),
+            targets: vec![vec![0; symbols.len()]],
+            symbols,
+            alphabet,
+            oracle,
+            queries: RefCell::new(math::OrderedMap::default()),
+            equivalence_queries: 0,
+            nodes: vec![Node::Leaf(0)],
+            parents: vec![None],
+            access: vec![vec![]],
+            leaves: vec![0],
+            variant,
+        }
+    }
+
+    fn output(&self, w: &Word<D>) -> D::Output {
+        if !self.queries.borrow().contains_key(w) {
+            let c = self.oracle.output(w);
+            self.queries.borrow_mut().insert(w.to_owned(), c);
+        }
+        self.queries.borrow().get(w).unwrap().clone()
+    }
+
+    fn concat(u: &[SymbolOf<D>], v: &[SymbolOf<D>]) -> Word<D> {
+        u.iter().chain(v).copied().collect()
+    }
+
+    /// Sifts `word` through the tree, starting in `node`, and returns the state whose leaf is
+    /// reached. If the word leaves the tree, it is the access word of a new state, which is then
+    /// added.
+    fn sift(&mut self, mut node: usize, word: &Word<D>) -> usize {
+        loop {
+            let (discriminator, children) = match &self.nodes[node] {
+                Node::Leaf(state) => return *state,
+                Node::Inner {
+                    discriminator,
+                    children,
+                    ..
+                } => (discriminator, children),
+            };
+            let output = self.output(&Self::concat(word, discriminator));
+            if let Some(child) = children.get(&output) {
+                node = *child;
+                continue;
+            }
+
+            let state = self.access.len();
+            trace!("sifting {} discovered a new state", word.as_string());
+            let leaf = self.nodes.len();
+            self.nodes.push(Node::Leaf(state));
+            self.parents.push(Some(node));
+            let Node::Inner { children, .. } = &mut self.nodes[node] else {
+                unreachable!()
+            };
+            children.insert(output, leaf);
+            self.add_state(word.clone(), leaf);
+            return state;
+        }
+    }
+
+    fn add_state(&mut self, word: Word<D>, leaf: usize) {
+        self.access.push(word);
+        self.leaves.push(leaf);
+        self.targets.push(vec![0; self.symbols.len()]);
+    }
+
+    /// Sifts every transition that points to an inner node further down until it reaches a
+    /// leaf, which may discover new states whose transitions are then sifted as well.
+    fn close(&mut self) {
+        let mut state = 0;
+        while state < self.access.len() {
+            for i in 0..self.symbols.len() {
+                let node = self.targets[state][i];
+                if let Node::Inner { .. } = self.nodes[node] {
+                    let word = Self::concat(&self.access[state], &[self.symbols[i]]);
+                    let target = self.sift(node, &word);
+                    self.targets[state][i] = self.leaves[target];
+                }
+            }
+            state += 1;
+        }
+    }
+
+    /// Returns the transitions of the hypothesis, the tree must be closed.
+    fn transitions(&self) -> Vec<Vec<usize>> {
+        self.targets
+            .iter()
+            .map(|targets| {
+                targets
+                    .iter()
+                    .map(|node| match &self.nodes[*node] {
+                        Node::Leaf(state) => *state,
+                        Node::Inner { .. } => unreachable!("transitions must be closed"),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn row(&self, state: usize) -> Vec<D::Output> {
+        self.experiments
+            .iter()
+            .map(|e| self.output(&Self::concat(&self.access[state], &e.0)))
+            .collect()
+    }
+
+    fn hypothesis(&self, transitions: &[Vec<usize>]) -> D {
+        let mut ts: DTS<_, _, _> =
+            DTS::for_alphabet_size_hint(self.alphabet.clone(), self.access.len());
+        let rows: Vec<_> = (0..self.access.len()).map(|q| self.row(q)).collect();
+        for (q, row) in rows.iter().enumerate() {
+            ts.add_state(D::give_state_color(&self.access[q], &self.experiments, row));
+        }
+        for (q, successors) in transitions.iter().enumerate() {
+            for (a, p) in self.alphabet.universe().zip(successors) {
+                let color = D::give_transition_color(
+                    &self.access[q],
+                    a,
+                    &self.access[*p],
+                    &self.experiments,
+                    &rows[q],
+                    &rows[*p],
+                );
+                ts.add_edge((q as u32, self.alphabet.make_expression(a), color, *p as u32));
+            }
+        }
+        D::from_transition_system(ts, 0)
+    }
+
+    fn infer(&mut self) -> D {
+        let start = std::time::Instant::now();
+        loop {
+            self.close();
+            let transitions = self.transitions();
+            let hypothesis = self.hypothesis(&transitions);
+            if self.variant == Variant::Ttt {
+                if let Some(witness) = self.instability(&hypothesis) {
+                    let output = hypothesis.output(&witness);
+                    self.process_counterexample(&witness, output, &transitions);
+                    continue;
+                }
+                if self.finalize(&transitions) {
+                    continue;
+                }
+            }
+            self.equivalence_queries += 1;
+            debug!("posing equivalence query with {} states", self.access.len());
+
+            let Err((witness, expected)) = self.oracle.equivalence(&hypothesis) else {
+                info!(
+                    "Execution of discrimination tree learner took {}ms",
+                    start.elapsed().as_millis()
+                );
+                return hypothesis;
+            };
+            let output = hypothesis.output(&witness);
+            assert!(output != expected);
+            self.process_counterexample(&witness, output, &transitions);
+        }
+    }
+
+    /// Decomposes the counterexample with the binary search of Rivest and Schapire and splits the
+    /// leaf of the state that has been identified with a new one.
+    fn process_counterexample(
+        &mut self,
+        word: &Word<D>,
+        hypothesis_output: D::Output,
+        transitions: &[Vec<usize>],
+    ) {
+        trace!("Processing counterexample {}", word.as_string());
+        let states: Vec<usize> = std::iter::once(0)
+            .chain(word.iter().scan(0, |q, a| {
+                let position = self.symbols.iter().position(|b| b == a).unwrap();
+                *q = transitions[*q][position];
+                Some(*q)
+            }))
+            .collect();
+
+        // alpha(i) is the output on the access word of the state reached by the first i symbols,
+        // followed by the remaining symbols
+        let alpha = |i: usize| {
+            if i == word.len() {
+                return hypothesis_output.clone();
+            }
+            self.output(&Self::concat(&self.access[states[i]], &word[i..]))
+        };
+        let (mut low, mut high) = (0, word.len());
+        while high - low > 1 {
+            let mid = (low + high) / 2;
+            if alpha(mid) != hypothesis_output {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        let new_access = Self::concat(&self.access[states[low]], &[word[low]]);
+        let old = states[high];
+        let discriminator = self.discriminator(&new_access, old, &word[high..]);
+        self.split(old, new_access, discriminator, self.variant == Variant::Ttt);
+    }
+
+    /// Returns a discriminator that separates `word` from the access word of `state`. The
+    /// given `fallback` must separate them, but for [`KvShortened`], a shorter discriminator that
+    /// consists of a symbol followed by an existing discriminator is preferred.
+    fn discriminator(&self, word: &Word<D>, state: usize, fallback: &[SymbolOf<D>]) -> Word<D> {
+        if self.variant != Variant::KvShortened {
+            return fallback.to_vec();
+        }
+        let known: math::Set<_> = self
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Inner { discriminator, .. } => Some(discriminator),
+                Node::Leaf(_) => None,
+            })
+            .collect();
+        self.alphabet
+            .universe()
+            .cartesian_product(known)
+            .map(|(a, d)| Self::concat(&[a], d))
+            .filter(|candidate| candidate.len() < fallback.len())
+            .sorted_by_key(|candidate| candidate.len())
+            .find(|candidate| {
+                self.output(&Self::concat(word, candidate))
+                    != self.output(&Self::concat(&self.access[state], candidate))
+            })
+            .unwrap_or_else(|| fallback.to_vec())
+    }
+
+    /// Replaces the leaf of `state` by an inner node with the given `discriminator`, whose
+    /// children are the leaf of `state` and a leaf for a new state with access word `word`.
+    fn split(&mut self, state: usize, word: Word<D>, discriminator: Word<D>, temporary: bool) {
+        trace!(
+            "splitting state {state} with discriminator {}",
+            discriminator.as_string()
+        );
+        let new_state = self.access.len();
+        let inner = self.leaves[state];
+        let (old_leaf, new_leaf) = (self.nodes.len(), self.nodes.len() + 1);
+        let children = math::Map::from_iter([
+            (
+                self.output(&Self::concat(&self.access[state], &discriminator)),
+                old_leaf,
+            ),
+            (self.output(&Self::concat(&word, &discriminator)), new_leaf),
+        ]);
+        assert_eq!(children.len(), 2, "discriminator must separate the words");
+
+        self.nodes.push(Node::Leaf(state));
+        self.nodes.push(Node::Leaf(new_state));
+        self.parents.extend([Some(inner), Some(inner)]);
+        self.nodes[inner] = Node::Inner {
+            discriminator,
+            children,
+            temporary,
+        };
+        self.leaves[state] = old_leaf;
+        self.add_state(word, new_leaf);
+    }
+
+    /// Returns a word on which the hypothesis disagrees with the tree, i.e. the access word of a
+    /// state followed by the discriminator of an ancestor, on which the hypothesis does not
+    /// produce the output that leads to the subtree of the state. Such a word is a counterexample
+    /// that can be found without an equivalence query.
+    fn instability(&self, hypothesis: &D) -> Option<Word<D>> {
+        self.nodes_below(0).into_iter().find_map(|node| {
+            let Node::Inner {
+                discriminator,
+                children,
+                ..
+            } = &self.nodes[node]
+            else {
+                return None;
+            };
+            children.iter().find_map(|(output, child)| {
+                self.states_below(*child)
+                    .into_iter()
+                    .map(|state| Self::concat(&self.access[state], discriminator))
+                    .find(|word| hypothesis.output(word) != *output)
+            })
+        })
+    }
+
+    /// Looks for a block, i.e. a maximal subtree of temporary discriminators, whose states can be
+    /// separated by a final discriminator. This is either a mandatory experiment or a word `a·v`,
+    /// where `v` is the final discriminator that separates the `a`-successors of the states in
+    /// the block. If one is found, it replaces the temporary discriminator at the root of the
+    /// block and `true` is returned.
+    fn finalize(&mut self, transitions: &[Vec<usize>]) -> bool {
+        let blocks = self.nodes_below(0).into_iter().filter(|node| {
+            self.is_temporary(*node) && self.parents[*node].is_none_or(|p| !self.is_temporary(p))
+        });
+        for block in blocks.collect_vec() {
+            let states = self.states_below(block);
+            let candidates = self
+                .experiments
+                .iter()
+                .map(|e| e.0.clone())
+                .chain((0..self.symbols.len()).filter_map(|i| {
+                    let lca = states
+                        .iter()
+                        .map(|q| self.leaves[transitions[*q][i]])
+                        .reduce(|x, y| self.lca(x, y))?;
+                    match &self.nodes[lca] {
+                        Node::Inner {
+                            discriminator,
+                            temporary: false,
+                            ..
+                        } => Some(Self::concat(&[self.symbols[i]], discriminator)),
+                        _ => None,
+                    }
+                }))
+                .sorted_by_key(|candidate| candidate.len())
+                .collect_vec();
+            if let Some(discriminator) = candidates.into_iter().find(|candidate| {
+                states
+                    .iter()
+                    .map(|q| self.output(&Self::concat(&self.access[*q], candidate)))
+                    .all_equal_value()
+                    .is_err()
+            }) {
+                self.replace_block(block, &states, discriminator);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Replaces the node `block` by an inner node with the final `discriminator`. Below each of
+    /// its children, the old subtree is restricted to the states that produce the corresponding
+    /// output on the discriminator. Transitions into the block have not been sifted through the
+    /// new discriminator, so they are reset to the block and sifted again.
+    fn replace_block(&mut self, block: usize, states: &[usize], discriminator: Word<D>) {
+        trace!(
+            "finalizing block {block} with discriminator {}",
+            discriminator.as_string()
+        );
+        let mut classes: math::Map<D::Output, Vec<usize>> = math::Map::default();
+        for q in states {
+            let output = self.output(&Self::concat(&self.access[*q], &discriminator));
+            classes.entry(output).or_default().push(*q);
+        }
+        let children: math::Map<_, _> = classes
+            .into_iter()
+            .map(|(output, class)| (output, self.restrict(block, &class).unwrap()))
+            .collect();
+        for child in children.values() {
+            self.parents[*child] = Some(block);
+        }
+        self.nodes[block] = Node::Inner {
+            discriminator,
+            children,
+            temporary: false,
+        };
+
+        let leaves: math::Set<_> = states.iter().map(|q| self.leaves[*q]).collect();
+        for target in self.targets.iter_mut().flatten() {
+            if leaves.contains(target) {
+                *target = block;
+            }
+        }
+    }
+
+    /// Copies the subtree below `node`, keeping only the leaves of the states in `class` and
+    /// skipping inner nodes that are left with a single child. Leaves are not copied, so that
+    /// transitions that point to them stay valid. The parent of the returned node has to be set
+    /// by the caller.
+    fn restrict(&mut self, node: usize, class: &[usize]) -> Option<usize> {
+        let Node::Inner {
+            discriminator,
+            children,
+            temporary,
+        } = self.nodes[node].clone()
+        else {
+            let Node::Leaf(state) = self.nodes[node] else {
+                unreachable!()
+            };
+            return class.contains(&state).then_some(node);
+        };
+        let children: math::Map<_, _> = children
+            .into_iter()
+            .filter_map(|(output, child)| Some((output, self.restrict(child, class)?)))
+            .collect();
+        if children.len() <= 1 {
+            return children.into_values().next();
+        }
+        let copy = self.nodes.len();
+        for child in children.values() {
+            self.parents[*child] = Some(copy);
+        }
+        self.nodes.push(Node::Inner {
+            discriminator,
+            children,
+            temporary,
+        });
+        self.parents.push(None);
+        Some(copy)
+    }
+
+    fn is_temporary(&self, node: usize) -> bool {
+        matches!(
+            self.nodes[node],
+            Node::Inner {
+                temporary: true,
+                ..
+            }
+        )
+    }
+
+    /// Returns the lowest common ancestor of the nodes `x` and `y`.
+    fn lca(&self, x: usize, y: usize) -> usize {
+        let mut ancestors = math::Set::default();
+        let mut node = Some(x);
+        while let Some(n) = node {
+            ancestors.insert(n);
+            node = self.parents[n];
+        }
+        let mut node = y;
+        while !ancestors.contains(&node) {
+            node = self.parents[node].expect("the root is a common ancestor");
+        }
+        node
+    }
+
+    /// Returns all nodes in the subtree below `node`, including `node` itself.
+    fn nodes_below(&self, node: usize) -> Vec<usize> {
+        let mut nodes = vec![];
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            nodes.push(node);
+            if let Node::Inner { children, .. } = &self.nodes[node] {
+                stack.extend(children.values().rev());
+            }
+        }
+        nodes
+    }
+
+    /// Returns the states whose leaves are in the subtree below `node`.
+    fn states_below(&self, node: usize) -> Vec<usize> {
+        self.nodes_below(node)
+            .into_iter()
+            .filter_map(|node| match self.nodes[node] {
+                Node::Leaf(state) => Some(state),
+                Node::Inner { .. } => None,
+            })
+            .collect()
+    }
+}
+
+macro_rules! discrimination_tree_learner {
+    ($(#[$meta:meta])* $name:ident, $variant:expr) => {
+        $(#[$meta])*
+        pub struct $name<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet>>(DiscriminationTree<D, T>);
+
+        impl<D, T> $name<D, T>
+        where
+            D: Hypothesis,
+            T: Oracle<Alphabet = D::Alphabet, Output = D::Output>,
+        {
+            /// Creates a new learner for the given alphabet, which poses its queries to `oracle`.
+            pub fn new(alphabet: D::Alphabet, oracle: T) -> Self {
+                Self(DiscriminationTree::new(alphabet, oracle, $variant))
+            }
+
+            /// Runs the learner until the oracle accepts the hypothesis, which is then returned.
+            pub fn infer(&mut self) -> D {
+                self.0.infer()
+            }
+
+            /// Returns the number of distinct membership queries that have been posed so far.
+            pub fn membership_queries(&self) -> usize {
+                self.0.queries.borrow().len()
+            }
+
+            /// Returns the number of equivalence queries that have been posed so far.
+            pub fn equivalence_queries(&self) -> usize {
+                self.0.equivalence_queries
+            }
+        }
+    };
+}
+
+discrimination_tree_learner!(
+    /// An implementation of the learning algorithm of Kearns and Vazirani, which organizes the
+    /// access words of the hypothesis in a discrimination tree instead of an observation table.
+    /// Counterexamples are decomposed with the binary search of Rivest and Schapire and the
+    /// resulting suffix is used as discriminator to split the leaf of the state that was
+    /// wrongly identified.
+    KearnsVazirani,
+    Variant::KearnsVazirani
+);
+
+discrimination_tree_learner!(
+    /// A variant of [`KearnsVazirani`] that keeps discriminators short: when a leaf is split, the
+    /// suffix that is obtained from the counterexample is replaced by a shortest word of the form
+    /// `a·v`, where `v` is a discriminator in the tree, if such a word separates the two states.
+    /// Unlike [`Ttt`], the discriminator is shortened right away, when it is introduced.
+    KvShortened,
+    Variant::KvShortened
+);
+
+discrimination_tree_learner!(
+    /// An implementation of the TTT algorithm of Isberner, Howar and Steffen. The access words
+    /// of the states form a spanning tree and all other transitions point into the
+    /// discrimination tree, through which they are sifted further whenever it grows. The suffix
+    /// that is obtained from a counterexample only becomes a temporary discriminator. Before an
+    /// equivalence query is posed, the hypothesis is stabilized, i.e. disagreements with the
+    /// outputs in the tree are processed as counterexamples, and the temporary discriminators
+    /// are finalized: the root of a block of temporary discriminators is replaced by a short
+    /// final discriminator `a·v`, where `v` is a final discriminator that separates the
+    /// `a`-successors of the states in the block.
+    Ttt,
+    Variant::Ttt
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{KearnsVazirani, KvShortened, Node, Ttt};
+    use crate::active::{DFAOracle, LStar, MealyOracle, MooreOracle, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::{DFA, MealyMachine, MooreMachine};
+    use automata::core::alphabet::CharAlphabet;
+    use automata::random::{generate_random_mealy_with_rng, generate_random_moore_with_rng};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use tracing::info;
+
+    #[test]
+    fn discrimination_tree_dfa() {
+        // words over {a, b} whose number of `a`s is divisible by three
+        let target = DFA::builder()
+            .with_state_colors([true, false, false])
+            .with_edges([
+                (0, 'a', 1),
+                (1, 'a', 2),
+                (2, 'a', 0),
+                (0, 'b', 0),
+                (1, 'b', 1),
+                (2, 'b', 2),
+            ])
+            .into_dfa(0);
+        let alphabet = CharAlphabet::of_size(2);
+
+        let dfa: DFA =
+            KearnsVazirani::new(alphabet.clone(), DFAOracle::new(target.clone())).infer();
+        assert_eq!(dfa.size(), 3);
+        assert!(dfa.equivalent(&target));
+        let dfa: DFA = KvShortened::new(alphabet.clone(), DFAOracle::new(target.clone())).infer();
+        assert_eq!(dfa.size(), 3);
+        assert!(dfa.equivalent(&target));
+        let dfa: DFA = Ttt::new(alphabet, DFAOracle::new(target.clone())).infer();
+        assert_eq!(dfa.size(), 3);
+        assert!(dfa.equivalent(&target));
+    }
+
+    #[test]
+    fn discrimination_tree_moore() {
+        let mut rng = StdRng::seed_from_u64(35);
+        for _ in 0..50 {
+            let symbols = rng.gen_range(1..4);
+            let (max_color, size) = (rng.gen_range(1..6), rng.gen_range(1..15));
+            let target = generate_random_moore_with_rng(&mut rng, symbols, max_color, size);
+            let alphabet = CharAlphabet::of_size(symbols);
+            let mm: MooreMachine =
+                KearnsVazirani::new(alphabet.clone(), MooreOracle::new(target.clone())).infer();
+            assert_eq!(mm.size(), target.size());
+            let mm: MooreMachine =
+                KvShortened::new(alphabet.clone(), MooreOracle::new(target.clone())).infer();
+            assert_eq!(mm.size(), target.size());
+            let mm: MooreMachine = Ttt::new(alphabet, MooreOracle::new(target.clone())).infer();
+            assert_eq!(mm.size(), target.size());
+        }
+    }
+
+    #[test]
+    fn discrimination_tree_mealy_queries() {
+        let mut rng = StdRng::seed_from_u64(35);
+        let (mut lstar, mut kv, mut shortened, mut ttt) = (0, 0, 0, 0);
+        for _ in 0..50 {
+            let (symbols, max_color, size) = (
+                rng.gen_range(1..4),
+                rng.gen_range(1..6),
+                rng.gen_range(1..20),
+            );
+            let target = generate_random_mealy_with_rng(&mut rng, symbols, max_color, size);
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = LStar::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            lstar += learner.membership_queries();
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = KearnsVazirani::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            kv += learner.membership_queries();
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = KvShortened::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            shortened += learner.membership_queries();
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = Ttt::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            ttt += learner.membership_queries();
+        }
+        info!(
+            "membership queries: LStar {lstar}, Kearns-Vazirani {kv}, shortened {shortened}, TTT {ttt}"
+        );
+        assert!(kv < lstar);
+        assert!(shortened < lstar);
+        assert!(ttt < lstar);
+    }
+
+    #[test]
+    fn ttt_finalizes_discriminators() {
+        let mut rng = StdRng::seed_from_u64(35);
+        for _ in 0..50 {
+            let (symbols, max_color, size) = (
+                rng.gen_range(1..4),
+                rng.gen_range(2..6),
+                rng.gen_range(1..20),
+            );
+            let target = generate_random_mealy_with_rng(&mut rng, symbols, max_color, size);
+            let oracle = MealyOracle::new(&target);
+            let mut learner = Ttt::new(oracle.alphabet().clone(), oracle);
+            let _: MealyMachine = learner.infer();
+            // every discriminator is final and either a single symbol, i.e. a mandatory
+            // experiment, or extends another discriminator of the tree by one symbol
+            let tree = &learner.0;
+            let discriminators: Vec<_> = tree
+                .nodes_below(0)
+                .into_iter()
+                .filter_map(|node| match &tree.nodes[node] {
+                    Node::Inner {
+                        discriminator,
+                        temporary,
+                        ..
+                    } => Some((discriminator, *temporary)),
+                    Node::Leaf(_) => None,
+                })
+                .collect();
+            for (discriminator, temporary) in &discriminators {
+                assert!(!temporary);
+                assert!(
+                    discriminator.len() == 1
+                        || discriminators
+                            .iter()
+                            .any(|(v, _)| **v == discriminator[1..])
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,459 @@
+use std::cell::{Cell, RefCell};
+
+use automata::core::Color;
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use tracing::{debug, trace};
+
+use super::{Counterexample, Hypothesis, Oracle};
+
+/// A strategy for approximating equivalence queries, which only relies on membership queries
+/// to the target. This is used when the target is a black box, for which no exact equivalence
+/// check is possible.
+///
+/// Strategies can be composed by combining them in a tuple, which tries each strategy in order
+/// until one of them finds a counterexample.
+pub trait EquivalenceOracle<A: Alphabet, X: Color> {
+    /// Searches for a word on which `hypothesis` disagrees with the target, whose outputs are
+    /// obtained through `query`. If one is found, it is returned together with the output of
+    /// the target.
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>;
+}
+
+impl<A: Alphabet, X: Color, E: EquivalenceOracle<A, X>> EquivalenceOracle<A, X> for &E {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        E::find_counterexample(self, hypothesis, query)
+    }
+}
+
+impl<A, X, E, F> EquivalenceOracle<A, X> for (E, F)
+where
+    A: Alphabet,
+    X: Color,
+    E: EquivalenceOracle<A, X>,
+    F: EquivalenceOracle<A, X>,
+{
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        self.0
+            .find_counterexample(hypothesis, query)
+            .or_else(|| self.1.find_counterexample(hypothesis, query))
+    }
+}
+
+/// Compares the hypothesis with the target on a single word. The empty word is only tested if
+/// the hypothesis produces an output on it, which is the case if the empty experiment is
+/// mandatory, as for Moore machines and DFAs, but not for Mealy machines.
+fn test<A: Alphabet, X: Color, H: Hypothesis<Alphabet = A, Output = X>>(
+    hypothesis: &H,
+    query: &dyn Fn(&[A::Symbol]) -> X,
+    word: &[A::Symbol],
+) -> Option<Counterexample<A, X>> {
+    if word.is_empty()
+        && !H::mandatory_experiments(hypothesis.alphabet())
+            .into_iter()
+            .any(|e| e.is_empty())
+    {
+        return None;
+    }
+    let expected = query(word);
+    if hypothesis.output(word) != expected {
+        trace!("found counterexample {}", word.as_string());
+        return Some((word.to_vec(), expected));
+    }
+    None
+}
+
+/// Tests the access word of every state of the hypothesis, extended by all words of length at
+/// most `depth`.
+#[derive(Debug, Clone, Copy)]
+pub struct AccessExtensions {
+    depth: usize,
+}
+
+impl AccessExtensions {
+    /// Creates a new instance that extends access words by words of length at most `depth`.
+    pub fn new(depth: usize) -> Self {
+        Self { depth }
+    }
+}
+
+impl<A: Alphabet, X: Color> EquivalenceOracle<A, X> for AccessExtensions {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        let symbols = hypothesis.alphabet().universe().collect_vec();
+        for access in hypothesis.minimal_representatives_iter() {
+            for length in 0..=self.depth {
+                for suffix in
+                    std::iter::repeat_n(symbols.iter().copied(), length).multi_cartesian_product()
+                {
+                    let word: Vec<_> = access.iter().copied().chain(suffix).collect();
+                    if let Some(counterexample) = test(hypothesis, query, &word) {
+                        return Some(counterexample);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Performs random walks through the hypothesis. Each walk starts in a uniformly chosen state,
+/// which is reached by its access word, and then follows uniformly chosen symbols. After each
+/// step, the walk is ended with the configured probability. The output is checked after every
+/// step and the search stops once the total number of steps is exhausted.
+#[derive(Debug, Clone)]
+pub struct RandomWalk {
+    steps: usize,
+    stop_probability: f64,
+    rng: RefCell<StdRng>,
+}
+
+impl RandomWalk {
+    /// Creates a new instance that takes at most `steps` steps in total, ends each walk with
+    /// probability `stop_probability` after every step and uses an RNG seeded with `seed`.
+    pub fn new(steps: usize, stop_probability: f64, seed: u64) -> Self {
+        assert!(
+            stop_probability > 0.0 && stop_probability <= 1.0,
+            "stop probability must be in (0, 1]"
+        );
+        Self {
+            steps,
+            stop_probability,
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+impl<A: Alphabet, X: Color> EquivalenceOracle<A, X> for RandomWalk {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        let symbols = hypothesis.alphabet().universe().collect_vec();
+        let access = hypothesis
+            .minimal_representatives_iter()
+            .map(|mr| mr.to_vec())
+            .collect_vec();
+        let mut rng = self.rng.borrow_mut();
+        let mut remaining = self.steps;
+        while remaining > 0 {
+            let mut word = access.choose(&mut *rng).unwrap().clone();
+            loop {
+                word.push(*symbols.choose(&mut *rng)?);
+                remaining -= 1;
+                if let Some(counterexample) = test(hypothesis, query, &word) {
+                    return Some(counterexample);
+                }
+                if remaining == 0 || rng.gen_bool(self.stop_probability) {
+                    break;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Tests a fixed number of random words, whose length is chosen uniformly from a range and
+/// whose symbols are chosen uniformly from the alphabet.
+#[derive(Debug, Clone)]
+pub struct RandomWords {
+    count: usize,
+    min_length: usize,
+    max_length: usize,
+    rng: RefCell<StdRng>,
+}
+
+impl RandomWords {
+    /// Creates a new instance that tests `count` words with a length between `min_length` and
+    /// `max_length` (both inclusive), using an RNG seeded with `seed`.
+    pub fn new(count: usize, min_length: usize, max_length: usize, seed: u64) -> Self {
+        assert!(min_length <= max_length, "length range must not be empty");
+        Self {
+            count,
+            min_length,
+            max_length,
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+}
+
+/// Draws `count` random words and returns the first one that is a counterexample.
+fn sample<A: Alphabet, X: Color, H: Hypothesis<Alphabet = A, Output = X>>(
+    hypothesis: &H,
+    query: &dyn Fn(&[A::Symbol]) -> X,
+    rng: &mut StdRng,
+    count: usize,
+    (min_length, max_length): (usize, usize),
+) -> Option<Counterexample<A, X>> {
+    let symbols = hypothesis.alphabet().universe().collect_vec();
+    for _ in 0..count {
+        let length = rng.gen_range(min_length..=max_length);
+        let word: Option<Vec<_>> = (0..length).map(|_| symbols.choose(rng).copied()).collect();
+        if let Some(counterexample) = test(hypothesis, query, &word?) {
+            return Some(counterexample);
+        }
+    }
+    None
+}
+
+impl<A: Alphabet, X: Color> EquivalenceOracle<A, X> for RandomWords {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        sample(
+            hypothesis,
+            query,
+            &mut self.rng.borrow_mut(),
+            self.count,
+            (self.min_length, self.max_length),
+        )
+    }
+}
+
+/// Sampling of random words as in the PAC (probably approximately correct) setting of Angluin.
+/// In the `i`-th round, counting from zero, `⌈(ln(1/δ) + (i+1)·ln 2) / ε⌉` words are tested. The
+/// failure probabilities of the rounds are then bounded by `δ/2, δ/4, …`, which guarantees that
+/// with probability at least `1 - δ`, the returned hypothesis disagrees with the target on at most
+/// an `ε` fraction of words with respect to the distribution from which words are drawn.
+#[derive(Debug, Clone)]
+pub struct Pac {
+    epsilon: f64,
+    delta: f64,
+    min_length: usize,
+    max_length: usize,
+    round: Cell<usize>,
+    rng: RefCell<StdRng>,
+}
+
+impl Pac {
+    /// Creates a new instance with accuracy `epsilon` and confidence `delta`, which draws words
+    /// of a length between `min_length` and `max_length` using an RNG seeded with `seed`.
+    pub fn new(epsilon: f64, delta: f64, min_length: usize, max_length: usize, seed: u64) -> Self {
+        assert!(epsilon > 0.0 && epsilon < 1.0, "epsilon must be in (0, 1)");
+        assert!(delta > 0.0 && delta < 1.0, "delta must be in (0, 1)");
+        assert!(min_length <= max_length, "length range must not be empty");
+        Self {
+            epsilon,
+            delta,
+            min_length,
+            max_length,
+            round: Cell::new(0),
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Returns the number of tests that are performed in the given round, starting from zero.
+    pub fn tests_in_round(&self, round: usize) -> usize {
+        (((1.0 / self.delta).ln() + (round + 1) as f64 * 2f64.ln()) / self.epsilon).ceil() as usize
+    }
+}
+
+impl<A: Alphabet, X: Color> EquivalenceOracle<A, X> for Pac {
+    fn find_counterexample<H>(
+        &self,
+        hypothesis: &H,
+        query: &dyn Fn(&[A::Symbol]) -> X,
+    ) -> Option<Counterexample<A, X>>
+    where
+        H: Hypothesis<Alphabet = A, Output = X>,
+    {
+        let round = self.round.get();
+        self.round.set(round + 1);
+        let count = self.tests_in_round(round);
+        debug!("PAC round {round} performs {count} tests");
+        sample(
+            hypothesis,
+            query,
+            &mut self.rng.borrow_mut(),
+            count,
+            (self.min_length, self.max_length),
+        )
+    }
+}
+
+/// An [`Oracle`] that answers membership queries through an underlying oracle, but approximates
+/// equivalence queries with an [`EquivalenceOracle`]. This way, any learner can be used with a
+/// target that is only accessible through membership queries.
+#[derive(Debug, Clone)]
+pub struct BlackBoxOracle<T, E> {
+    oracle: T,
+    equivalence: E,
+}
+
+impl<T: Oracle, E: EquivalenceOracle<T::Alphabet, T::Output>> BlackBoxOracle<T, E> {
+    /// Creates a new instance, which answers membership queries with `oracle` and searches for
+    /// counterexamples with the given `equivalence` strategy.
+    pub fn new(oracle: T, equivalence: E) -> Self {
+        Self {
+            oracle,
+            equivalence,
+        }
+    }
+
+    /// Returns a reference to the oracle that answers membership queries.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+}
+
+impl<T: Oracle, E: EquivalenceOracle<T::Alphabet, T::Output>> Oracle for BlackBoxOracle<T, E> {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = <Self::Alphabet as Alphabet>::Symbol>>(
+        &self,
+        word: W,
+    ) -> Self::Output {
+        self.oracle.output(word)
+    }
+
+    fn output_sequence(&self, word: &[<Self::Alphabet as Alphabet>::Symbol]) -> Vec<Self::Output> {
+        self.oracle.output_sequence(word)
+    }
+
+    fn output_batch(
+        &self,
+        words: &[Vec<<Self::Alphabet as Alphabet>::Symbol>],
+    ) -> Vec<Self::Output> {
+        self.oracle.output_batch(words)
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        match self
+            .equivalence
+            .find_counterexample(hypothesis, &|word| self.oracle.output(word))
+        {
+            Some(counterexample) => Err(counterexample),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AccessExtensions, BlackBoxOracle, EquivalenceOracle, Pac, RandomWalk, RandomWords,
+    };
+    use crate::active::{DFAOracle, LSharp, LStar, MealyOracle, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::{DFA, MealyMachine};
+    use automata::random::generate_random_mealy_with_rng;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn pac_rounds() {
+        let pac = Pac::new(0.1, 0.05, 1, 10, 0);
+        assert_eq!(pac.tests_in_round(0), 37);
+        assert_eq!(pac.tests_in_round(1), 44);
+        assert_eq!(pac.tests_in_round(10), 107);
+    }
+
+    #[test]
+    fn black_box_learning() {
+        for seed in 0..10 {
+            let target = generate_random_mealy_with_rng(&mut StdRng::seed_from_u64(seed), 2, 3, 8);
+
+            // a walk that is long enough finds all differences with overwhelming probability
+            let walk = RandomWalk::new(20_000, 0.1, seed);
+            let oracle = BlackBoxOracle::new(MealyOracle::new(&target), walk);
+            let mm: MealyMachine = LStar::new(oracle.alphabet().clone(), oracle).infer();
+            assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+
+            let words = (
+                AccessExtensions::new(1),
+                RandomWords::new(5_000, 1, 2 * target.size(), seed),
+            );
+            let oracle = BlackBoxOracle::new(MealyOracle::new(&target), words);
+            let mm = LSharp::new(oracle.alphabet().clone(), oracle).infer();
+            assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+
+            // PAC learning only guarantees approximate correctness
+            let pac = Pac::new(0.01, 0.01, 1, 2 * target.size(), seed);
+            let oracle = BlackBoxOracle::new(MealyOracle::new(&target), pac);
+            let mm = LSharp::new(oracle.alphabet().clone(), oracle).infer();
+            assert!(mm.size() <= target.size());
+        }
+    }
+
+    #[test]
+    fn empty_word_is_tested() {
+        // the target only accepts the empty word, on which alone it differs from the hypothesis
+        let target = DFA::builder()
+            .with_state_colors([true, false])
+            .with_edges([(0, 'a', 1), (0, 'b', 1), (1, 'a', 1), (1, 'b', 1)])
+            .into_dfa(0);
+        let hypothesis = DFA::builder()
+            .with_state_colors([false])
+            .with_edges([(0, 'a', 0), (0, 'b', 0)])
+            .into_dfa(0);
+        let oracle = DFAOracle::new(target);
+        let query = |word: &[char]| oracle.output(word);
+
+        let counterexample = AccessExtensions::new(0).find_counterexample(&hypothesis, &query);
+        assert_eq!(counterexample, Some((vec![], true)));
+        let counterexample = RandomWords::new(10, 0, 0, 0).find_counterexample(&hypothesis, &query);
+        assert_eq!(counterexample, Some((vec![], true)));
+    }
+
+    #[test]
+    fn random_walk_is_reproducible() {
+        let mut rng = StdRng::seed_from_u64(38);
+        let target = generate_random_mealy_with_rng(&mut rng, 3, 3, 10);
+        let hypothesis = generate_random_mealy_with_rng(&mut rng, 3, 3, 10);
+        let oracle = MealyOracle::new(&target);
+        let query = |word: &[char]| oracle.output(word);
+        let first = RandomWalk::new(1_000, 0.2, 7).find_counterexample(&hypothesis, &query);
+        let second = RandomWalk::new(1_000, 0.2, 7).find_counterexample(&hypothesis, &query);
+        assert_eq!(first, second);
+    }
+}
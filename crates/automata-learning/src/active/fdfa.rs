@@ -0,0 +1,746 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use automata::automaton::{DFA, DPA};
+use automata::core::alphabet::Alphabet;
+use automata::core::word::{OmegaWord, ReducedOmegaWord};
+use automata::core::{Int, Void, math};
+use automata::families::{FDFA, FWPM};
+use automata::representation::CollectTs;
+use automata::ts::{Deterministic, ForAlphabet, IsEdge, Sproutable, StateIndex};
+use automata::{DTS, Pointed, RightCongruence, TransitionSystem};
+use itertools::Itertools;
+use tracing::{debug, info, trace};
+
+use super::{OmegaHypothesis, OmegaOracle};
+use crate::passive::OmegaSample;
+use crate::passive::precise::{MAX_PRIORITIES, build_precise_dpa_for};
+
+type Symbol<A> = <A as Alphabet>::Symbol;
+type Word<A> = Vec<Symbol<A>>;
+// progress tables store the reached leading class and whether the word is accepted
+type ProgressTable<A> = Table<Symbol<A>, Word<A>, (usize, bool)>;
+
+/// The different kinds of [`FDFA`] that can be learned. All of them share the same leading
+/// congruence, which is the right congruence of the target language, but they differ in the
+/// progress DFAs. For a class with representative `u`, the progress DFA of
+/// - a periodic FDFA accepts all words `v` such that `u v^ω` is in the language,
+/// - a syntactic FDFA accepts all `v` with `u v ~ u` such that `u v^ω` is in the language and
+///   additionally distinguishes words that lead to different classes of the leading congruence,
+/// - a recurrent FDFA accepts the same words as the syntactic FDFA, but only distinguishes words
+///   by whether they are accepted after some extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FdfaVariant {
+    /// The periodic FDFA, whose progress DFAs can be exponentially larger than the others.
+    Periodic,
+    /// The syntactic FDFA, whose progress DFAs refine the leading congruence.
+    Syntactic,
+    /// The recurrent FDFA, which is the smallest of the three variants.
+    Recurrent,
+}
+
+/// An observation table whose rows are indexed by finite words and whose columns are given by
+/// experiments of type `E`. The access words form a prefix-closed set and their rows are pairwise
+/// distinct, such that each of them corresponds to a state of the hypothesis.
+#[derive(Debug, Clone)]
+struct Table<S, E, X> {
+    access: Vec<Vec<S>>,
+    experiments: Vec<E>,
+    rows: math::Map<Vec<S>, Vec<X>>,
+}
+
+impl<S: Copy + Eq + std::hash::Hash, E, X: Clone + Eq + std::hash::Hash> Table<S, E, X> {
+    fn new(experiments: Vec<E>) -> Self {
+        Self {
+            access: vec![vec![]],
+            experiments,
+            rows: math::Map::default(),
+        }
+    }
+
+    fn row(&self, word: &[S]) -> &Vec<X> {
+        self.rows.get(word).expect("row must be filled")
+    }
+
+    /// Fills all rows of access words and their one-symbol extensions with `entry` and promotes
+    /// extensions whose row does not occur among the access words until the table is closed.
+    fn close(&mut self, symbols: &[S], entry: impl Fn(&[S], &E) -> X) {
+        loop {
+            let words = self
+                .access
+                .iter()
+                .flat_map(|u| {
+                    std::iter::once(u.clone()).chain(symbols.iter().map(|a| {
+                        let mut ua = u.clone();
+                        ua.push(*a);
+                        ua
+                    }))
+                })
+                .collect_vec();
+            for word in words {
+                let row = self.rows.entry(word.clone()).or_default();
+                for experiment in &self.experiments[row.len()..] {
+                    row.push(entry(&word, experiment));
+                }
+            }
+
+            let known: math::Set<_> = self.access.iter().map(|u| self.row(u)).collect();
+            let missing = self.access.iter().find_map(|u| {
+                symbols.iter().find_map(|a| {
+                    let mut ua = u.clone();
+                    ua.push(*a);
+                    (!known.contains(self.row(&ua))).then_some(ua)
+                })
+            });
+            match missing {
+                Some(word) => {
+                    trace!("promoting row of length {}", word.len());
+                    self.access.push(word)
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// Computes the successor of every access word on every symbol, given by the index of the
+    /// access word whose row matches. Requires the table to be closed.
+    fn transitions(&self, symbols: &[S]) -> Vec<Vec<usize>> {
+        let index: math::Map<_, _> = self
+            .access
+            .iter()
+            .enumerate()
+            .map(|(i, u)| (self.row(u), i))
+            .collect();
+        self.access
+            .iter()
+            .map(|u| {
+                symbols
+                    .iter()
+                    .map(|a| {
+                        let mut ua = u.clone();
+                        ua.push(*a);
+                        index[self.row(&ua)]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Caches the answers to membership queries posed to an [`OmegaOracle`].
+struct Queries<T: OmegaOracle> {
+    oracle: T,
+    cache: RefCell<math::Map<ReducedOmegaWord<Symbol<T::Alphabet>>, bool>>,
+}
+
+impl<T: OmegaOracle> Queries<T> {
+    /// Returns whether `spoke cycle^ω` is in the target language.
+    fn lasso(&self, spoke: &[Symbol<T::Alphabet>], cycle: &[Symbol<T::Alphabet>]) -> bool {
+        let word = ReducedOmegaWord::ultimately_periodic(spoke, cycle);
+        if let Some(answer) = self.cache.borrow().get(&word) {
+            return *answer;
+        }
+        let answer = self.oracle.membership(&word);
+        self.cache.borrow_mut().insert(word, answer);
+        answer
+    }
+}
+
+/// Computes the entry of the progress table of the leading class `class` with access word
+/// `access` for the finite word `z`. It consists of the class that `access z` leads to (which is
+/// only tracked for syntactic FDFAs) and whether `z` should be accepted.
+fn progress_entry<T: OmegaOracle>(
+    variant: FdfaVariant,
+    queries: &Queries<T>,
+    leading: &RightCongruence<T::Alphabet>,
+    class: usize,
+    access: &[Symbol<T::Alphabet>],
+    z: &[Symbol<T::Alphabet>],
+) -> (usize, bool) {
+    let reached = leading
+        .reached_state_index_from(class as u32, z)
+        .expect("leading congruence must be complete") as usize;
+    let accepted = !z.is_empty()
+        && match variant {
+            FdfaVariant::Periodic => queries.lasso(access, z),
+            FdfaVariant::Syntactic | FdfaVariant::Recurrent => {
+                reached == class && queries.lasso(access, z)
+            }
+        };
+    match variant {
+        FdfaVariant::Syntactic => (reached, accepted),
+        FdfaVariant::Periodic | FdfaVariant::Recurrent => (0, accepted),
+    }
+}
+
+/// An implementation of the FDFA learning algorithm of Angluin and Fisman, which learns an
+/// omega-regular language in the form of an [`FDFA`] of the chosen [`FdfaVariant`]. It maintains
+/// an observation table for the leading congruence, whose experiments are pairs `(x, y)` that
+/// are appended to a word `u` as `u x y^ω`, and one observation table for each class of the
+/// leading congruence that yields the corresponding progress DFA.
+///
+/// Counterexamples are ultimately periodic words, which are decomposed such that the hypothesis
+/// answers wrongly on the decomposition. The counterexample is then either attributed to the
+/// leading congruence or to one of the progress DFAs, and a new experiment is found with a
+/// binary search.
+pub struct FdfaLearner<A: Alphabet, T: OmegaOracle<Alphabet = A>> {
+    alphabet: A,
+    variant: FdfaVariant,
+    queries: Queries<T>,
+    leading: Table<Symbol<A>, (Word<A>, Word<A>), bool>,
+    progress: Vec<ProgressTable<A>>,
+    // whether the leading congruence changed since the progress tables were filled
+    leading_changed: bool,
+    equivalence_queries: usize,
+}
+
+impl<A: Alphabet, T: OmegaOracle<Alphabet = A>> FdfaLearner<A, T> {
+    /// Creates a new learner for an FDFA of the given variant, which poses queries to `oracle`.
+    pub fn new(alphabet: A, oracle: T, variant: FdfaVariant) -> Self {
+        Self {
+            alphabet,
+            variant,
+            queries: Queries {
+                oracle,
+                cache: RefCell::new(math::Map::default()),
+            },
+            leading: Table::new(vec![]),
+            progress: vec![],
+            leading_changed: false,
+            equivalence_queries: 0,
+        }
+    }
+
+    /// Returns the number of distinct membership queries that have been posed so far.
+    pub fn membership_queries(&self) -> usize {
+        self.queries.cache.borrow().len()
+    }
+
+    /// Returns the number of equivalence queries that have been posed so far.
+    pub fn equivalence_queries(&self) -> usize {
+        self.equivalence_queries
+    }
+
+    /// Returns a reference to the oracle.
+    pub(super) fn oracle(&self) -> &T {
+        &self.queries.oracle
+    }
+
+    /// Runs the learner until the oracle accepts the hypothesis and returns it.
+    pub fn infer(&mut self) -> FDFA<A> {
+        loop {
+            let hypothesis = self.hypothesis();
+            self.equivalence_queries += 1;
+            match self.queries.oracle.equivalence(&hypothesis) {
+                Ok(()) => {
+                    info!(
+                        "learned FDFA with {} leading classes after {} equivalence queries",
+                        hypothesis.leading().size(),
+                        self.equivalence_queries
+                    );
+                    return hypothesis;
+                }
+                Err(counterexample) => {
+                    debug!("processing counterexample {counterexample:?}");
+                    self.process_counterexample(&hypothesis, &counterexample);
+                }
+            }
+        }
+    }
+
+    /// Runs the learner and converts the learned FDFA into a [`DPA`] with [`fdfa_to_dpa`]. Returns
+    /// `None` if the learned FDFA needs more priorities than this conversion supports.
+    pub fn infer_dpa(&mut self) -> Option<DPA<A>> {
+        fdfa_to_dpa(&self.infer())
+    }
+
+    /// Returns the sample of all membership queries that have been answered so far.
+    pub(super) fn sample(&self) -> OmegaSample<A> {
+        OmegaSample::new_omega(
+            self.alphabet.clone(),
+            self.queries
+                .cache
+                .borrow()
+                .iter()
+                .map(|(w, b)| (w.clone(), *b)),
+        )
+    }
+
+    /// Returns whether `hypothesis` classifies `word` wrongly on some decomposition. The answer
+    /// for `word` is added to the answered membership queries.
+    pub(super) fn refutes(&self, hypothesis: &FDFA<A>, word: &ReducedOmegaWord<Symbol<A>>) -> bool {
+        let expected = self.queries.lasso(&word.spoke_vec(), &word.cycle_vec());
+        refuting_decomposition(hypothesis, word, expected).is_some()
+    }
+
+    /// Closes all tables and builds the hypothesis from them.
+    pub(super) fn hypothesis(&mut self) -> FDFA<A> {
+        let symbols = self.alphabet.universe().collect_vec();
+        let queries = &self.queries;
+        self.leading.close(&symbols, |u, (x, y)| {
+            let prefix = u.iter().chain(x).copied().collect_vec();
+            queries.lasso(&prefix, y)
+        });
+
+        let leading_transitions = self.leading.transitions(&symbols);
+        let mut ts = DTS::for_alphabet_size_hint(self.alphabet.clone(), leading_transitions.len());
+        for _ in &leading_transitions {
+            ts.add_state(Void);
+        }
+        for (q, successors) in leading_transitions.iter().enumerate() {
+            for (a, p) in symbols.iter().zip(successors) {
+                ts.add_edge((q as u32, self.alphabet.make_expression(*a), Void, *p as u32));
+            }
+        }
+        let leading = RightCongruence::from_parts(ts, 0);
+
+        // entries of syntactic and recurrent progress tables depend on the leading congruence
+        if self.leading_changed && self.variant != FdfaVariant::Periodic {
+            self.progress.clear();
+        }
+        self.leading_changed = false;
+        while self.progress.len() < leading.size() {
+            self.progress.push(Table::new(vec![vec![]]));
+        }
+
+        let mut progress = Vec::with_capacity(leading.size());
+        for (class, table) in self.progress.iter_mut().enumerate() {
+            let access = &self.leading.access[class];
+            table.close(&symbols, |v, e| {
+                let z = v.iter().chain(e).copied().collect_vec();
+                progress_entry(self.variant, queries, &leading, class, access, &z)
+            });
+
+            let transitions = table.transitions(&symbols);
+            let mut ts = DTS::for_alphabet_size_hint(self.alphabet.clone(), transitions.len());
+            for v in &table.access {
+                ts.add_state(table.row(v)[0].1);
+            }
+            for (q, successors) in transitions.iter().enumerate() {
+                for (a, p) in symbols.iter().zip(successors) {
+                    ts.add_edge((q as u32, self.alphabet.make_expression(*a), Void, *p as u32));
+                }
+            }
+            progress.push((class as u32, DFA::from_parts(ts, 0)));
+        }
+        debug!(
+            "built hypothesis with {} leading classes and progress DFAs of sizes {}",
+            leading.size(),
+            progress.iter().map(|(_, dfa)| dfa.size()).join(", ")
+        );
+        FDFA::from_iter(leading, progress)
+    }
+
+    /// Decomposes the counterexample such that `hypothesis` is wrong on the decomposition and adds
+    /// an experiment either to the leading table or to one of the progress tables.
+    pub(super) fn process_counterexample(
+        &mut self,
+        hypothesis: &FDFA<A>,
+        counterexample: &ReducedOmegaWord<Symbol<A>>,
+    ) {
+        let expected = self
+            .queries
+            .lasso(&counterexample.spoke_vec(), &counterexample.cycle_vec());
+        let (u, v) = refuting_decomposition(hypothesis, counterexample, expected)
+            .expect("counterexample must refute the hypothesis");
+        let leading = hypothesis.leading();
+        let class = |word: &[Symbol<A>]| {
+            leading
+                .reached_state_index(word)
+                .expect("leading congruence must be complete") as usize
+        };
+
+        let q = class(&u);
+        if self.queries.lasso(&self.leading.access[q], &v) != expected {
+            // replacing prefixes of u by their access words eventually changes the outcome
+            let outcome = |k: usize| {
+                let prefix = self.leading.access[class(&u[..k])]
+                    .iter()
+                    .chain(&u[k..])
+                    .copied()
+                    .collect_vec();
+                self.queries.lasso(&prefix, &v)
+            };
+            let (mut lo, mut hi) = (0, u.len());
+            while hi - lo > 1 {
+                let mid = (lo + hi) / 2;
+                if outcome(mid) == expected {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            trace!("adding leading experiment from position {hi}");
+            self.leading.experiments.push((u[hi..].to_vec(), v));
+            self.leading_changed = true;
+        } else {
+            // the progress DFA for q is wrong on v, so we proceed analogously
+            let table = &self.progress[q];
+            let progress = &hypothesis[q as u32];
+            let outcome = |k: usize| {
+                let reached = progress
+                    .reached_state_index(&v[..k])
+                    .expect("progress DFA must be complete");
+                let z = table.access[reached as usize]
+                    .iter()
+                    .chain(&v[k..])
+                    .copied()
+                    .collect_vec();
+                progress_entry(
+                    self.variant,
+                    &self.queries,
+                    leading,
+                    q,
+                    &self.leading.access[q],
+                    &z,
+                )
+            };
+            let target = outcome(0);
+            let (mut lo, mut hi) = (0, v.len());
+            while hi - lo > 1 {
+                let mid = (lo + hi) / 2;
+                if outcome(mid) == target {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            trace!("adding progress experiment from position {hi} for class {q}");
+            self.progress[q].experiments.push(v[hi..].to_vec());
+        }
+    }
+}
+
+/// Searches for a decomposition of `word` into `u v^ω` with `u v ~ u` in the leading congruence
+/// of `fdfa`, on which the progress DFA of the class of `u` does not accept `v` if and only if
+/// `expected` holds. Such a decomposition exists whenever `fdfa` is not saturated on `word` or
+/// answers wrongly on it.
+fn refuting_decomposition<A: Alphabet>(
+    fdfa: &FDFA<A>,
+    word: &ReducedOmegaWord<Symbol<A>>,
+    expected: bool,
+) -> Option<(Word<A>, Word<A>)> {
+    let leading = fdfa.leading();
+    let mut u = word.spoke_vec();
+    let cycle = word.cycle_vec();
+    let mut class = leading
+        .reached_state_index(&u)
+        .expect("leading congruence must be complete");
+    let mut seen = math::Set::default();
+    for r in (0..cycle.len()).cycle() {
+        if !seen.insert((class, r)) {
+            break;
+        }
+        let rotation = cycle[r..].iter().chain(&cycle[..r]).copied().collect_vec();
+        if let Some(v) = refuting_power(fdfa, class, &rotation, expected) {
+            return Some((u, v));
+        }
+        class = leading
+            .successor_index(class, cycle[r])
+            .expect("leading congruence must be complete");
+        u.push(cycle[r]);
+    }
+    None
+}
+
+/// Checks all powers `v` of `rotation` with `u v ~ u` for a class `u` of the leading congruence
+/// and returns the first one on which the progress DFA does not answer `expected`.
+fn refuting_power<A: Alphabet>(
+    fdfa: &FDFA<A>,
+    class: StateIndex<RightCongruence<A>>,
+    rotation: &[Symbol<A>],
+    expected: bool,
+) -> Option<Word<A>> {
+    let leading = fdfa.leading();
+    let mut reached = class;
+    let period = (1..=leading.size()).find(|_| {
+        reached = leading
+            .reached_state_index_from(reached, rotation)
+            .expect("leading congruence must be complete");
+        reached == class
+    })?;
+
+    let block = rotation.repeat(period);
+    let progress = &fdfa[class];
+    let mut state = progress.initial();
+    let mut seen = math::Set::default();
+    let mut v = vec![];
+    loop {
+        state = progress
+            .reached_state_index_from(state, &block)
+            .expect("progress DFA must be complete");
+        v.extend(&block);
+        if progress.state_color(state) != Some(expected) {
+            return Some(v);
+        }
+        if !seen.insert(state) {
+            return None;
+        }
+    }
+}
+
+/// Returns whether the given DFA can be colored by [`FWPM::from`], which requires that all states
+/// of an SCC that loop on their minimal representative agree on whether they are accepting.
+fn is_consistently_colorable<A: Alphabet>(dfa: &DFA<A>) -> bool {
+    let sccs = dfa.sccs();
+    let mut classification = math::Map::default();
+    dfa.minimal_representatives_iter().all(|mr| {
+        let (word, q) = mr.decompose();
+        if !dfa.is_loop_on(q, &word) {
+            return true;
+        }
+        let accepting = dfa.state_color(q).expect("state must exist");
+        let scc = sccs.scc_index_of(q).expect("state must be in an SCC");
+        *classification.entry(scc).or_insert(accepting) == accepting
+    })
+}
+
+/// Turns the progress DFAs of a saturated FDFA into weak priority mappings, which can then be
+/// used for the precise DPA construction.
+fn weak_priority_mapping<A: Alphabet>(fdfa: &FDFA<A>) -> crate::passive::fwpm::FWPM<A> {
+    let leading = fdfa.leading().clone();
+    let fwpm = FWPM::from(fdfa.clone());
+    let mut mapping = crate::passive::fwpm::FWPM::empty(leading.clone());
+    for class in leading.state_indices() {
+        let mm = &fwpm[class];
+        // colors are constant on SCCs and weak, so every state inherits the color of its
+        // incoming edges and the initial state is at least as important as all others
+        let mut colors: math::Map<_, Int> = mm
+            .state_indices()
+            .flat_map(|q| mm.edges_from(q).unwrap().map(|e| (e.target(), e.color())))
+            .collect();
+        let max = colors.values().copied().max().unwrap_or(0);
+        colors.entry(mm.initial()).or_insert(max + max % 2);
+        let moore = mm
+            .with_state_color(|q| colors[&q])
+            .erase_edge_colors()
+            .collect_moore();
+        mapping.insert_pm(class, moore);
+    }
+    mapping
+}
+
+/// Converts a saturated FDFA into an equivalent [`DPA`]. The progress DFAs are first turned into
+/// weak priority mappings, from which the precise DPA construction builds a DPA that is then
+/// minimized. The FDFAs produced by [`FdfaLearner`] are saturated.
+///
+/// Returns `None` if the progress DFAs cannot be turned into weak priority mappings or if these
+/// need more priorities than the precise DPA construction supports. The resulting DPA only
+/// agrees with the FDFA if it is saturated.
+pub fn fdfa_to_dpa<A: Alphabet>(fdfa: &FDFA<A>) -> Option<DPA<A>> {
+    let colorable = fdfa
+        .leading()
+        .state_indices()
+        .all(|class| is_consistently_colorable(&fdfa[class]));
+    if !colorable {
+        return None;
+    }
+    let mapping = weak_priority_mapping(fdfa);
+    (1..=MAX_PRIORITIES)
+        .contains(&mapping.complexity())
+        .then(|| build_precise_dpa_for(mapping))
+}
+
+/// Converts the FDFA into a [`DPA`] that agrees with it on every decomposition of every
+/// ultimately periodic word, which is possible if and only if the FDFA is saturated. Otherwise,
+/// `None` is returned.
+pub(super) fn saturated_dpa<A: Alphabet>(fdfa: &FDFA<A>) -> Option<DPA<A>> {
+    fdfa_to_dpa(fdfa).filter(|dpa| search_refutation(fdfa, dpa).is_none())
+}
+
+/// Runs a breadth-first search over pairs of classes of the leading congruence of `fdfa` and
+/// states of `target`, looking for a word `x` and a finite word `v` with `x v ~ x` on which the
+/// progress DFA for `x` disagrees with whether `x v^ω` is accepted by `target`.
+fn search_refutation<A: Alphabet>(
+    fdfa: &FDFA<A>,
+    target: &DPA<A>,
+) -> Option<ReducedOmegaWord<Symbol<A>>> {
+    let symbols = target.alphabet().universe().collect_vec();
+    let leading = fdfa.leading();
+    let start = (leading.initial(), target.initial());
+    let mut seen = math::Set::from_iter([start]);
+    let mut queue = VecDeque::from([(start, vec![])]);
+    while let Some(((class, state), x)) = queue.pop_front() {
+        if let Some(v) = search_period(fdfa, target, &symbols, class, state) {
+            return Some(ReducedOmegaWord::ultimately_periodic(x, v));
+        }
+        for a in &symbols {
+            let successors = (
+                leading
+                    .successor_index(class, *a)
+                    .expect("leading congruence must be complete"),
+                target
+                    .successor_index(state, *a)
+                    .expect("target must be complete"),
+            );
+            if seen.insert(successors) {
+                let mut extended = x.clone();
+                extended.push(*a);
+                queue.push_back((successors, extended));
+            }
+        }
+    }
+    None
+}
+
+/// Searches a non-empty word `v` that loops on `class` in the leading congruence, such that
+/// the progress DFA of `class` accepts `v` if and only if `target` rejects `v^ω` from `origin`.
+/// The effect of `v` on `target` is tracked as a profile, which maps each state to the state
+/// reached on `v` together with the least priority seen on the way.
+fn search_period<A: Alphabet>(
+    fdfa: &FDFA<A>,
+    target: &DPA<A>,
+    symbols: &[Symbol<A>],
+    class: StateIndex<RightCongruence<A>>,
+    origin: StateIndex<DPA<A>>,
+) -> Option<Word<A>> {
+    let leading = fdfa.leading();
+    let progress = &fdfa[class];
+    let states = target.state_indices().collect_vec();
+    let position: math::Map<_, _> = states.iter().enumerate().map(|(i, q)| (*q, i)).collect();
+    let accepts_power = |profile: &[(StateIndex<DPA<A>>, Int)]| {
+        let mut order = vec![position[&origin]];
+        loop {
+            let next = position[&profile[*order.last().unwrap()].0];
+            if let Some(start) = order.iter().position(|i| *i == next) {
+                let least = order[start..].iter().map(|i| profile[*i].1).min().unwrap();
+                return least % 2 == 0;
+            }
+            order.push(next);
+        }
+    };
+
+    let identity = states.iter().map(|q| (*q, Int::MAX)).collect_vec();
+    let start = (class, progress.initial(), identity);
+    let mut seen = math::Set::from_iter([start.clone()]);
+    let mut queue = VecDeque::from([(start, vec![])]);
+    while let Some(((reached, state, profile), v)) = queue.pop_front() {
+        for a in symbols {
+            let profile = profile
+                .iter()
+                .map(|(q, least)| {
+                    let edge = target.edge(*q, *a).expect("target must be complete");
+                    (edge.target(), (*least).min(edge.color()))
+                })
+                .collect_vec();
+            let successor = (
+                leading
+                    .successor_index(reached, *a)
+                    .expect("leading congruence must be complete"),
+                progress
+                    .successor_index(state, *a)
+                    .expect("progress DFA must be complete"),
+                profile,
+            );
+            let mut extended = v.clone();
+            extended.push(*a);
+            if successor.0 == class
+                && progress.state_color(successor.1) != Some(accepts_power(&successor.2))
+            {
+                return Some(extended);
+            }
+            if seen.insert(successor.clone()) {
+                queue.push_back((successor, extended));
+            }
+        }
+    }
+    None
+}
+
+impl<A: Alphabet> OmegaHypothesis for FDFA<A> {
+    type Alphabet = A;
+
+    /// If the FDFA can be converted into a DPA, a witness for the inequivalence of this DPA and
+    /// `target` is returned, provided that the FDFA is wrong on some decomposition of it. Otherwise,
+    /// the search falls back to exploring the combinations of leading classes, progress states
+    /// and target states, which finds a refuting word whenever the FDFA is not correct.
+    fn separate_from(&self, target: &DPA<A>) -> Option<ReducedOmegaWord<Symbol<A>>> {
+        if let Some(dpa) = fdfa_to_dpa(self)
+            && let Some(witness) = target.witness_inequivalence(&dpa)
+            && refuting_decomposition(self, &witness, target.accepts(&witness)).is_some()
+        {
+            return Some(witness);
+        }
+        search_refutation(self, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FdfaLearner, FdfaVariant, MAX_PRIORITIES, fdfa_to_dpa, weak_priority_mapping};
+    use crate::active::{DPAOracle, OmegaOracle};
+    use automata::TransitionSystem;
+    use automata::automaton::DPA;
+    use automata::random::generate_random_dpa;
+    use automata::ts::TSBuilder;
+
+    const VARIANTS: [FdfaVariant; 3] = [
+        FdfaVariant::Periodic,
+        FdfaVariant::Syntactic,
+        FdfaVariant::Recurrent,
+    ];
+
+    fn learn(target: &DPA, variant: FdfaVariant) -> DPA {
+        let oracle = DPAOracle::new(target.clone());
+        let mut learner = FdfaLearner::new(oracle.alphabet().clone(), &oracle, variant);
+        let fdfa = learner.infer();
+        assert!(oracle.equivalence(&fdfa).is_ok());
+        fdfa_to_dpa(&fdfa).unwrap()
+    }
+
+    #[test]
+    fn fdfa_learning() {
+        // infinitely many a and finitely many b, respectively
+        let inf_a = TSBuilder::without_state_colors()
+            .with_edges([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        let fin_b = TSBuilder::without_state_colors()
+            .with_edges([(0, 'a', 2, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        // a parity condition over three priorities that needs a nontrivial leading congruence
+        let parity = TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 0, 1),
+                (0, 'b', 2, 0),
+                (1, 'a', 3, 0),
+                (1, 'b', 1, 1),
+            ])
+            .into_dpa(0);
+        for target in [inf_a, fin_b, parity] {
+            for variant in VARIANTS {
+                let dpa = learn(&target, variant);
+                assert!(target.language_equivalent(&dpa), "{variant:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn fdfa_to_dpa_with_too_many_priorities() {
+        // the least priority that is seen infinitely often is given by the least symbol
+        let target = TSBuilder::without_state_colors()
+            .with_edges(
+                ('a'..='j')
+                    .zip(0..)
+                    .map(|(a, priority)| (0, a, priority, 0)),
+            )
+            .into_dpa(0);
+        let oracle = DPAOracle::new(target.clone());
+        let mut learner =
+            FdfaLearner::new(oracle.alphabet().clone(), &oracle, FdfaVariant::Syntactic);
+        let fdfa = learner.infer();
+        assert!(oracle.equivalence(&fdfa).is_ok());
+        assert!(weak_priority_mapping(&fdfa).complexity() > MAX_PRIORITIES);
+        assert!(fdfa_to_dpa(&fdfa).is_none());
+    }
+
+    #[test]
+    fn fdfa_learning_random() {
+        for _ in 0..10 {
+            let target = generate_random_dpa(2, 4, 3, 0.5);
+            for variant in VARIANTS {
+                let dpa = learn(&target, variant);
+                assert!(target.language_equivalent(&dpa), "{variant:?}");
+            }
+        }
+    }
+}
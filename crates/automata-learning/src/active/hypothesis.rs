@@ -1,7 +1,7 @@
 #![allow(missing_docs)]
 
 use super::{Experiment, Experiments};
-use automata::automaton::{MealyMachine, MooreMachine};
+use automata::automaton::{DFA, MealyMachine, MooreMachine};
 use automata::core::{
     Color, Void,
     alphabet::{Alphabet, Symbol},
@@ -99,6 +99,47 @@ impl<A: Alphabet, Q: Color> Hypothesis for MooreMachine<A, Q, Void> {
     }
 }
 
+impl<A: Alphabet> Hypothesis for DFA<A> {
+    type Output = bool;
+
+    fn output_from<W: FiniteWord<Symbol = <Self::Alphabet as Alphabet>::Symbol>>(
+        &self,
+        input: W,
+        source: Self::StateIndex,
+    ) -> Self::Output {
+        self.reached_state_color_from(source, input)
+            .expect("Hypothesis must be complete")
+    }
+    fn from_transition_system(
+        ts: DTS<Self::Alphabet, Self::StateColor, Self::EdgeColor>,
+        initial: StateIndex,
+    ) -> Self {
+        Self::from_parts(ts, initial)
+    }
+    fn mandatory_experiments(
+        _alphabet: &Self::Alphabet,
+    ) -> impl IntoIterator<Item = Experiment<SymbolOf<Self>>> {
+        [Experiment::empty()]
+    }
+    fn give_state_color(
+        _mr: &[SymbolOf<Self>],
+        _experiments: &Experiments<Self>,
+        row: &[Self::Output],
+    ) -> Self::StateColor {
+        row[0]
+    }
+    fn give_transition_color(
+        _source_mr: &[SymbolOf<Self>],
+        _a: SymbolOf<Self>,
+        _target_mr: &[SymbolOf<Self>],
+        _experiments: &Experiments<Self>,
+        _source_row: &[Self::Output],
+        _target_row: &[Self::Output],
+    ) -> Self::EdgeColor {
+        Void
+    }
+}
+
 impl<A: Alphabet, C: Color> Hypothesis for MealyMachine<A, Void, C> {
     type Output = C;
 
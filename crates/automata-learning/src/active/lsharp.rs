@@ -0,0 +1,593 @@
+use std::collections::VecDeque;
+
+use automata::automaton::MealyMachine;
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use automata::core::{Color, Void, math};
+use automata::ts::{Deterministic, ForAlphabet, IsEdge, Sproutable};
+use automata::{DTS, Pointed};
+use itertools::Itertools;
+use tracing::{debug, info, trace};
+
+use super::oracle::Oracle;
+
+/// A node of the observation tree. Each node corresponds to the word that leads to it from the
+/// root and stores the outgoing transitions together with the output that was observed.
+#[derive(Debug, Clone)]
+struct TreeNode<S, C> {
+    parent: Option<(usize, S)>,
+    successors: math::Map<S, (C, usize)>,
+}
+
+/// An implementation of the L# algorithm of Vaandrager, Garhewal, Rot and Wißmann for learning
+/// Mealy machines. Instead of an observation table, L# maintains an observation tree that
+/// contains all queries that have been posed so far. The nodes of the tree are partitioned into
+/// a basis, whose nodes are pairwise apart and become the states of the hypothesis, and a frontier
+/// consisting of the immediate successors of basis nodes. Two nodes are apart if there is a word
+/// on which both have been observed to produce different outputs.
+///
+/// If adaptive distinguishing sequences are enabled via [`Self::with_adaptive_distinguishing_sequences`],
+/// queries for new frontier nodes and for separating the candidates of a frontier node are extended
+/// symbol by symbol, each time choosing an input that splits the remaining candidates according to
+/// their output. This usually identifies frontier nodes with far fewer queries.
+pub struct LSharp<A: Alphabet, T, C: Color> {
+    alphabet: A,
+    oracle: T,
+    tree: Vec<TreeNode<A::Symbol, C>>,
+    basis: Vec<usize>,
+    ads: bool,
+    linear: bool,
+    membership_queries: usize,
+    equivalence_queries: usize,
+}
+
+impl<A, T, C> LSharp<A, T, C>
+where
+    A: Alphabet,
+    C: Color,
+    T: Oracle<Alphabet = A, Output = C>,
+{
+    /// Creates a new learner for the given alphabet, which poses its queries to `oracle`.
+    pub fn new(alphabet: A, oracle: T) -> Self {
+        Self {
+            alphabet,
+            oracle,
+            tree: vec![TreeNode {
+                parent: None,
+                successors: math::Map::default(),
+            }],
+            basis: vec![0],
+            ads: false,
+            linear: false,
+            membership_queries: 0,
+            equivalence_queries: 0,
+        }
+    }
+
+    /// Enables the use of adaptive distinguishing sequences for identifying frontier nodes.
+    pub fn with_adaptive_distinguishing_sequences(mut self) -> Self {
+        self.ads = true;
+        self
+    }
+
+    /// Decomposes counterexamples by a linear scan from the front instead of the binary search of
+    /// Rivest and Schapire. This usually needs more membership queries, but it finds the earliest
+    /// position at which the hypothesis goes wrong.
+    pub fn with_linear_counterexample_search(mut self) -> Self {
+        self.linear = true;
+        self
+    }
+
+    /// Returns the number of membership queries that have been posed so far. Each query is a
+    /// word whose outputs are observed in a single run, and as every query is stored in the
+    /// observation tree, no query is posed twice.
+    pub fn membership_queries(&self) -> usize {
+        self.membership_queries
+    }
+
+    /// Returns the number of equivalence queries that have been posed so far.
+    pub fn equivalence_queries(&self) -> usize {
+        self.equivalence_queries
+    }
+
+    /// Returns the node of the observation tree that is reached by `word`, if it exists.
+    fn node(&self, word: &[A::Symbol]) -> Option<usize> {
+        word.iter().try_fold(0, |node, a| {
+            self.tree[node].successors.get(a).map(|(_, p)| *p)
+        })
+    }
+
+    fn successor(&self, node: usize, a: A::Symbol) -> Option<(&C, usize)> {
+        self.tree[node].successors.get(&a).map(|(c, p)| (c, *p))
+    }
+
+    /// Returns the word that leads from the root to `node`.
+    fn access(&self, mut node: usize) -> Vec<A::Symbol> {
+        let mut word = vec![];
+        while let Some((parent, a)) = self.tree[node].parent {
+            word.push(a);
+            node = parent;
+        }
+        word.reverse();
+        word
+    }
+
+    /// Inserts `word` into the observation tree. If some prefix is not yet present, the outputs
+    /// on all prefixes are obtained with a single query for `word`. Returns the node that is
+    /// reached by `word`.
+    fn query(&mut self, word: &[A::Symbol]) -> usize {
+        if let Some(node) = self.node(word) {
+            return node;
+        }
+        let outputs = self.oracle.output_sequence(word);
+        self.membership_queries += 1;
+        let mut node = 0;
+        for (a, output) in word.iter().zip(outputs) {
+            if let Some((_, p)) = self.successor(node, *a) {
+                node = p;
+                continue;
+            }
+            let p = self.tree.len();
+            self.tree.push(TreeNode {
+                parent: Some((node, *a)),
+                successors: math::Map::default(),
+            });
+            self.tree[node].successors.insert(*a, (output, p));
+            node = p;
+        }
+        node
+    }
+
+    /// Returns a word on which the nodes `p` and `q` have been observed to produce different
+    /// outputs, or `None` if they are not apart.
+    fn witness(&self, p: usize, q: usize) -> Option<Vec<A::Symbol>> {
+        let mut queue = VecDeque::from([(p, q, vec![])]);
+        while let Some((p, q, word)) = queue.pop_front() {
+            for (a, (c, p_successor)) in &self.tree[p].successors {
+                let Some((d, q_successor)) = self.successor(q, *a) else {
+                    continue;
+                };
+                let mut extended = word.clone();
+                extended.push(*a);
+                if c != d {
+                    return Some(extended);
+                }
+                queue.push_back((*p_successor, q_successor, extended));
+            }
+        }
+        None
+    }
+
+    fn apart(&self, p: usize, q: usize) -> bool {
+        self.witness(p, q).is_some()
+    }
+
+    /// Returns the frontier, i.e. all successors of basis nodes that are not in the basis.
+    fn frontier(&self) -> Vec<usize> {
+        self.basis
+            .iter()
+            .flat_map(|q| self.tree[*q].successors.values().map(|(_, p)| *p))
+            .filter(|p| !self.basis.contains(p))
+            .collect()
+    }
+
+    /// Returns the basis nodes that are not apart from `node`.
+    fn candidates(&self, node: usize) -> Vec<usize> {
+        self.basis
+            .iter()
+            .copied()
+            .filter(|q| !self.apart(node, *q))
+            .collect()
+    }
+
+    /// Extends the word leading to `node` with an adaptive distinguishing sequence for the given
+    /// candidates. In each step, an input is chosen that splits the current candidates into the
+    /// largest number of classes according to their output. The input is then queried and only
+    /// the candidates that produced the same output are kept. Returns the number of queried inputs.
+    fn adaptive_distinguishing_sequence(&mut self, node: usize, candidates: Vec<usize>) -> usize {
+        let mut word = self.access(node);
+        let mut current = candidates;
+        let mut steps = 0;
+        while current.len() > 1 {
+            let best = self
+                .alphabet
+                .universe()
+                .map(|a| {
+                    let classes = current
+                        .iter()
+                        .filter_map(|q| self.successor(*q, a).map(|(c, _)| c))
+                        .unique()
+                        .count();
+                    (a, classes)
+                })
+                .filter(|(_, classes)| *classes > 1)
+                .max_by_key(|(_, classes)| *classes);
+            let Some((a, _)) = best else {
+                break;
+            };
+
+            word.push(a);
+            let reached = self.query(&word);
+            let (parent, _) = self.tree[reached].parent.unwrap();
+            let output = self.successor(parent, a).unwrap().0.clone();
+            current = current
+                .into_iter()
+                .filter_map(|q| match self.successor(q, a) {
+                    Some((c, p)) if *c == output => Some(p),
+                    _ => None,
+                })
+                .collect();
+            steps += 1;
+        }
+        trace!("adaptive distinguishing sequence of length {steps}");
+        steps
+    }
+
+    /// Builds the hypothesis, assuming that every basis node has all successors in the tree and
+    /// that every frontier node has exactly one candidate.
+    fn hypothesis(&self) -> MealyMachine<A, Void, C> {
+        let mut ts: DTS<A, Void, C> =
+            DTS::for_alphabet_size_hint(self.alphabet.clone(), self.basis.len());
+        for _ in &self.basis {
+            ts.add_state(Void);
+        }
+        for (q, node) in self.basis.iter().enumerate() {
+            for a in self.alphabet.universe() {
+                let (color, successor) = self.successor(*node, a).unwrap();
+                let target = match self.basis.iter().position(|p| *p == successor) {
+                    Some(p) => p,
+                    None => {
+                        let candidate = self.candidates(successor)[0];
+                        self.basis.iter().position(|p| *p == candidate).unwrap()
+                    }
+                };
+                ts.add_edge((
+                    q as u32,
+                    self.alphabet.make_expression(a),
+                    color.clone(),
+                    target as u32,
+                ));
+            }
+        }
+        MealyMachine::from_parts(ts, 0)
+    }
+
+    /// Returns the state of the hypothesis that is reached by `word`.
+    fn hypothesis_state(&self, hypothesis: &MealyMachine<A, Void, C>, word: &[A::Symbol]) -> usize {
+        hypothesis
+            .reached_state_index(word)
+            .expect("hypothesis must be complete") as usize
+    }
+
+    /// Walks through the observation tree and the hypothesis in parallel and returns a word on
+    /// which the hypothesis disagrees with an observation, if one exists.
+    fn inconsistency(&self, hypothesis: &MealyMachine<A, Void, C>) -> Option<Vec<A::Symbol>> {
+        let mut queue = VecDeque::from([(0usize, hypothesis.initial())]);
+        while let Some((node, state)) = queue.pop_front() {
+            for (a, (c, p)) in &self.tree[node].successors {
+                let t = hypothesis
+                    .edge(state, *a)
+                    .expect("hypothesis must be complete");
+                if t.color().clone() != *c {
+                    return Some(self.access(*p));
+                }
+                queue.push_back((*p, t.target()));
+            }
+        }
+        None
+    }
+
+    /// Processes a counterexample, which must already be contained in the observation tree. A
+    /// binary search in the style of Rivest and Schapire determines a position `i`, at which the
+    /// hypothesis wrongly identifies a frontier node with a basis node: the successor on the
+    /// `i`-th symbol of the basis node for the first `i` symbols and the basis node for the first
+    /// `i + 1` symbols produce different outputs on the remaining suffix. Returns this frontier
+    /// node, which is afterwards apart from the basis node it was previously identified with.
+    fn process_counterexample(
+        &mut self,
+        word: &[A::Symbol],
+        hypothesis: &MealyMachine<A, Void, C>,
+    ) -> usize {
+        trace!("processing counterexample {}", word.as_string());
+        let node = self.node(word).expect("counterexample must be in tree");
+        let (parent, a) = self.tree[node]
+            .parent
+            .expect("counterexample must not be empty");
+        let observed = self.successor(parent, a).unwrap().0.clone();
+        let last = word.len() - 1;
+        let hypothesis_output = hypothesis
+            .edge(self.hypothesis_state(hypothesis, &word[..last]) as u32, a)
+            .unwrap()
+            .color()
+            .clone();
+        assert!(observed != hypothesis_output);
+
+        // alpha(i) is the output on the access word of the hypothesis state that is reached by the
+        // first i symbols, followed by the remaining symbols. alpha(0) is the observed output and
+        // alpha(last) coincides with the output of the hypothesis.
+        let linear = self.linear;
+        let mut alpha = |i: usize| {
+            let state = self.hypothesis_state(hypothesis, &word[..i]);
+            let mut query = self.access(self.basis[state]);
+            query.extend_from_slice(&word[i..]);
+            let node = self.query(&query);
+            let (parent, a) = self.tree[node].parent.unwrap();
+            self.successor(parent, a).unwrap().0.clone()
+        };
+        let (low, high) = if linear {
+            let high = (1..=last)
+                .find(|i| *i == last || alpha(*i) == hypothesis_output)
+                .unwrap();
+            (high - 1, high)
+        } else {
+            let (mut low, mut high) = (0, last);
+            while high - low > 1 {
+                let mid = (low + high) / 2;
+                if alpha(mid) != hypothesis_output {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            (low, high)
+        };
+
+        // both alpha(low) and alpha(high) are now in the tree and differ, so the successor of the
+        // basis node for position low is apart from the basis node of position high
+        let state = self.hypothesis_state(hypothesis, &word[..low]);
+        let (_, frontier) = self
+            .successor(self.basis[state], word[low])
+            .expect("basis nodes have all successors");
+        let identified = self.basis[self.hypothesis_state(hypothesis, &word[..high])];
+        debug_assert!(self.apart(frontier, identified));
+        trace!(
+            "counterexample decomposed at position {low}, {} is apart from {}",
+            self.access(frontier).as_string(),
+            self.access(identified).as_string()
+        );
+        frontier
+    }
+
+    /// Identifies a frontier node that was obtained from a counterexample. If it is apart from
+    /// all basis nodes, it is promoted right away, otherwise its remaining candidates are
+    /// separated with an adaptive distinguishing sequence if those are enabled.
+    fn refine(&mut self, node: usize) {
+        let candidates = self.candidates(node);
+        if candidates.is_empty() {
+            trace!("promoting {} to the basis", self.access(node).as_string());
+            self.basis.push(node);
+        } else if self.ads && candidates.len() > 1 {
+            self.adaptive_distinguishing_sequence(node, candidates);
+        }
+    }
+
+    /// Runs the learner until the oracle accepts the hypothesis, which is then returned.
+    pub fn infer(&mut self) -> MealyMachine<A, Void, C> {
+        let start = std::time::Instant::now();
+        loop {
+            // promotion: a frontier node that is apart from all basis nodes becomes a basis node
+            if let Some(node) = self
+                .frontier()
+                .into_iter()
+                .find(|f| self.candidates(*f).is_empty())
+            {
+                trace!("promoting {} to the basis", self.access(node).as_string());
+                self.basis.push(node);
+                continue;
+            }
+
+            // extension: every basis node needs a successor for each symbol
+            let missing = self
+                .basis
+                .iter()
+                .cartesian_product(self.alphabet.universe().collect_vec())
+                .find(|(q, a)| self.successor(**q, *a).is_none())
+                .map(|(q, a)| (*q, a));
+            if let Some((q, a)) = missing {
+                let mut word = self.access(q);
+                word.push(a);
+                let node = self.query(&word);
+                if self.ads {
+                    self.adaptive_distinguishing_sequence(node, self.basis.clone());
+                }
+                continue;
+            }
+
+            // separation: a frontier node that is compatible with multiple basis nodes is
+            // extended by a witness for the apartness of two of them
+            let ambiguous = self.frontier().into_iter().find_map(|f| {
+                let candidates = self.candidates(f);
+                (candidates.len() > 1).then_some((f, candidates))
+            });
+            if let Some((node, candidates)) = ambiguous {
+                if self.ads && self.adaptive_distinguishing_sequence(node, candidates.clone()) > 0 {
+                    continue;
+                }
+                let witness = self
+                    .witness(candidates[0], candidates[1])
+                    .expect("basis nodes must be apart");
+                let mut word = self.access(node);
+                word.extend(witness);
+                self.query(&word);
+                continue;
+            }
+
+            // equivalence: the hypothesis is first checked against the tree and only then
+            // given to the oracle
+            let hypothesis = self.hypothesis();
+            if let Some(word) = self.inconsistency(&hypothesis) {
+                let node = self.process_counterexample(&word, &hypothesis);
+                self.refine(node);
+                continue;
+            }
+            self.equivalence_queries += 1;
+            debug!("posing equivalence query with {} states", self.basis.len());
+            let Err((witness, _)) = self.oracle.equivalence(&hypothesis) else {
+                info!("Execution of L# took {}ms", start.elapsed().as_millis());
+                return hypothesis;
+            };
+            self.query(&witness);
+            let node = self.process_counterexample(&witness, &hypothesis);
+            self.refine(node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LSharp;
+    use crate::active::{
+        CompletingMealyOracle, Counterexample, Hypothesis, LStar, MealyOracle, Oracle, QueryCache,
+    };
+    use automata::TransitionSystem;
+    use automata::automaton::MealyMachine;
+    use automata::core::Int;
+    use automata::core::alphabet::CharAlphabet;
+    use automata::core::word::FiniteWord;
+    use automata::random::generate_random_mealy_with_rng;
+    use automata::ts::TSBuilder;
+    use itertools::Itertools;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use tracing::info;
+
+    #[test]
+    fn lsharp_mealy() {
+        let target = TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 0, 0),
+                (0, 'b', 1, 1),
+                (0, 'c', 2, 2),
+                (1, 'a', 0, 2),
+                (1, 'b', 1, 1),
+                (1, 'c', 2, 2),
+                (2, 'a', 2, 2),
+                (2, 'b', 0, 0),
+                (2, 'c', 1, 2),
+            ])
+            .into_mealy(0);
+
+        let oracle = CompletingMealyOracle::new(target.clone(), 0);
+        let mm = LSharp::new(target.alphabet().clone(), oracle).infer();
+        assert_eq!(mm.size(), 3);
+        let oracle = MealyOracle::new(&target);
+        let mm = LSharp::new(target.alphabet().clone(), oracle)
+            .with_adaptive_distinguishing_sequences()
+            .infer();
+        assert_eq!(mm.size(), 3);
+    }
+
+    /// Prepends a long sequence of `b`s to every counterexample, which keeps the hypothesis in
+    /// its initial state for a long time before it goes wrong.
+    struct PaddingOracle(MealyOracle<CharAlphabet>);
+
+    impl Oracle for PaddingOracle {
+        type Alphabet = CharAlphabet;
+        type Output = Int;
+
+        fn alphabet(&self) -> &Self::Alphabet {
+            self.0.alphabet()
+        }
+
+        fn output<W: FiniteWord<Symbol = char>>(&self, word: W) -> Self::Output {
+            self.0.output(word)
+        }
+
+        fn equivalence<H>(&self, hypothesis: &H) -> Result<(), Counterexample<CharAlphabet, Int>>
+        where
+            H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+        {
+            let Err((witness, expected)) = self.0.equivalence(hypothesis) else {
+                return Ok(());
+            };
+            let padded = std::iter::repeat_n('b', 64)
+                .chain(witness.clone())
+                .collect_vec();
+            let output = self.0.output(&padded);
+            if hypothesis.output(&padded) != output {
+                return Err((padded, output));
+            }
+            Err((witness, expected))
+        }
+    }
+
+    #[test]
+    fn lsharp_binary_counterexample_search() {
+        // outputs 1 when reading `b` after a number of `a`s that is three modulo four
+        let target: MealyMachine = TSBuilder::without_state_colors()
+            .with_transitions((0..4).flat_map(|i| {
+                [
+                    (i, 'a', 0, (i + 1) % 4),
+                    (i, 'b', if i == 3 { 1 } else { 0 }, 0),
+                ]
+            }))
+            .into_mealy(0);
+
+        let mut queries = vec![];
+        for linear in [true, false] {
+            let oracle = PaddingOracle(MealyOracle::new(&target));
+            let mut learner = LSharp::new(oracle.alphabet().clone(), oracle);
+            if linear {
+                learner = learner.with_linear_counterexample_search();
+            }
+            let mm = learner.infer();
+            assert_eq!(mm.size(), 4);
+            queries.push(learner.membership_queries());
+        }
+        info!(
+            "membership queries: linear {}, binary {}",
+            queries[0], queries[1]
+        );
+        assert!(queries[1] < queries[0]);
+    }
+
+    #[test]
+    fn lsharp_random_mealy() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let (mut lstar, mut lsharp, mut ads) = (0, 0, 0);
+        for _ in 0..50 {
+            let (symbols, max_color, size) = (
+                rng.gen_range(1..4),
+                rng.gen_range(1..6),
+                rng.gen_range(1..20),
+            );
+            let target = generate_random_mealy_with_rng(&mut rng, symbols, max_color, size);
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = LStar::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = learner.infer();
+            lstar += learner.membership_queries();
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = LSharp::new(oracle.alphabet().clone(), oracle);
+            let mm = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+            lsharp += learner.membership_queries();
+
+            let oracle = MealyOracle::new(&target);
+            let mut learner = LSharp::new(oracle.alphabet().clone(), oracle)
+                .with_adaptive_distinguishing_sequences();
+            let mm = learner.infer();
+            assert_eq!(mm.size(), target.size());
+            assert!(MealyOracle::new(&target).equivalence(&mm).is_ok());
+            ads += learner.membership_queries();
+        }
+        info!("membership queries: LStar {lstar}, L# {lsharp}, L# with ADS {ads}");
+        assert!(lsharp < lstar);
+        assert!(ads < lstar);
+    }
+
+    #[test]
+    fn lsharp_queries_whole_words() {
+        let mut rng = StdRng::seed_from_u64(36);
+        let target = generate_random_mealy_with_rng(&mut rng, 3, 3, 10);
+        // every query of the learner runs a word that is not yet cached in full
+        let cache = QueryCache::prefix_closed(MealyOracle::new(&target));
+        let mut learner = LSharp::new(cache.alphabet().clone(), &cache);
+        let mm = learner.infer();
+        assert_eq!(mm.size(), target.size());
+        assert_eq!(cache.misses(), learner.membership_queries());
+        assert_eq!(cache.hits(), 0);
+    }
+}
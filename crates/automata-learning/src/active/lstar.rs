@@ -10,7 +10,10 @@ use fixedbitset::FixedBitSet;
 use itertools::Itertools;
 use tracing::{debug, info, trace, warn};
 
-use super::{Experiment, Hypothesis, ObservationTable, oracle::Oracle};
+use super::{
+    CounterexampleContext, CounterexampleHandler, Experiment, Hypothesis, MalerPnueli,
+    ObservationTable, oracle::Oracle,
+};
 
 const ITERATION_THRESHOLD: usize = if cfg!(debug_assertions) { 50 } else { 200000 };
 
@@ -33,6 +36,10 @@ pub struct LStar<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet>> {
     // the oracle
     oracle: T,
     observations: ObservationTable<SymbolOf<D>, T::Output>,
+    // the strategy that is used for incorporating counterexamples
+    handler: Box<dyn CounterexampleHandler<SymbolOf<D>, D::Output>>,
+    // the number of equivalence queries that have been posed so far
+    equivalence_queries: usize,
 }
 
 impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar<D, T> {
@@ -48,9 +55,31 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
             base: vec![vec![]],
             table: math::OrderedMap::default(),
             oracle,
+            handler: Box::new(MalerPnueli),
+            equivalence_queries: 0,
         }
     }
 
+    /// Sets the strategy that is used for processing counterexamples, which defaults to
+    /// [`MalerPnueli`].
+    pub fn with_counterexample_handler<H>(mut self, handler: H) -> Self
+    where
+        H: CounterexampleHandler<SymbolOf<D>, D::Output> + 'static,
+    {
+        self.handler = Box::new(handler);
+        self
+    }
+
+    /// Returns the number of distinct membership queries that have been posed so far.
+    pub fn membership_queries(&self) -> usize {
+        self.queries.borrow().len()
+    }
+
+    /// Returns the number of equivalence queries that have been posed so far.
+    pub fn equivalence_queries(&self) -> usize {
+        self.equivalence_queries
+    }
+
     fn output(&self, w: &Word<D>) -> D::Output {
         if !self.queries.borrow().contains_key(w) {
             let c = self.oracle.output(w);
@@ -59,6 +88,26 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
         self.queries.borrow().get(w).unwrap().clone()
     }
 
+    /// Poses all queries for the given words that have not been posed before as one batch.
+    fn output_batch(&self, words: &[Word<D>]) -> Vec<D::Output> {
+        let missing = words
+            .iter()
+            .filter(|w| !self.queries.borrow().contains_key(*w))
+            .unique()
+            .cloned()
+            .collect_vec();
+        if !missing.is_empty() {
+            trace!("posing batch of {} membership queries", missing.len());
+            let outputs = self.oracle.output_batch(&missing);
+            assert_eq!(outputs.len(), missing.len());
+            self.queries
+                .borrow_mut()
+                .extend(missing.into_iter().zip(outputs));
+        }
+        let queries = self.queries.borrow();
+        words.iter().map(|w| queries[w].clone()).collect()
+    }
+
     fn update_table(&mut self) {
         let mut updates = vec![];
         let experiment_count = self.experiments.len();
@@ -68,8 +117,7 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
             if stored_experiment_count < experiment_count {
                 for i in stored_experiment_count..experiment_count {
                     let concat = Concat(&mr, &self.experiments[i]).collect_vec();
-                    let output = self.output(&concat).clone();
-                    updates.push((mr.clone(), i, output));
+                    updates.push((mr.clone(), i, concat));
                 }
             } else {
                 assert_eq!(
@@ -81,7 +129,10 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
             }
         }
 
-        for (mr, i, output) in updates {
+        // all missing cells of the table are filled with a single batch
+        let words = updates.iter().map(|(_, _, w)| w.clone()).collect_vec();
+        let outputs = self.output_batch(&words);
+        for ((mr, i, _), output) in updates.into_iter().zip(outputs) {
             assert!(i < self.experiments.len());
             let mut row = self.table.entry(mr).or_default();
             row.push(output);
@@ -132,28 +183,45 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
                 continue 'outer;
             }
 
+            // Handlers other than `MalerPnueli` may add rows to the base that are not distinguished by
+            // the current experiments, so the table has to be made consistent before we can build a
+            // hypothesis from it. With suffix-closed experiments this check never succeeds.
+            if let Some(experiment) = self.inconsistent_words() {
+                trace!("resolving inconsistency with {}", experiment.as_string());
+                self.experiments.push(Experiment(experiment));
+                continue 'outer;
+            }
+
             let hypothesis = self.hypothesis();
+            self.equivalence_queries += 1;
 
             let Err((witness, expected_color)) = self.oracle.equivalence(&hypothesis) else {
                 let duration = start.elapsed().as_millis();
                 info!("Execution of LStar took {duration}ms");
                 return hypothesis;
             };
-            assert!(hypothesis.output(&witness) != expected_color);
-            self.process_counterexample(witness, expected_color);
+            let output = hypothesis.output(&witness);
+            assert!(output != expected_color);
+            self.process_counterexample(witness, output);
         }
 
         panic!("Iteration threshold exceeded!")
     }
 
-    fn process_counterexample(&mut self, word: Word<D>, color: D::Output) {
+    fn process_counterexample(&mut self, word: Word<D>, hypothesis_output: D::Output) {
         trace!("Processing counterexample {}", word.as_string());
-        for i in 0..(word.len()) {
-            let suffix = Experiment(word[i..].to_vec());
-            assert!(!suffix.is_empty());
-            if !self.experiments.contains(&suffix) {
-                trace!("Adding counterexample {}", suffix.as_string());
-                self.experiments.push(suffix)
+        let refinement = self.handler.handle(&word, &hypothesis_output, self);
+        for row in refinement.rows {
+            if !self.base.contains(&row) {
+                trace!("Adding access word {}", row.as_string());
+                self.base.push(row);
+            }
+        }
+        for experiment in refinement.experiments {
+            let experiment = Experiment(experiment);
+            if !experiment.is_empty() && !self.experiments.contains(&experiment) {
+                trace!("Adding experiment {}", experiment.as_string());
+                self.experiments.push(experiment)
             }
         }
     }
@@ -176,11 +244,14 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
         let mut observations = math::Map::default();
 
         for mr in &self.base {
+            let observed = self.table.get(mr).unwrap();
+            if observations.contains_key(observed) {
+                // the table is consistent, so we only need one state per row
+                continue;
+            }
             let color = self.state_color(mr);
             let id = ts.add_state(color);
             state_map.insert(mr, id);
-
-            let observed = self.table.get(mr).unwrap();
             observations.insert(observed, mr);
         }
 
@@ -258,8 +329,9 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
                     let l = self.table.get(&left_ext).expect("Should be present!");
                     let r = self.table.get(&right_ext).expect("Should be present!");
 
+                    // only this symbol agrees, the remaining ones may still reveal an inconsistency
                     if l == r {
-                        continue 'middle;
+                        continue;
                     }
                     'inner: for (j, e) in self.experiments.iter().enumerate() {
                         assert!(j < std::cmp::min(l.len(), r.len()));
@@ -320,6 +392,38 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet, Output = D::Output>> LStar
     }
 }
 
+impl<D, T> CounterexampleContext<SymbolOf<D>, D::Output> for LStar<D, T>
+where
+    D: Hypothesis,
+    T: Oracle<Alphabet = D::Alphabet, Output = D::Output>,
+{
+    fn query(&self, word: &[SymbolOf<D>]) -> D::Output {
+        self.output(&word.to_vec())
+    }
+
+    fn access_word(&self, word: &[SymbolOf<D>]) -> Vec<SymbolOf<D>> {
+        let mut access = vec![];
+        for sym in word {
+            access.push(*sym);
+            let row = self
+                .table
+                .get(&access)
+                .expect("table must be closed when processing counterexamples");
+            access = self
+                .base
+                .iter()
+                .find(|mr| self.table.get(*mr) == Some(row))
+                .expect("table must be closed when processing counterexamples")
+                .clone();
+        }
+        access
+    }
+
+    fn is_row(&self, word: &[SymbolOf<D>]) -> bool {
+        self.table.contains_key(word)
+    }
+}
+
 impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet>> std::fmt::Debug for LStar<D, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut builder = tabled::builder::Builder::default();
@@ -347,11 +451,18 @@ impl<D: Hypothesis, T: Oracle<Alphabet = D::Alphabet>> std::fmt::Debug for LStar
 
 #[cfg(test)]
 mod tests {
-    use crate::active::{MealyOracle, MooreOracle, Oracle};
+    use super::LStar;
+    use crate::active::{
+        Angluin, CounterexampleHandler, MalerPnueli, MealyOracle, MooreOracle, Oracle,
+        RivestSchapire, ShahbazGroz,
+    };
     use automata::TransitionSystem;
     use automata::automaton::{MealyMachine, MooreMachine};
+    use automata::core::Int;
     use automata::core::alphabet::CharAlphabet;
-    use rand::Rng;
+    use automata::ts::TSBuilder;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
     use tracing::trace;
 
     #[test]
@@ -385,6 +496,87 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn lstar_maler_pnueli_query_counts() {
+        // The default handler adds all suffixes of a counterexample as experiments, which keeps the
+        // rows of the base pairwise distinct. The consistency check and the deduplication of rows in
+        // the hypothesis thus never fire, and the query counts are the same as before they existed.
+        let target = TSBuilder::without_edge_colors()
+            .with_state_colors((0..8).map(|i| if i == 0 { 1 } else { 0 }))
+            .with_edges((0..8).flat_map(|i| [(i, 'a', (i + 1) % 8), (i, 'b', i)]))
+            .into_moore(0);
+        let mut learner = LStar::new(CharAlphabet::of_size(2), MooreOracle::new(target));
+        let mm: MooreMachine = learner.infer();
+        assert_eq!(mm.size(), 8);
+        assert_eq!(learner.membership_queries(), 89);
+        assert_eq!(learner.equivalence_queries(), 2);
+
+        // outputs 1 when reading `b` after a number of `a`s that is four modulo five
+        let target: MealyMachine = TSBuilder::without_state_colors()
+            .with_transitions((0..5).flat_map(|i| {
+                [
+                    (i, 'a', 0, (i + 1) % 5),
+                    (i, 'b', if i == 4 { 1 } else { 0 }, 0),
+                ]
+            }))
+            .into_mealy(0);
+        let mut learner = LStar::new(CharAlphabet::of_size(2), MealyOracle::new(&target));
+        let mm: MealyMachine = learner.infer();
+        assert_eq!(mm.size(), 5);
+        assert_eq!(learner.membership_queries(), 46);
+        assert_eq!(learner.equivalence_queries(), 2);
+    }
+
+    #[test]
+    fn lstar_counterexample_strategies() {
+        // outputs 1 iff the number of `a`s is divisible by eight
+        let target = TSBuilder::without_edge_colors()
+            .with_state_colors((0..8).map(|i| if i == 0 { 1 } else { 0 }))
+            .with_edges((0..8).flat_map(|i| [(i, 'a', (i + 1) % 8), (i, 'b', i)]))
+            .into_moore(0);
+        let alphabet = CharAlphabet::of_size(2);
+
+        let mut counts = vec![];
+        for handler in [
+            Box::new(Angluin) as Box<dyn CounterexampleHandler<char, Int>>,
+            Box::new(MalerPnueli),
+            Box::new(RivestSchapire),
+            Box::new(ShahbazGroz),
+        ] {
+            let oracle = MooreOracle::new(target.clone());
+            let mut learner =
+                LStar::new(alphabet.clone(), oracle).with_counterexample_handler(handler);
+            let mm: MooreMachine<CharAlphabet, Int> = learner.infer();
+            assert_eq!(mm.size(), 8);
+            counts.push(learner.membership_queries());
+        }
+        trace!("membership queries per strategy: {counts:?}");
+        assert!(counts[2] < counts[1]);
+
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for _ in 0..50 {
+            let (symbols, max_color, size) = (
+                rng.gen_range(1..4),
+                rng.gen_range(1..5),
+                rng.gen_range(1..15),
+            );
+            let ts = automata::random::generate_random_mealy_with_rng(
+                &mut rng, symbols, max_color, size,
+            );
+            for handler in [
+                Box::new(Angluin) as Box<dyn CounterexampleHandler<char, Int>>,
+                Box::new(RivestSchapire),
+                Box::new(ShahbazGroz),
+            ] {
+                let oracle = MealyOracle::new(&ts);
+                let mut learner = LStar::new(oracle.alphabet().clone(), oracle)
+                    .with_counterexample_handler(handler);
+                let mm: MealyMachine = learner.infer();
+                assert_eq!(mm.size(), ts.size());
+            }
+        }
+    }
+
     #[test]
     fn lstar_random_moore() {
         let mut rng = rand::thread_rng();
@@ -0,0 +1,662 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use automata::core::{Show, math};
+use itertools::Itertools;
+use tracing::{debug, info, trace, warn};
+
+use super::{Counterexample, Hypothesis, Oracle};
+
+type Symbol<T> = <<T as Oracle>::Alphabet as Alphabet>::Symbol;
+
+#[derive(Debug, Clone)]
+struct CacheNode<S, X> {
+    output: Option<X>,
+    successors: math::Map<S, usize>,
+}
+
+/// An [`Oracle`] wrapper that caches the answers to membership queries in a tree, whose nodes
+/// correspond to the prefixes of all queried words. This allows the cache to be shared between
+/// learners, simply by reusing the wrapped oracle.
+///
+/// A cache that is created with [`Self::prefix_closed`] obtains the outputs on all prefixes of a
+/// word whenever it has to pose a query, see [`Oracle::output_sequence`]. For Mealy machines,
+/// where a single run produces all of these outputs, this means that queries whose answer is
+/// implied by a longer query that has been posed before are answered without the underlying
+/// oracle.
+#[derive(Debug, Clone)]
+pub struct QueryCache<T: Oracle> {
+    oracle: T,
+    tree: RefCell<Vec<CacheNode<Symbol<T>, T::Output>>>,
+    prefix_closed: bool,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl<T: Oracle> QueryCache<T> {
+    /// Creates a new cache that only stores the answers to the queries that are posed.
+    pub fn new(oracle: T) -> Self {
+        Self {
+            oracle,
+            tree: RefCell::new(vec![CacheNode {
+                output: None,
+                successors: math::Map::default(),
+            }]),
+            prefix_closed: false,
+            hits: Cell::new(0),
+            misses: Cell::new(0),
+        }
+    }
+
+    /// Creates a new cache that stores the answers for all prefixes of the queries that are posed.
+    pub fn prefix_closed(oracle: T) -> Self {
+        Self {
+            prefix_closed: true,
+            ..Self::new(oracle)
+        }
+    }
+
+    /// Returns a reference to the underlying oracle.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+
+    /// Returns the number of queries that were answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Returns the number of queries that were passed to the underlying oracle.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// Returns the number of words whose output is stored in the cache.
+    pub fn len(&self) -> usize {
+        self.tree
+            .borrow()
+            .iter()
+            .filter(|node| node.output.is_some())
+            .count()
+    }
+
+    /// Returns `true` if the cache does not contain any outputs.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the cached output for `word`, if there is one.
+    pub fn lookup(&self, word: &[Symbol<T>]) -> Option<T::Output> {
+        let tree = self.tree.borrow();
+        let node = word
+            .iter()
+            .try_fold(0, |node, a| tree[node].successors.get(a).copied())?;
+        tree[node].output.clone()
+    }
+
+    /// Stores `outputs` for the prefixes of `word`, where the first output belongs to the prefix
+    /// of length `offset`.
+    fn insert(&self, word: &[Symbol<T>], offset: usize, outputs: Vec<T::Output>) {
+        let mut tree = self.tree.borrow_mut();
+        let mut node = 0;
+        let mut outputs = outputs.into_iter();
+        for i in 0..=word.len() {
+            if i > 0 {
+                let a = word[i - 1];
+                node = match tree[node].successors.get(&a) {
+                    Some(successor) => *successor,
+                    None => {
+                        let successor = tree.len();
+                        tree.push(CacheNode {
+                            output: None,
+                            successors: math::Map::default(),
+                        });
+                        tree[node].successors.insert(a, successor);
+                        successor
+                    }
+                };
+            }
+            if i >= offset {
+                tree[node].output = outputs.next();
+            }
+        }
+    }
+}
+
+impl<T: Oracle> Oracle for QueryCache<T> {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<T>>>(&self, word: W) -> Self::Output {
+        let word = word.symbols().collect_vec();
+        if let Some(output) = self.lookup(&word) {
+            self.hits.set(self.hits.get() + 1);
+            return output;
+        }
+        self.misses.set(self.misses.get() + 1);
+        if self.prefix_closed && !word.is_empty() {
+            let outputs = self.oracle.output_sequence(&word);
+            let output = outputs.last().cloned().expect("word is not empty");
+            self.insert(&word, 1, outputs);
+            output
+        } else {
+            let output = self.oracle.output(&word);
+            self.insert(&word, word.len(), vec![output.clone()]);
+            output
+        }
+    }
+
+    /// A prefix-closed cache answers the whole sequence with a single query for `word` if the
+    /// output on some prefix is missing.
+    fn output_sequence(&self, word: &[Symbol<T>]) -> Vec<Self::Output> {
+        if self.prefix_closed && (1..=word.len()).any(|i| self.lookup(&word[..i]).is_none()) {
+            self.misses.set(self.misses.get() + 1);
+            let outputs = self.oracle.output_sequence(word);
+            self.insert(word, 1, outputs.clone());
+            return outputs;
+        }
+        (1..=word.len()).map(|i| self.output(&word[..i])).collect()
+    }
+
+    fn output_batch(&self, words: &[Vec<Symbol<T>>]) -> Vec<Self::Output> {
+        if self.prefix_closed {
+            return words.iter().map(|word| self.output(word)).collect();
+        }
+        // only the words that are not cached are passed on, each of them once
+        let missing = words
+            .iter()
+            .filter(|word| self.lookup(word).is_none())
+            .unique()
+            .cloned()
+            .collect_vec();
+        self.misses.set(self.misses.get() + missing.len());
+        self.hits.set(self.hits.get() + words.len() - missing.len());
+        for (word, output) in missing.iter().zip(self.oracle.output_batch(&missing)) {
+            self.insert(word, word.len(), vec![output]);
+        }
+        words
+            .iter()
+            .map(|word| self.lookup(word).expect("all words are cached"))
+            .collect()
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        self.oracle.equivalence(hypothesis)
+    }
+}
+
+/// Statistics about the queries that have been posed to an oracle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct QueryStatistics {
+    /// The number of membership queries.
+    pub membership_queries: usize,
+    /// The total number of symbols in all membership queries.
+    pub symbols: usize,
+    /// The number of equivalence queries.
+    pub equivalence_queries: usize,
+}
+
+/// An [`Oracle`] wrapper that counts the queries that are posed to the underlying oracle, see
+/// [`QueryStatistics`]. An output sequence is answered by a single run, so it is counted as one
+/// query whose symbols are those of the whole word.
+#[derive(Debug, Clone)]
+pub struct QueryCounter<T> {
+    oracle: T,
+    statistics: Cell<QueryStatistics>,
+}
+
+impl<T: Oracle> QueryCounter<T> {
+    /// Creates a new counter for the given oracle.
+    pub fn new(oracle: T) -> Self {
+        Self {
+            oracle,
+            statistics: Cell::new(QueryStatistics::default()),
+        }
+    }
+
+    /// Returns a reference to the underlying oracle.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+
+    /// Returns the statistics that have been collected so far.
+    pub fn statistics(&self) -> QueryStatistics {
+        self.statistics.get()
+    }
+
+    /// Resets all counts to zero.
+    pub fn reset(&self) {
+        self.statistics.set(QueryStatistics::default());
+    }
+
+    fn update(&self, f: impl FnOnce(&mut QueryStatistics)) {
+        let mut statistics = self.statistics.get();
+        f(&mut statistics);
+        self.statistics.set(statistics);
+    }
+}
+
+impl<T: Oracle> Oracle for QueryCounter<T> {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<T>>>(&self, word: W) -> Self::Output {
+        let length = word.symbols().count();
+        self.update(|s| {
+            s.membership_queries += 1;
+            s.symbols += length;
+        });
+        self.oracle.output(word)
+    }
+
+    fn output_sequence(&self, word: &[Symbol<T>]) -> Vec<Self::Output> {
+        self.update(|s| {
+            s.membership_queries += 1;
+            s.symbols += word.len();
+        });
+        self.oracle.output_sequence(word)
+    }
+
+    fn output_batch(&self, words: &[Vec<Symbol<T>>]) -> Vec<Self::Output> {
+        self.update(|s| {
+            s.membership_queries += words.len();
+            s.symbols += words.iter().map(|word| word.len()).sum::<usize>();
+        });
+        self.oracle.output_batch(words)
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        self.update(|s| s.equivalence_queries += 1);
+        self.oracle.equivalence(hypothesis)
+    }
+}
+
+/// An [`Oracle`] wrapper that logs all queries and their answers with [`tracing`]. Membership
+/// queries are logged on the `debug` level and equivalence queries on the `info` level.
+#[derive(Debug, Clone)]
+pub struct QueryLogger<T> {
+    oracle: T,
+    name: String,
+}
+
+impl<T: Oracle> QueryLogger<T> {
+    /// Creates a new logger for the given oracle, whose messages are prefixed with `name`.
+    pub fn new(oracle: T, name: impl Into<String>) -> Self {
+        Self {
+            oracle,
+            name: name.into(),
+        }
+    }
+
+    /// Returns a reference to the underlying oracle.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+}
+
+impl<T: Oracle> Oracle for QueryLogger<T> {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<T>>>(&self, word: W) -> Self::Output {
+        let shown = word.as_string();
+        let output = self.oracle.output(word);
+        debug!("[{}] membership query {shown} -> {output:?}", self.name);
+        output
+    }
+
+    fn output_sequence(&self, word: &[Symbol<T>]) -> Vec<Self::Output> {
+        let outputs = self.oracle.output_sequence(word);
+        debug!(
+            "[{}] output query {} -> {outputs:?}",
+            self.name,
+            word.as_string()
+        );
+        outputs
+    }
+
+    fn output_batch(&self, words: &[Vec<Symbol<T>>]) -> Vec<Self::Output> {
+        let outputs = self.oracle.output_batch(words);
+        debug!(
+            "[{}] batch of {} membership queries",
+            self.name,
+            words.len()
+        );
+        for (word, output) in words.iter().zip(&outputs) {
+            debug!(
+                "[{}] membership query {} -> {output:?}",
+                self.name,
+                word.as_string()
+            );
+        }
+        outputs
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        let result = self.oracle.equivalence(hypothesis);
+        match &result {
+            Ok(()) => info!(
+                "[{}] equivalence query with {} states succeeded",
+                self.name,
+                hypothesis.size()
+            ),
+            Err((word, output)) => info!(
+                "[{}] equivalence query with {} states returned counterexample {} -> {output:?}",
+                self.name,
+                hypothesis.size(),
+                word.as_string()
+            ),
+        }
+        result
+    }
+}
+
+/// An [`Oracle`] wrapper that caches membership queries in a file, such that an interrupted
+/// learning run can be resumed without posing the same queries again. Each answered query is
+/// immediately appended to the file as a line, which consists of the symbols of the word (as given
+/// by [`Show`]) separated by spaces, followed by a tab and the output. When the cache is opened,
+/// all entries of an existing file are loaded.
+///
+/// If an entry cannot be written, a warning is emitted and the query is still answered from
+/// memory. Such entries are retried with every following write and by [`Self::flush`], which
+/// reports the error to the caller.
+#[derive(Debug)]
+pub struct PersistentCache<T: Oracle> {
+    oracle: T,
+    entries: RefCell<math::OrderedMap<Vec<Symbol<T>>, T::Output>>,
+    file: RefCell<File>,
+    // lines that could not be written to the file yet
+    pending: RefCell<VecDeque<String>>,
+}
+
+impl<T> PersistentCache<T>
+where
+    T: Oracle,
+    Symbol<T>: FromStr,
+    T::Output: Display + FromStr,
+{
+    /// Opens the cache file at `path` for the given oracle, creating it if it does not exist.
+    pub fn open(oracle: T, path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let invalid = |line: &str| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid cache entry `{line}` in {}", path.display()),
+            )
+        };
+
+        let mut entries = math::OrderedMap::default();
+        if path.exists() {
+            for line in BufReader::new(File::open(path)?).lines() {
+                let line = line?;
+                let Some((word, output)) = line.split_once('\t') else {
+                    return Err(invalid(&line));
+                };
+                let word = word
+                    .split_whitespace()
+                    .map(|symbol| symbol.parse().map_err(|_| invalid(&line)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let output = output.parse().map_err(|_| invalid(&line))?;
+                entries.insert(word, output);
+            }
+            debug!(
+                "loaded {} cached queries from {}",
+                entries.len(),
+                path.display()
+            );
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            oracle,
+            entries: RefCell::new(entries),
+            file: RefCell::new(file),
+            pending: RefCell::new(VecDeque::new()),
+        })
+    }
+
+    /// Returns a reference to the underlying oracle.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+
+    /// Writes all entries that have not been written yet to the file. Returns an error if this
+    /// fails, in which case the remaining entries are kept and retried on the next call.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let mut pending = self.pending.borrow_mut();
+        let mut file = self.file.borrow_mut();
+        while let Some(line) = pending.front() {
+            writeln!(file, "{line}")?;
+            pending.pop_front();
+        }
+        file.flush()
+    }
+
+    /// Stores the given query in memory and appends it to the file.
+    fn persist(&self, word: Vec<Symbol<T>>, output: T::Output) {
+        let line = format!(
+            "{}\t{output}",
+            word.iter().map(|symbol| symbol.show()).join(" ")
+        );
+        trace!("persisting query {line}");
+        self.entries.borrow_mut().insert(word, output);
+        self.pending.borrow_mut().push_back(line);
+        if let Err(e) = self.flush() {
+            warn!(
+                "could not write to query cache, keeping {} queries in memory: {e}",
+                self.pending.borrow().len()
+            );
+        }
+    }
+
+    /// Returns the number of cached queries.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Returns `true` if no query is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+}
+
+impl<T> Oracle for PersistentCache<T>
+where
+    T: Oracle,
+    Symbol<T>: FromStr,
+    T::Output: Display + FromStr,
+{
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<T>>>(&self, word: W) -> Self::Output {
+        let word = word.symbols().collect_vec();
+        if let Some(output) = self.entries.borrow().get(&word) {
+            return output.clone();
+        }
+
+        let output = self.oracle.output(&word);
+        self.persist(word, output.clone());
+        output
+    }
+
+    fn output_batch(&self, words: &[Vec<Symbol<T>>]) -> Vec<Self::Output> {
+        let missing = words
+            .iter()
+            .filter(|word| !self.entries.borrow().contains_key(*word))
+            .unique()
+            .cloned()
+            .collect_vec();
+        let outputs = self.oracle.output_batch(&missing);
+        for (word, output) in missing.into_iter().zip(outputs) {
+            self.persist(word, output);
+        }
+        let entries = self.entries.borrow();
+        words.iter().map(|word| entries[word].clone()).collect()
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        self.oracle.equivalence(hypothesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PersistentCache, QueryCache, QueryCounter, QueryLogger, QueryStatistics};
+    use crate::active::{LSharp, LStar, MealyOracle, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::MealyMachine;
+    use automata::random::generate_random_mealy;
+
+    #[test]
+    fn prefix_closed_cache() {
+        let target = generate_random_mealy(2, 3, 5);
+        let cache = QueryCache::prefix_closed(QueryCounter::new(MealyOracle::new(&target)));
+        let output = cache.output("abba");
+        assert_eq!(Some(output), cache.lookup(&['a', 'b', 'b', 'a']));
+        assert_eq!(cache.len(), 4);
+
+        // prefixes of the first query are implied
+        cache.output("ab");
+        cache.output("abb");
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 1);
+        let statistics = cache.inner().statistics();
+        assert_eq!((statistics.membership_queries, statistics.symbols), (1, 4));
+
+        // an output sequence is answered with a single query, unless all prefixes are cached
+        let outputs = cache.output_sequence(&['b', 'a', 'b']);
+        assert_eq!(cache.lookup(&['b', 'a', 'b']), outputs.last().copied());
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.output_sequence(&['b', 'a']), outputs[..2]);
+        assert_eq!(cache.misses(), 2);
+        let statistics = cache.inner().statistics();
+        assert_eq!((statistics.membership_queries, statistics.symbols), (2, 7));
+
+        let cache = QueryCache::new(QueryCounter::new(MealyOracle::new(&target)));
+        cache.output("abba");
+        cache.output("ab");
+        cache.output("abba");
+        assert_eq!(cache.len(), 2);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+    }
+
+    #[test]
+    fn shared_cache() {
+        let target = generate_random_mealy(3, 3, 10);
+        let oracle = QueryLogger::new(
+            QueryCache::prefix_closed(QueryCounter::new(MealyOracle::new(&target))),
+            "target",
+        );
+
+        let mm: MealyMachine = LStar::new(oracle.alphabet().clone(), &oracle).infer();
+        assert_eq!(mm.size(), target.size());
+        let QueryStatistics {
+            membership_queries,
+            equivalence_queries,
+            ..
+        } = oracle.inner().inner().statistics();
+        assert!(membership_queries >= oracle.inner().misses());
+        assert!(equivalence_queries > 0);
+
+        // a second learner reuses the answers of the first one
+        let hits = oracle.inner().hits();
+        let mm = LSharp::new(oracle.alphabet().clone(), &oracle).infer();
+        assert_eq!(mm.size(), target.size());
+        assert!(oracle.inner().hits() > hits);
+    }
+
+    #[test]
+    fn persistent_cache() {
+        let path = std::env::temp_dir().join(format!("queries-{}.cache", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let target = generate_random_mealy(2, 3, 5);
+
+        let cache = PersistentCache::open(MealyOracle::new(&target), &path).unwrap();
+        let mm: MealyMachine = LStar::new(cache.alphabet().clone(), &cache).infer();
+        let stored = cache.len();
+        assert!(stored > 0);
+        drop(cache);
+
+        // resuming answers all queries from the file
+        let counter = QueryCounter::new(MealyOracle::new(&target));
+        let cache = PersistentCache::open(&counter, &path).unwrap();
+        assert_eq!(cache.len(), stored);
+        let resumed: MealyMachine = LStar::new(cache.alphabet().clone(), &cache).infer();
+        assert_eq!(resumed.size(), mm.size());
+        assert_eq!(counter.statistics().membership_queries, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn persistent_cache_write_failure() {
+        let path = std::env::temp_dir().join(format!("failing-{}.cache", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let target = generate_random_mealy(2, 3, 5);
+
+        let cache = PersistentCache::open(MealyOracle::new(&target), &path).unwrap();
+        // a read-only handle makes every write fail
+        *cache.file.borrow_mut() = std::fs::File::open(&path).unwrap();
+        let output = cache.output("ab");
+        assert_eq!(cache.output("ab"), output);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.flush().is_err());
+
+        *cache.file.borrow_mut() = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        cache.flush().unwrap();
+        drop(cache);
+        let cache = PersistentCache::open(MealyOracle::new(&target), &path).unwrap();
+        assert_eq!(cache.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
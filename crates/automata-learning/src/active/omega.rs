@@ -0,0 +1,115 @@
+use automata::TransitionSystem;
+use automata::automaton::DPA;
+use automata::core::alphabet::Alphabet;
+use automata::core::word::ReducedOmegaWord;
+use tracing::trace;
+
+type Symbol<A> = <A as Alphabet>::Symbol;
+
+/// A hypothesis for an omega-regular language, which can be compared against a [`DPA`]
+/// representing the target language. Implementors return an ultimately periodic word on which
+/// the hypothesis and the target disagree, or `None` if they accept the same language.
+pub trait OmegaHypothesis {
+    /// The alphabet over which the hypothesis is defined.
+    type Alphabet: Alphabet;
+
+    /// Computes an ultimately periodic word that witnesses the inequivalence of `self` and
+    /// `target`, or returns `None` if both are equivalent.
+    fn separate_from(
+        &self,
+        target: &DPA<Self::Alphabet>,
+    ) -> Option<ReducedOmegaWord<Symbol<Self::Alphabet>>>;
+}
+
+impl<A: Alphabet> OmegaHypothesis for DPA<A> {
+    type Alphabet = A;
+
+    fn separate_from(&self, target: &DPA<A>) -> Option<ReducedOmegaWord<A::Symbol>> {
+        target.witness_inequivalence(self)
+    }
+}
+
+/// An oracle for an omega-regular language, which answers membership queries for ultimately
+/// periodic words and equivalence queries for hypotheses. Counterexamples to equivalence queries
+/// are ultimately periodic words on which the hypothesis and the target disagree.
+pub trait OmegaOracle {
+    /// The alphabet over which the target language is defined.
+    type Alphabet: Alphabet;
+
+    /// Returns a reference to the alphabet.
+    fn alphabet(&self) -> &Self::Alphabet;
+
+    /// Returns whether the given ultimately periodic word belongs to the target language.
+    fn membership(&self, word: &ReducedOmegaWord<Symbol<Self::Alphabet>>) -> bool;
+
+    /// Checks whether `hypothesis` is equivalent to the target, returning a counterexample if it
+    /// is not.
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), ReducedOmegaWord<Symbol<Self::Alphabet>>>
+    where
+        H: OmegaHypothesis<Alphabet = Self::Alphabet>;
+}
+
+impl<T: OmegaOracle> OmegaOracle for &T {
+    type Alphabet = T::Alphabet;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        T::alphabet(self)
+    }
+
+    fn membership(&self, word: &ReducedOmegaWord<Symbol<Self::Alphabet>>) -> bool {
+        T::membership(self, word)
+    }
+
+    fn equivalence<H>(&self, hypothesis: &H) -> Result<(), ReducedOmegaWord<Symbol<Self::Alphabet>>>
+    where
+        H: OmegaHypothesis<Alphabet = Self::Alphabet>,
+    {
+        T::equivalence(self, hypothesis)
+    }
+}
+
+/// An [`OmegaOracle`] that is backed by a [`DPA`] recognizing the target language.
+#[derive(Debug, Clone)]
+pub struct DPAOracle<A: Alphabet> {
+    dpa: DPA<A>,
+}
+
+impl<A: Alphabet> DPAOracle<A> {
+    /// Creates a new oracle for the language of the given [`DPA`].
+    pub fn new(dpa: DPA<A>) -> Self {
+        Self { dpa }
+    }
+
+    /// Returns a reference to the underlying [`DPA`].
+    pub fn dpa(&self) -> &DPA<A> {
+        &self.dpa
+    }
+}
+
+impl<A: Alphabet> OmegaOracle for DPAOracle<A> {
+    type Alphabet = A;
+
+    fn alphabet(&self) -> &A {
+        self.dpa.alphabet()
+    }
+
+    fn membership(&self, word: &ReducedOmegaWord<A::Symbol>) -> bool {
+        self.dpa.accepts(word)
+    }
+
+    fn equivalence<H>(&self, hypothesis: &H) -> Result<(), ReducedOmegaWord<A::Symbol>>
+    where
+        H: OmegaHypothesis<Alphabet = A>,
+    {
+        match hypothesis.separate_from(&self.dpa) {
+            Some(counterexample) => {
+                trace!("found counterexample {counterexample:?}");
+                Err(counterexample)
+            }
+            None => Ok(()),
+        }
+    }
+}
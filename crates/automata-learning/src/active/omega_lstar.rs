@@ -0,0 +1,203 @@
+use automata::TransitionSystem;
+use automata::automaton::DPA;
+use automata::core::alphabet::Alphabet;
+use tracing::{debug, info};
+
+use super::fdfa::saturated_dpa;
+use super::{FdfaLearner, FdfaVariant, OmegaOracle, fdfa_to_dpa};
+use crate::passive::precise::{MAX_PRIORITIES, build_precise_dpa_for};
+use crate::passive::{OmegaSample, infer_fwpm};
+
+/// An omega-L* style learner that infers a [`DPA`] for an omega-regular language from an
+/// [`OmegaOracle`], which answers membership queries for ultimately periodic words.
+///
+/// The membership queries are posed by observation tables for the leading congruence and a
+/// syntactic progress congruence for each of its classes, like the ones of the [`FdfaLearner`].
+/// From the answered queries, a DPA hypothesis is built passively: the leading congruence is
+/// inferred with [`OmegaSample::infer_prefix_congruence`], the progress congruences of its
+/// classes are colored canonically and the resulting mapping is turned into a DPA with the
+/// precise DPA construction (see [`infer_fwpm`]).
+///
+/// If no such DPA can be built, if it contradicts the answered queries or if the previous
+/// counterexample did not refute the tables, the DPA obtained from the tables themselves is
+/// posed instead. Only if the tables do not yet describe a saturated family, this conversion
+/// would not be faithful, so the family itself is posed. Every counterexample that refutes the
+/// tables is analysed on them, which guarantees termination.
+pub struct OmegaLStar<A: Alphabet, T: OmegaOracle<Alphabet = A>> {
+    learner: FdfaLearner<A, T>,
+    equivalence_queries: usize,
+}
+
+impl<A: Alphabet, T: OmegaOracle<Alphabet = A>> OmegaLStar<A, T> {
+    /// Creates a new learner that poses queries to `oracle`.
+    pub fn new(alphabet: A, oracle: T) -> Self {
+        Self {
+            learner: FdfaLearner::new(alphabet, oracle, FdfaVariant::Syntactic),
+            equivalence_queries: 0,
+        }
+    }
+
+    /// Returns the number of distinct membership queries that have been posed so far.
+    pub fn membership_queries(&self) -> usize {
+        self.learner.membership_queries()
+    }
+
+    /// Returns the number of equivalence queries that have been posed so far.
+    pub fn equivalence_queries(&self) -> usize {
+        self.equivalence_queries
+    }
+
+    /// Runs the learner until the oracle accepts a hypothesis and returns the learned [`DPA`].
+    /// Returns `None` if the oracle accepts the family described by the tables, but neither it
+    /// nor the answered queries can be turned into an equivalent DPA, which happens if more
+    /// priorities are needed than the precise DPA construction supports.
+    pub fn infer(&mut self) -> Option<DPA<A>> {
+        let mut fdfa = self.learner.hypothesis();
+        let mut passive = true;
+        loop {
+            let candidate = if passive {
+                self.passive_hypothesis()
+            } else {
+                None
+            };
+            self.equivalence_queries += 1;
+            let result = match (candidate, saturated_dpa(&fdfa)) {
+                (Some(dpa), _) | (None, Some(dpa)) => {
+                    debug!("posing DPA hypothesis with {} states", dpa.size());
+                    self.learner.oracle().equivalence(&dpa).map(|_| Some(dpa))
+                }
+                (None, None) => {
+                    debug!("tables are not saturated, posing FDFA hypothesis");
+                    self.learner
+                        .oracle()
+                        .equivalence(&fdfa)
+                        .map(|_| fdfa_to_dpa(&fdfa))
+                }
+            };
+            match result {
+                Ok(Some(dpa)) => {
+                    info!(
+                        "learned DPA with {} states after {} equivalence queries",
+                        dpa.size(),
+                        self.equivalence_queries
+                    );
+                    return Some(dpa);
+                }
+                Ok(None) => {
+                    debug!("FDFA cannot be converted, posing DPA inferred from the queries");
+                    let dpa = self.passive_hypothesis()?;
+                    self.equivalence_queries += 1;
+                    return self.learner.oracle().equivalence(&dpa).ok().map(|_| dpa);
+                }
+                Err(counterexample) => {
+                    passive = self.learner.refutes(&fdfa, &counterexample);
+                    if passive {
+                        debug!("processing counterexample {counterexample:?}");
+                        self.learner.process_counterexample(&fdfa, &counterexample);
+                        fdfa = self.learner.hypothesis();
+                    } else {
+                        debug!("counterexample {counterexample:?} does not refute the tables");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Infers a DPA from the sample of all answered membership queries, which is returned if it
+    /// is consistent with the sample.
+    fn passive_hypothesis(&self) -> Option<DPA<A>> {
+        let sample = self.learner.sample();
+        let fwpm = infer_fwpm(&sample).ok()?;
+        if !(1..=MAX_PRIORITIES).contains(&fwpm.complexity()) {
+            return None;
+        }
+        let dpa = build_precise_dpa_for(fwpm);
+        consistent(&dpa, &sample).then_some(dpa)
+    }
+}
+
+/// Checks whether `dpa` classifies all words of `sample` correctly.
+fn consistent<A: Alphabet>(dpa: &DPA<A>, sample: &OmegaSample<A>) -> bool {
+    sample.positive_words().all(|w| dpa.accepts(w))
+        && sample.negative_words().all(|w| !dpa.accepts(w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OmegaLStar;
+    use crate::active::{DPAOracle, OmegaOracle};
+    use automata::TransitionSystem;
+    use automata::automaton::DPA;
+    use automata::ts::TSBuilder;
+
+    fn learn(target: DPA) -> (DPA, usize) {
+        let oracle = DPAOracle::new(target.clone());
+        let mut learner = OmegaLStar::new(oracle.alphabet().clone(), &oracle);
+        let dpa = learner.infer().expect("target needs few priorities");
+        assert!(dpa.language_equivalent(&target));
+        assert!(oracle.equivalence(&dpa).is_ok());
+        (dpa, learner.equivalence_queries())
+    }
+
+    #[test]
+    fn omega_lstar() {
+        // infinitely often, an a is read after an odd number of consecutive b's
+        let target = TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 1, 0),
+                (0, 'b', 1, 1),
+                (1, 'a', 0, 0),
+                (1, 'b', 1, 0),
+            ])
+            .into_dpa(0);
+        let (dpa, equivalence_queries) = learn(target.clone());
+        assert!(dpa.size() <= 2 * target.size());
+        assert!(equivalence_queries > 0);
+    }
+
+    #[test]
+    fn omega_lstar_fixed_targets() {
+        // infinitely many a's
+        learn(
+            TSBuilder::without_state_colors()
+                .with_edges([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+                .into_dpa(0),
+        );
+        // finitely many a's
+        learn(
+            TSBuilder::without_state_colors()
+                .with_edges([(0, 'a', 1, 0), (0, 'b', 2, 0)])
+                .into_dpa(0),
+        );
+        // the least priority seen infinitely often is determined by c, a or b in this order
+        learn(
+            TSBuilder::without_state_colors()
+                .with_edges([(0, 'a', 1, 0), (0, 'b', 2, 0), (0, 'c', 0, 0)])
+                .into_dpa(0),
+        );
+        // after the first b, the word must contain infinitely many a's
+        learn(
+            TSBuilder::without_state_colors()
+                .with_edges([
+                    (0, 'a', 1, 0),
+                    (0, 'b', 1, 1),
+                    (1, 'a', 0, 1),
+                    (1, 'b', 1, 1),
+                ])
+                .into_dpa(0),
+        );
+        // words with a b at an even position and infinitely many a's afterwards
+        learn(
+            TSBuilder::without_state_colors()
+                .with_edges([
+                    (0, 'a', 1, 1),
+                    (0, 'b', 1, 2),
+                    (1, 'a', 1, 0),
+                    (1, 'b', 1, 0),
+                    (2, 'a', 0, 2),
+                    (2, 'b', 1, 2),
+                ])
+                .into_dpa(0),
+        );
+    }
+}
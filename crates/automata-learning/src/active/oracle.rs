@@ -1,14 +1,14 @@
 use std::cell::RefCell;
 
-use crate::passive::SetSample;
+use crate::passive::{OutputSample, SetSample};
 use automata::automaton::{DFA, IntoMooreMachine, MealyLike, MealyMachine};
 use automata::core::alphabet::{Alphabet, Symbol};
 use automata::core::word::{FiniteWord, Word};
 use automata::core::{Color, Int, Lattice, Void, math::Set};
 use automata::representation::CollectTs;
 use automata::ts::operations::Product;
-use automata::ts::{Deterministic, EdgeColor, StateColor};
-use automata::{Congruence, TransitionSystem, ts::operations::MapStateColor};
+use automata::ts::{Deterministic, EdgeColor, IsEdge, StateColor};
+use automata::{Congruence, Pointed, TransitionSystem, ts::operations::MapStateColor};
 use tracing::trace;
 
 use super::Hypothesis;
@@ -33,6 +33,25 @@ pub trait Oracle {
         word: W,
     ) -> Self::Output;
 
+    /// Returns the outputs on all non-empty prefixes of `word`, ordered by increasing length. For
+    /// a Mealy machine, this is the sequence of outputs that is produced when reading `word`. The
+    /// default implementation poses one query for each prefix, oracles that observe all these
+    /// outputs in a single run should override it.
+    fn output_sequence(&self, word: &[<Self::Alphabet as Alphabet>::Symbol]) -> Vec<Self::Output> {
+        (1..=word.len()).map(|i| self.output(&word[..i])).collect()
+    }
+
+    /// Answers a batch of membership queries at once and returns the outputs in the same order
+    /// as the given words. The default implementation answers them one after another, oracles
+    /// for which submitting many queries at once is cheaper should override it. See
+    /// [`ParallelOracle`](super::ParallelOracle) for answering a batch in parallel.
+    fn output_batch(
+        &self,
+        words: &[Vec<<Self::Alphabet as Alphabet>::Symbol>],
+    ) -> Vec<Self::Output> {
+        words.iter().map(|word| self.output(word)).collect()
+    }
+
     fn equivalence<H>(
         &self,
         hypothesis: &H,
@@ -41,6 +60,43 @@ pub trait Oracle {
         H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>;
 }
 
+impl<T: Oracle> Oracle for &T {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        T::alphabet(self)
+    }
+
+    fn output<W: FiniteWord<Symbol = <Self::Alphabet as Alphabet>::Symbol>>(
+        &self,
+        word: W,
+    ) -> Self::Output {
+        T::output(self, word)
+    }
+
+    fn output_sequence(&self, word: &[<Self::Alphabet as Alphabet>::Symbol]) -> Vec<Self::Output> {
+        T::output_sequence(self, word)
+    }
+
+    fn output_batch(
+        &self,
+        words: &[Vec<<Self::Alphabet as Alphabet>::Symbol>],
+    ) -> Vec<Self::Output> {
+        T::output_batch(self, words)
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        T::equivalence(self, hypothesis)
+    }
+}
+
 pub fn lstar<H, O>(oracle: O) -> H
 where
     O: Oracle,
@@ -52,14 +108,15 @@ where
 /// An oracle/minimally adequate teacher based on a [`SetSample`]. It answers membership queries by looking up the
 /// word in the sample and returning the corresponding color. If the word is not in the sample, it returns the
 /// default color. Equivalence queries are perfomed by checking if the hypothesis produces the same output as the
-/// sample for all words in the sample.
+/// sample for all words in the sample, the counterexample is the length-lexicographically least word on which
+/// they disagree.
 #[derive(Debug, Clone)]
 pub struct SampleOracle<A: Alphabet, W: Word<Symbol = A::Symbol>> {
     sample: SetSample<A, W>,
     default: bool,
 }
 
-impl<A: Alphabet, X: FiniteWord<Symbol = A::Symbol>> Oracle for SampleOracle<A, X> {
+impl<A: Alphabet> Oracle for SampleOracle<A, Vec<A::Symbol>> {
     type Alphabet = A;
 
     type Output = bool;
@@ -68,7 +125,9 @@ impl<A: Alphabet, X: FiniteWord<Symbol = A::Symbol>> Oracle for SampleOracle<A,
         &self,
         word: W,
     ) -> Self::Output {
-        todo!()
+        self.sample
+            .classify(&word.collect_vec())
+            .unwrap_or(self.default)
     }
 
     fn equivalence<H>(
@@ -78,7 +137,12 @@ impl<A: Alphabet, X: FiniteWord<Symbol = A::Symbol>> Oracle for SampleOracle<A,
     where
         H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
     {
-        todo!()
+        least_disagreement(
+            self.sample
+                .entries()
+                .map(|(w, c)| (w, if c { &true } else { &false })),
+            hypothesis,
+        )
     }
 
     fn alphabet(&self) -> &Self::Alphabet {
@@ -86,6 +150,57 @@ impl<A: Alphabet, X: FiniteWord<Symbol = A::Symbol>> Oracle for SampleOracle<A,
     }
 }
 
+/// An [`OutputSample`] can be used as an oracle, which answers membership queries by looking up the word in the
+/// sample. If the word is not in the sample, the default output is returned. Like for a [`SampleOracle`],
+/// counterexamples are the length-lexicographically least words of the sample on which the hypothesis produces
+/// a different output.
+impl<A: Alphabet, C: Color + Default> Oracle for OutputSample<A, C> {
+    type Alphabet = A;
+    type Output = C;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        &self.alphabet
+    }
+
+    fn output<W: FiniteWord<Symbol = A::Symbol>>(&self, word: W) -> C {
+        OutputSample::output(self, &word.collect_vec())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        least_disagreement(self.entries(), hypothesis)
+    }
+}
+
+/// Finds the length-lexicographically least of the given words on which `hypothesis` does not produce the
+/// expected output. This is the order in which minimal representatives are enumerated, so the oracles that search
+/// for counterexamples in a product with the hypothesis return exactly this word when they are given the same words.
+fn least_disagreement<'a, A, C, H>(
+    entries: impl IntoIterator<Item = (&'a Vec<A::Symbol>, &'a C)>,
+    hypothesis: &H,
+) -> Result<(), Counterexample<A, C>>
+where
+    A: Alphabet + 'a,
+    C: Color + 'a,
+    H: Hypothesis<Alphabet = A, Output = C>,
+{
+    match entries
+        .into_iter()
+        .filter(|(word, expected)| &hypothesis.output(*word) != *expected)
+        .min_by_key(|(word, _)| (word.len(), *word))
+    {
+        Some((word, expected)) => Err((word.clone(), expected.clone())),
+        None => Ok(()),
+    }
+}
+
 impl<A: Alphabet, W: Word<Symbol = A::Symbol>> SampleOracle<A, W> {
     /// Returns a reference to the underlying alphabet, as provided by [`SetSample::alphabet()`].
     pub fn alphabet(&self) -> &A {
@@ -192,6 +307,20 @@ impl<A: Alphabet, C: Color + Lattice> Oracle for MealyOracle<A, C> {
         out
     }
 
+    fn output_sequence(&self, word: &[A::Symbol]) -> Vec<Self::Output> {
+        let mut state = self.mm.initial();
+        word.iter()
+            .map(|a| {
+                let edge = self
+                    .mm
+                    .edge(state, *a)
+                    .expect("underlying Mealy machine must be complete");
+                state = edge.target();
+                edge.color().clone()
+            })
+            .collect()
+    }
+
     fn equivalence<H>(
         &self,
         hypothesis: &H,
@@ -0,0 +1,155 @@
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use rayon::prelude::*;
+use tracing::trace;
+
+use super::{Counterexample, Hypothesis, Oracle};
+
+type Symbol<T> = <<T as Oracle>::Alphabet as Alphabet>::Symbol;
+
+/// An [`Oracle`] wrapper that answers batches of membership queries in parallel using the
+/// global [`rayon`] thread pool. This requires the underlying oracle to be thread-safe, so it
+/// cannot wrap oracles with interior mutability like [`QueryCache`](super::QueryCache). Instead,
+/// a cache should wrap the parallel oracle, which then only receives the queries that are missing.
+#[derive(Debug, Clone)]
+pub struct ParallelOracle<T> {
+    oracle: T,
+}
+
+impl<T: Oracle + Sync> ParallelOracle<T> {
+    /// Creates a new instance that answers batches with the given oracle.
+    pub fn new(oracle: T) -> Self {
+        Self { oracle }
+    }
+
+    /// Returns a reference to the underlying oracle.
+    pub fn inner(&self) -> &T {
+        &self.oracle
+    }
+}
+
+impl<T> Oracle for ParallelOracle<T>
+where
+    T: Oracle + Sync,
+    T::Output: Send,
+    Symbol<T>: Sync,
+{
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<T>>>(&self, word: W) -> Self::Output {
+        self.oracle.output(word)
+    }
+
+    fn output_sequence(&self, word: &[Symbol<T>]) -> Vec<Self::Output> {
+        self.oracle.output_sequence(word)
+    }
+
+    fn output_batch(&self, words: &[Vec<Symbol<T>>]) -> Vec<Self::Output> {
+        trace!("answering batch of {} queries in parallel", words.len());
+        words
+            .par_iter()
+            .map(|word| self.oracle.output(word))
+            .collect()
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        self.oracle.equivalence(hypothesis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::ParallelOracle;
+    use crate::active::{Counterexample, Hypothesis, LStar, MealyOracle, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::MealyMachine;
+    use automata::core::Int;
+    use automata::core::alphabet::CharAlphabet;
+    use automata::core::word::FiniteWord;
+    use automata::random::generate_random_mealy;
+
+    /// Records the size of every batch that is posed.
+    struct Recording<'a> {
+        oracle: MealyOracle<CharAlphabet>,
+        batches: &'a Mutex<Vec<usize>>,
+    }
+
+    impl Oracle for Recording<'_> {
+        type Alphabet = CharAlphabet;
+        type Output = Int;
+
+        fn alphabet(&self) -> &CharAlphabet {
+            self.oracle.alphabet()
+        }
+
+        fn output<W: FiniteWord<Symbol = char>>(&self, word: W) -> Int {
+            self.oracle.output(word)
+        }
+
+        fn output_batch(&self, words: &[Vec<char>]) -> Vec<Int> {
+            self.batches.lock().unwrap().push(words.len());
+            words.iter().map(|word| self.oracle.output(word)).collect()
+        }
+
+        fn equivalence<H>(&self, hypothesis: &H) -> Result<(), Counterexample<CharAlphabet, Int>>
+        where
+            H: Hypothesis<Alphabet = CharAlphabet, Output = Int>,
+        {
+            self.oracle.equivalence(hypothesis)
+        }
+    }
+
+    #[test]
+    fn lstar_poses_batches() {
+        let target = generate_random_mealy(3, 4, 12);
+        let batches = Mutex::new(vec![]);
+        let oracle = Recording {
+            oracle: MealyOracle::new(&target),
+            batches: &batches,
+        };
+        let mut learner = LStar::new(oracle.alphabet().clone(), oracle);
+        let mm: MealyMachine = learner.infer();
+        assert_eq!(mm.size(), target.size());
+
+        let batches = batches.lock().unwrap();
+        assert!(batches.iter().all(|size| *size > 0));
+        assert!(batches.iter().sum::<usize>() <= learner.membership_queries());
+        assert!(batches.len() < learner.membership_queries());
+    }
+
+    #[test]
+    fn parallel_lstar() {
+        for _ in 0..10 {
+            let target = generate_random_mealy(3, 4, 15);
+            let oracle = MealyOracle::new(&target);
+            let mut sequential = LStar::new(oracle.alphabet().clone(), oracle);
+            let expected: MealyMachine = sequential.infer();
+
+            let oracle = ParallelOracle::new(MealyOracle::new(&target));
+            let batch = vec![vec!['a', 'b'], vec!['c', 'a', 'a']];
+            let outputs = oracle.output_batch(&batch);
+            assert_eq!(outputs, vec![oracle.output("ab"), oracle.output("caa")]);
+
+            let mut parallel = LStar::new(oracle.alphabet().clone(), oracle);
+            let mm: MealyMachine = parallel.infer();
+            assert_eq!(mm.size(), expected.size());
+            assert_eq!(
+                parallel.membership_queries(),
+                sequential.membership_queries()
+            );
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::cell::{Cell, RefCell};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::str::FromStr;
+
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use automata::core::{Color, Show, math};
+use itertools::Itertools;
+use thiserror::Error;
+use tracing::trace;
+
+use super::{AccessExtensions, Counterexample, EquivalenceOracle, Hypothesis, Oracle};
+
+type Symbol<S> = <<S as SystemUnderLearning>::Alphabet as Alphabet>::Symbol;
+
+/// Errors that can occur while interacting with a [`SystemUnderLearning`].
+#[derive(Debug, Error)]
+pub enum SulError {
+    /// Communication with the system failed.
+    #[error("communication with the system under learning failed: {0}")]
+    Io(#[from] std::io::Error),
+    /// The system terminated or stopped responding before producing an output.
+    #[error("the system under learning terminated unexpectedly")]
+    Terminated,
+    /// The system produced a response that could not be parsed as an output.
+    #[error("could not parse response `{0}` of the system under learning")]
+    InvalidResponse(String),
+}
+
+/// A system that is learned as a black box. It can only be reset to its initial configuration
+/// and then be driven by feeding it one symbol at a time, which produces an output in each
+/// step. The output of the system on a finite word is the output produced by its last symbol.
+pub trait SystemUnderLearning {
+    /// The alphabet of inputs that the system accepts.
+    type Alphabet: Alphabet;
+    /// The type of outputs that the system produces.
+    type Output: Color;
+
+    /// Returns a reference to the input alphabet.
+    fn alphabet(&self) -> &Self::Alphabet;
+
+    /// Resets the system to its initial configuration and returns the output that it produces
+    /// there, which is the output on the empty word.
+    fn reset(&mut self) -> Result<Self::Output, SulError>;
+
+    /// Feeds `symbol` to the system and returns the output that is produced in response.
+    fn step(&mut self, symbol: Symbol<Self>) -> Result<Self::Output, SulError>;
+}
+
+/// An [`Oracle`] that answers membership queries by driving a [`SystemUnderLearning`]. As
+/// running a word produces outputs for all of its prefixes, these are cached as well, such that
+/// no query is posed to the system twice.
+///
+/// Since the system is a black box, equivalence queries can only be approximated. This oracle
+/// uses [`AccessExtensions`] with the configured test depth; other strategies can be used by
+/// wrapping it in a [`BlackBoxOracle`](super::BlackBoxOracle).
+pub struct SulOracle<S: SystemUnderLearning> {
+    alphabet: S::Alphabet,
+    sul: RefCell<S>,
+    cache: RefCell<math::OrderedMap<Vec<Symbol<S>>, S::Output>>,
+    depth: usize,
+    resets: Cell<usize>,
+    steps: Cell<usize>,
+}
+
+impl<S: SystemUnderLearning> SulOracle<S> {
+    /// Creates a new oracle for the given system, which uses a test depth of two.
+    pub fn new(sul: S) -> Self {
+        Self {
+            alphabet: sul.alphabet().clone(),
+            sul: RefCell::new(sul),
+            cache: RefCell::new(math::OrderedMap::default()),
+            depth: 2,
+            resets: Cell::new(0),
+            steps: Cell::new(0),
+        }
+    }
+
+    /// Sets the length of the words by which access words of the hypothesis are extended when an
+    /// equivalence query is approximated.
+    pub fn with_test_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Returns the number of times the system has been reset.
+    pub fn resets(&self) -> usize {
+        self.resets.get()
+    }
+
+    /// Returns the number of symbols that have been fed to the system.
+    pub fn steps(&self) -> usize {
+        self.steps.get()
+    }
+
+    /// Returns the output of the system on `word`, driving the system if it is not cached.
+    pub fn try_output(&self, word: &[Symbol<S>]) -> Result<S::Output, SulError> {
+        if let Some(output) = self.cache.borrow().get(word) {
+            return Ok(output.clone());
+        }
+
+        trace!("driving system under learning on {}", word.as_string());
+        let mut sul = self.sul.borrow_mut();
+        let mut cache = self.cache.borrow_mut();
+        self.resets.set(self.resets.get() + 1);
+        let mut output = sul.reset()?;
+        cache.insert(vec![], output.clone());
+        for i in 0..word.len() {
+            output = sul.step(word[i])?;
+            self.steps.set(self.steps.get() + 1);
+            cache.insert(word[..=i].to_vec(), output.clone());
+        }
+        Ok(output)
+    }
+}
+
+impl<S: SystemUnderLearning> Oracle for SulOracle<S> {
+    type Alphabet = S::Alphabet;
+    type Output = S::Output;
+
+    fn alphabet(&self) -> &Self::Alphabet {
+        &self.alphabet
+    }
+
+    fn output<W: FiniteWord<Symbol = Symbol<S>>>(&self, word: W) -> Self::Output {
+        match self.try_output(&word.symbols().collect_vec()) {
+            Ok(output) => output,
+            Err(e) => panic!("could not query system under learning: {e}"),
+        }
+    }
+
+    fn output_sequence(&self, word: &[Symbol<S>]) -> Vec<Self::Output> {
+        // a single run caches the outputs on all prefixes
+        self.output(word);
+        let cache = self.cache.borrow();
+        (1..=word.len())
+            .map(|i| cache[&word[..i]].clone())
+            .collect()
+    }
+
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        match AccessExtensions::new(self.depth)
+            .find_counterexample(hypothesis, &|word| self.output(word))
+        {
+            Some(counterexample) => Err(counterexample),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A [`SystemUnderLearning`] that runs as a local subprocess and communicates over a line-based
+/// protocol on its standard input and output. To reset the system, the reset command (by default
+/// `reset`) is written as a line, and to feed a symbol, its [`Show`] representation is written
+/// as a line. In both cases, the process must respond with a single line that contains the output,
+/// which is parsed with [`FromStr`].
+pub struct SubprocessSul<A: Alphabet, O> {
+    alphabet: A,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    reset_command: String,
+    _output: std::marker::PhantomData<O>,
+}
+
+impl<A: Alphabet, O> SubprocessSul<A, O> {
+    /// Spawns the given command with piped standard input and output and uses it as a system
+    /// over the given alphabet.
+    pub fn spawn(mut command: Command, alphabet: A) -> Result<Self, SulError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or(SulError::Terminated)?;
+        let stdout = BufReader::new(child.stdout.take().ok_or(SulError::Terminated)?);
+        Ok(Self {
+            alphabet,
+            child,
+            stdin,
+            stdout,
+            reset_command: "reset".to_string(),
+            _output: std::marker::PhantomData,
+        })
+    }
+
+    /// Sets the line that is sent to the process to reset it.
+    pub fn with_reset_command(mut self, command: impl Into<String>) -> Self {
+        self.reset_command = command.into();
+        self
+    }
+
+    fn exchange(&mut self, line: &str) -> Result<O, SulError>
+    where
+        O: FromStr,
+    {
+        writeln!(self.stdin, "{line}")?;
+        self.stdin.flush()?;
+        let mut response = String::new();
+        if self.stdout.read_line(&mut response)? == 0 {
+            return Err(SulError::Terminated);
+        }
+        let response = response.trim();
+        response
+            .parse()
+            .map_err(|_| SulError::InvalidResponse(response.to_string()))
+    }
+}
+
+impl<A: Alphabet, O: Color + FromStr> SystemUnderLearning for SubprocessSul<A, O> {
+    type Alphabet = A;
+    type Output = O;
+
+    fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+
+    fn reset(&mut self) -> Result<O, SulError> {
+        let command = std::mem::take(&mut self.reset_command);
+        let output = self.exchange(&command);
+        self.reset_command = command;
+        output
+    }
+
+    fn step(&mut self, symbol: A::Symbol) -> Result<O, SulError> {
+        self.exchange(&symbol.show())
+    }
+}
+
+impl<A: Alphabet, O> Drop for SubprocessSul<A, O> {
+    fn drop(&mut self) {
+        if let Err(e) = self.child.kill() {
+            trace!("could not kill subprocess: {e}");
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::{SubprocessSul, SulError, SulOracle, SystemUnderLearning};
+    use crate::active::{LStar, Oracle};
+    use automata::TransitionSystem;
+    use automata::automaton::DFA;
+    use automata::core::alphabet::CharAlphabet;
+
+    // accepts the words over {a, b} whose number of `a`s is divisible by three
+    const SCRIPT: &str = r#"
+        count=0
+        while read line; do
+            case "$line" in
+                reset) count=0 ;;
+                a) count=$(( (count + 1) % 3 )) ;;
+                b) ;;
+                *) echo error; continue ;;
+            esac
+            if [ "$count" -eq 0 ]; then echo true; else echo false; fi
+        done
+    "#;
+
+    fn spawn() -> SubprocessSul<CharAlphabet, bool> {
+        let mut command = Command::new("sh");
+        command.args(["-c", SCRIPT]);
+        SubprocessSul::spawn(command, CharAlphabet::of_size(2)).unwrap()
+    }
+
+    #[test]
+    fn subprocess_sul() {
+        let mut sul = spawn();
+        assert!(sul.reset().unwrap());
+        assert!(!sul.step('a').unwrap());
+        assert!(!sul.step('b').unwrap());
+        assert!(!sul.step('a').unwrap());
+        assert!(sul.step('a').unwrap());
+        assert!(matches!(
+            sul.step('c'),
+            Err(SulError::InvalidResponse(response)) if response == "error"
+        ));
+    }
+
+    #[test]
+    fn learn_subprocess() {
+        let oracle = SulOracle::new(spawn());
+        assert!(!oracle.output("abab"));
+        assert!(oracle.output("aaab"));
+        // all prefixes of the queried words are cached
+        assert_eq!(oracle.resets(), 2);
+        assert!(oracle.output("aaa"));
+        assert!(!oracle.output("ab"));
+        assert_eq!(oracle.resets(), 2);
+
+        let alphabet = oracle.alphabet().clone();
+        let mut learner = LStar::new(alphabet, oracle);
+        let dfa: DFA = learner.infer();
+        assert_eq!(dfa.size(), 3);
+        assert!(dfa.accepts("babaa"));
+        assert!(!dfa.accepts("bab"));
+    }
+}
@@ -1,8 +1,11 @@
-use automata::automaton::{DBA, DFA, DPA, MealyMachine};
-use automata::core::alphabet::{Alphabet, CharAlphabet};
+use automata::automaton::{Automaton, DBA, DFA, DPA, MealyMachine, MooreMachine, WithInitial};
+use automata::core::alphabet::{Alphabet, CharAlphabet, Expression};
+use automata::core::word::{OmegaWord, ReducedOmegaWord};
+use automata::core::{Color, Int, Void, math};
 use automata::representation::{CollectTs, IntoTs};
-use automata::ts::Deterministic;
 use automata::ts::operations::Product;
+use automata::ts::{Deterministic, IsEdge, Sproutable, StateIndex};
+use automata::{DTS, Pointed};
 use automata::{
     TransitionSystem,
     ts::{
@@ -10,14 +13,18 @@ use automata::{
         operations::{DefaultIfMissing, MapStateColor},
     },
 };
+use itertools::Itertools;
 use owo_colors::OwoColorize;
+use std::collections::VecDeque;
 use tracing::{debug, trace};
 
 /// Contains definitions for samples, which are collections of positive and
 /// negative example words.
 #[macro_use]
 pub mod sample;
-pub use sample::{ClassOmegaSample, PeriodicOmegaSample, SetSample, SplitOmegaSample};
+pub use sample::{
+    ClassOmegaSample, PeriodicOmegaSample, SampleAlphabet, SetSample, SplitOmegaSample,
+};
 
 use crate::{
     AnnotatedCongruence,
@@ -26,13 +33,21 @@ use crate::{
     prefixtree::prefix_tree,
 };
 
+use self::dpainf::DpaInfError;
 use self::precise::PreciseDPA;
+use self::sprout::{buchi_rejecting_transitions, to_infinity_sets};
 
-pub use self::sample::{FiniteSample, OmegaSample};
+pub use self::sample::{FiniteSample, OmegaSample, OutputSample};
 
 /// Module containing the implementations of the sprout/glerc algorithm.
 pub mod dpainf;
 
+/// Blue-fringe evidence-driven state merging for learning DFAs.
+pub mod edsm;
+
+/// Exact identification of minimal automata via SAT solving.
+pub mod exact;
+
 /// Deals with families of weak priority mappings.
 pub mod fwpm;
 
@@ -61,9 +76,194 @@ pub fn dfa_rpni<A: Alphabet>(sample: &FiniteSample<A>) -> DFA<A> {
         .into_dfa()
 }
 
+/// Works like [`dfa_rpni`], but on a sample of words that are annotated with arbitrary outputs.
+/// The result is a [`MooreMachine`], in which each class is colored with the output of the sample
+/// words reaching it. Classes that are not reached by any sample word obtain the default color.
+pub fn moore_rpni<A: Alphabet, C: Color + Default>(
+    sample: &OutputSample<A, C>,
+) -> MooreMachine<A, C> {
+    let cong = dpainf::dpainf(sample, vec![], true, None).unwrap();
+    let colors = sample
+        .entries()
+        .map(|(w, c)| {
+            (
+                cong.reached_state_index(w)
+                    .expect("Every sample word must induce a successful run!"),
+                c.clone(),
+            )
+        })
+        .collect();
+    cong.with_state_color(DefaultIfMissing::new(colors, C::default()))
+        .into_moore()
+}
+
 /// Executes a variant of the RPNI algorithm for omega-words, producing a DBA.
+///
+/// Starting from the prefix tree of the sample, states are considered in breadth-first order.
+/// Each state is merged into the first state of the already fixed (red) part with which it can be
+/// merged, such that the quotient stays consistent with the sample, i.e. there is a Büchi
+/// condition under which it accepts all positive and rejects all negative words (see
+/// [`BuchiCondition`](automata::automaton::BuchiCondition) for the check used by sprout). If no such state exists,
+/// the state itself becomes red. Merging states may require the successors of both to be merged as
+/// well, which is done until the quotient is deterministic again.
+///
+/// As the prefix tree itself is consistent with the sample, and only consistent merges are
+/// performed, the resulting DBA is guaranteed to accept all positive and reject all negative
+/// sample words. It is completed with a rejecting sink. The number of consistency checks is
+/// quadratic in the size of the prefix tree.
 pub fn dba_rpni<A: Alphabet>(sample: &OmegaSample<A>) -> DBA<A> {
-    todo!()
+    let pta = sample.prefix_tree();
+    let mut order = vec![pta.initial()];
+    let mut index = math::Map::from_iter([(pta.initial(), 0)]);
+    let mut successors: Vec<math::OrderedMap<A::Expression, usize>> = vec![];
+    let mut i = 0;
+    while i < order.len() {
+        let mut out = math::OrderedMap::default();
+        for edge in pta.edges_from(order[i]).expect("state must exist") {
+            let next = index.len();
+            let target = *index.entry(edge.target()).or_insert_with(|| {
+                order.push(edge.target());
+                next
+            });
+            out.insert(edge.expression().clone(), target);
+        }
+        successors.push(out);
+        i += 1;
+    }
+
+    let mut current = Merger::new(successors);
+    let mut red = vec![0];
+    for q in 1..current.parent.len() {
+        if current.find(q) != q {
+            continue;
+        }
+        let merged = red.iter().find_map(|p| {
+            let mut candidate = current.clone();
+            candidate.merge(*p, q);
+            let quotient = candidate.quotient(sample.alphabet());
+            trace!("attempting to merge {q} into {p}");
+            buchi_rejecting(&quotient, sample).map(|_| candidate)
+        });
+        match merged {
+            Some(candidate) => current = candidate,
+            None => red.push(q),
+        }
+    }
+    debug!("dba_rpni found {} red states", red.len());
+
+    let quotient = current.quotient(sample.alphabet());
+    let rejecting = buchi_rejecting(&quotient, sample).expect("only consistent merges are made");
+    let mut dba = quotient
+        .map_edge_colors_full(|q, e, _, _| !rejecting.contains(&(q, e.clone())))
+        .erase_state_colors()
+        .collect_dba();
+
+    // complete with a rejecting sink
+    let alphabet = sample.alphabet();
+    let missing = dba
+        .state_indices_vec()
+        .into_iter()
+        .flat_map(|q| alphabet.universe().map(move |sym| (q, sym)))
+        .filter(|(q, sym)| dba.successor_index(*q, *sym).is_none())
+        .collect_vec();
+    if !missing.is_empty() {
+        let sink = dba.add_state(Void);
+        for (q, sym) in missing
+            .into_iter()
+            .chain(alphabet.universe().map(|sym| (sink, sym)))
+        {
+            dba.add_edge((q, alphabet.make_expression(sym), false, sink));
+        }
+    }
+    dba
+}
+
+/// Checks whether `ts` is consistent with `sample` under some Büchi condition and if so,
+/// returns the transitions that must not be accepting, which are those that a negative word
+/// takes infinitely often. This is the consistency check of sprout for
+/// [`BuchiCondition`](automata::automaton::BuchiCondition), which fails if a positive word only
+/// takes such transitions infinitely often, or if a positive and a negative word leave `ts` from
+/// the same state with the same remaining suffix.
+fn buchi_rejecting<A: Alphabet>(
+    ts: &WithInitial<DTS<A>>,
+    sample: &OmegaSample<A>,
+) -> Option<math::OrderedSet<(u32, A::Expression)>> {
+    let [pos_sets, neg_sets] = to_infinity_sets(ts, sample)?;
+    let rejecting = buchi_rejecting_transitions(&pos_sets, &neg_sets)?;
+    Some(
+        rejecting
+            .into_iter()
+            .map(|(q, sym)| (q, sample.alphabet().make_expression(sym)))
+            .collect(),
+    )
+}
+
+/// Keeps track of the states of a prefix tree that have been merged by [`dba_rpni`]. States are
+/// identified by their position in breadth-first order, and each class is represented by its
+/// minimal element.
+#[derive(Clone)]
+struct Merger<E> {
+    parent: Vec<usize>,
+    /// The successors of each representative, where the targets need not be representatives.
+    successors: Vec<math::OrderedMap<E, usize>>,
+}
+
+impl<E: Expression> Merger<E> {
+    fn new(successors: Vec<math::OrderedMap<E, usize>>) -> Self {
+        Self {
+            parent: (0..successors.len()).collect(),
+            successors,
+        }
+    }
+
+    fn find(&mut self, mut q: usize) -> usize {
+        while self.parent[q] != q {
+            self.parent[q] = self.parent[self.parent[q]];
+            q = self.parent[q];
+        }
+        q
+    }
+
+    /// Merges the classes of `p` and `q` and afterwards folds the successors, i.e. whenever both
+    /// classes have a successor on the same symbol, these successors are merged as well.
+    fn merge(&mut self, p: usize, q: usize) {
+        let mut pending = vec![(p, q)];
+        while let Some((p, q)) = pending.pop() {
+            let (p, q) = (self.find(p), self.find(q));
+            if p == q {
+                continue;
+            }
+            let (keep, drop) = (p.min(q), p.max(q));
+            self.parent[drop] = keep;
+            for (sym, target) in std::mem::take(&mut self.successors[drop]) {
+                match self.successors[keep].get(&sym) {
+                    Some(existing) => pending.push((*existing, target)),
+                    None => {
+                        self.successors[keep].insert(sym, target);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds the quotient of the prefix tree, whose initial state is the class of the root.
+    fn quotient<A: Alphabet<Expression = E>>(&mut self, alphabet: &A) -> WithInitial<DTS<A>> {
+        let mut ts = Automaton::new_with_initial_color(alphabet.clone(), Void);
+        let representatives: Vec<_> = (0..self.parent.len())
+            .filter(|q| self.parent[*q] == *q)
+            .collect();
+        let index: math::Map<usize, u32> = representatives
+            .iter()
+            .map(|q| (*q, if *q == 0 { 0 } else { ts.add_state(Void) }))
+            .collect();
+        for q in representatives {
+            for (sym, target) in self.successors[q].clone() {
+                let target = index[&self.find(target)];
+                ts.add_edge((index[&q], sym, target));
+            }
+        }
+        ts
+    }
 }
 
 /// Takes a reference to an [`OmegaSample`], which classifies infinite words over the alphabet `A`
@@ -78,7 +278,15 @@ pub fn dba_rpni<A: Alphabet>(sample: &OmegaSample<A>) -> DBA<A> {
 pub fn infer_precise_dpa<A: Alphabet>(
     sample: &OmegaSample<A>,
 ) -> PreciseDPA<A, { precise::PRECISE_DPA_COLORS }> {
-    let cong = sample.infer_prefix_congruence().unwrap();
+    infer_fwpm(sample).unwrap().into_precise_dpa()
+}
+
+/// Infers the [`FWPM`] from which [`infer_precise_dpa`] builds its DPA, that is the leading
+/// prefix congruence of `sample` together with a canonic coloring of the inferred progress
+/// congruence for each of its classes. Fails if the leading congruence cannot be inferred.
+#[allow(clippy::result_large_err)]
+pub fn infer_fwpm<A: Alphabet>(sample: &OmegaSample<A>) -> Result<FWPM<A>, DpaInfError<A>> {
+    let cong = sample.infer_prefix_congruence()?;
     let split = sample.split(&cong);
 
     let forc = split.infer_forc();
@@ -86,8 +294,8 @@ pub fn infer_precise_dpa<A: Alphabet>(
 
     let mut fwpm = FWPM::empty(cong.clone());
     for (class, idx) in cong.classes() {
-        let periodic_sample = split.get(idx).expect("Must exist!").to_periodic_sample();
-        let annotated_prc = AnnotatedCongruence::build(&forc[idx], &periodic_sample);
+        let class_sample = split.get(idx).expect("Must exist!");
+        let annotated_prc = AnnotatedCongruence::build(&forc[idx], class_sample);
         trace!(
             "{} for class {:?}\t{:?}",
             "ANNOTATED CONGRUENCE".bold().blue(),
@@ -99,7 +307,7 @@ pub fn infer_precise_dpa<A: Alphabet>(
         fwpm.insert_pm(idx, coloring);
     }
     trace!("Calculated the FWPM\n{:?}", fwpm);
-    fwpm.into_precise_dpa()
+    Ok(fwpm)
 }
 
 /// Similar to [`dba_rpni`], but produces a DPA instead.
@@ -128,23 +336,407 @@ pub fn dpa_rpni(sample: &OmegaSample<CharAlphabet>) -> DPA {
     learned.collect_dpa()
 }
 
-fn characterize_dpa(dpa: DPA) -> OmegaSample {
-    let cong = dpa.prefix_congruence();
+/// Computes a characteristic sample for the given `dpa`, from which [`dpa_rpni`] learns a DPA
+/// that is language-equivalent to `dpa`. The sample mirrors the steps of [`dpa_rpni`].
+/// - For the leading congruence, it contains the words `u_p w` and `u_q a w` for every transition
+///   from the class `u_q` on `a` and every class `u_p` that differs from its target, where `w`
+///   separates the two. Then [`OmegaSample::infer_prefix_congruence`] yields the prefix
+///   congruence of `dpa`, just like [`characterize_moore`](sample::characterize_moore) does for
+///   Moore machines.
+/// - For the progress congruence of each class `u`, the same is done for the syntactic progress
+///   congruence: with `x_p` ranging over its minimal representatives and `z` over the least words
+///   separating two of its classes, the sample contains the words `u (x_p z)^ω` and
+///   `u (x_q a z)^ω`, as well as `u (x_p)^ω` for every class that loops on `u`. This determines
+///   the FORC and the annotation of its idempotents, and thereby the precise DPA.
+/// - Finally, it contains an extension of each word that [`LStar`] exchanges with an oracle for
+///   the precise DPA, see [`sample::actively_exchanged_words_mealy`]. So the run of [`LStar`]
+///   in [`dpa_rpni`], which may only consult the prefix tree of the sample, takes the same course.
+///
+/// The size of the sample is polynomial in the size of the syntactic FORC of the language, which
+/// may however be exponentially larger than `dpa`.
+pub fn characterize_dpa(dpa: &DPA) -> OmegaSample {
+    let mut sample = OmegaSample::new_for_alphabet(dpa.alphabet().clone());
+    let mut insert = |sample: &mut OmegaSample, word: ReducedOmegaWord<char>| {
+        let accepted = dpa.accepts(&word);
+        sample.insert(word, accepted);
+    };
 
-    todo!()
+    // one state for each prefix class, in the order of their minimal representatives
+    let mut representatives: Vec<(Vec<char>, StateIndex<DPA>)> = vec![];
+    for rep in dpa.minimal_representatives_iter() {
+        let q = rep.state_index();
+        if representatives
+            .iter()
+            .all(|(_, p)| dpa.separate(*p, q).is_some())
+        {
+            representatives.push((rep.into_inner(), q));
+        }
+    }
+    let class_of: math::Map<_, _> = dpa
+        .state_indices()
+        .map(|q| {
+            let class = representatives
+                .iter()
+                .position(|(_, p)| dpa.separate(*p, q).is_none())
+                .expect("every state must belong to a class");
+            (q, class)
+        })
+        .collect();
+
+    for (u, q) in &representatives {
+        for a in dpa.symbols() {
+            let ua = u.iter().copied().chain([a]).collect_vec();
+            let target = dpa.successor_index(*q, a).expect("DPA must be complete");
+            for (v, p) in &representatives {
+                let Some(suffix) = dpa.separate(*p, target) else {
+                    continue;
+                };
+                for prefix in [v, &ua] {
+                    let spoke = prefix.iter().chain(suffix.spoke()).copied().collect_vec();
+                    insert(
+                        &mut sample,
+                        ReducedOmegaWord::ultimately_periodic(spoke, suffix.cycle()),
+                    );
+                }
+            }
+        }
+    }
+
+    let profiles = TransitionProfiles::new(dpa);
+    for (class, (u, q)) in representatives.iter().enumerate() {
+        let output = profiles.outputs(dpa, *q, |p| class_of[&p]);
+        let loops = |x: &[char]| {
+            !x.is_empty()
+                && class_of[&dpa.with_initial(*q).reached_state_index(x).unwrap()] == class
+        };
+
+        // one profile for each class of the syntactic progress congruence
+        let block = profiles.refine(&output);
+        let mut progress: Vec<usize> = vec![];
+        for (i, b) in block.iter().enumerate() {
+            if *b == progress.len() {
+                progress.push(i);
+            }
+        }
+
+        for i in &progress {
+            let x = &profiles.words[*i];
+            if loops(x) {
+                insert(&mut sample, ReducedOmegaWord::ultimately_periodic(u, x));
+            }
+            for (a, sym) in dpa.symbols().enumerate() {
+                let xa = x.iter().copied().chain([sym]).collect_vec();
+                let target = profiles.successors[*i][a];
+                for j in &progress {
+                    let Some(z) = profiles.separate(&output, &block, *j, target) else {
+                        continue;
+                    };
+                    for prefix in [&profiles.words[*j], &xa] {
+                        let cycle = prefix.iter().chain(&z).copied().collect_vec();
+                        if loops(&cycle) {
+                            insert(&mut sample, ReducedOmegaWord::ultimately_periodic(u, cycle));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let precise = infer_precise_dpa(&sample).collect_dpa();
+    let pta = sample.prefix_tree();
+    let first = dpa.symbols().next().expect("alphabet must not be empty");
+    for (word, _) in sample::actively_exchanged_words_mealy(&precise).entries() {
+        if pta.reached_state_index(word.as_slice()).is_none() {
+            insert(
+                &mut sample,
+                ReducedOmegaWord::ultimately_periodic(word.as_slice(), [first]),
+            );
+        }
+    }
+    debug!(
+        "characteristic sample for DPA of size {} has {} words",
+        dpa.size(),
+        sample.count_words()
+    );
+    sample
+}
+
+/// The transition profiles of a [`DPA`], which are reachable from the profile of the empty
+/// word. The profile of a word `x` maps each state `q` to the state that is reached on `x` from
+/// `q` and the least priority that is seen along the way. So it captures everything that is
+/// relevant about `x` when it is iterated.
+struct TransitionProfiles {
+    symbols: Vec<char>,
+    profiles: Vec<Vec<(StateIndex<DPA>, Int)>>,
+    words: Vec<Vec<char>>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl TransitionProfiles {
+    /// Explores the profiles in breadth-first order, so each profile is associated with the
+    /// length-lexicographically least word that has it.
+    fn new(dpa: &DPA) -> Self {
+        let identity = dpa.state_indices().map(|q| (q, Int::MAX)).collect_vec();
+        let mut out = Self {
+            symbols: dpa.symbols().collect(),
+            profiles: vec![identity],
+            words: vec![vec![]],
+            successors: vec![],
+        };
+        let mut index: math::Map<_, _> = math::Map::from_iter([(out.profiles[0].clone(), 0)]);
+
+        let mut i = 0;
+        while i < out.profiles.len() {
+            let mut successors = vec![];
+            for sym in out.symbols.clone() {
+                let next = out.profiles[i]
+                    .iter()
+                    .map(|(q, k)| {
+                        let edge = dpa.edge(*q, sym).expect("DPA must be complete");
+                        (edge.target(), (*k).min(IsEdge::color(&edge)))
+                    })
+                    .collect_vec();
+                let j = *index.entry(next.clone()).or_insert_with(|| {
+                    let mut word = out.words[i].clone();
+                    word.push(sym);
+                    out.profiles.push(next);
+                    out.words.push(word);
+                    out.profiles.len() - 1
+                });
+                successors.push(j);
+            }
+            out.successors.push(successors);
+            i += 1;
+        }
+        out
+    }
+
+    fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    /// Computes what is observable about each profile from the state `q`, namely the class that
+    /// it leads to and, for words that loop on the class of `q`, whether iterating them from `q`
+    /// is accepting.
+    fn outputs<F: Fn(StateIndex<DPA>) -> usize>(
+        &self,
+        dpa: &DPA,
+        q: StateIndex<DPA>,
+        class_of: F,
+    ) -> Vec<(usize, Option<bool>)> {
+        let position: math::Map<_, _> = dpa
+            .state_indices()
+            .enumerate()
+            .map(|(i, q)| (q, i))
+            .collect();
+        self.profiles
+            .iter()
+            .enumerate()
+            .map(|(i, profile)| {
+                let (reached, _) = profile[position[&q]];
+                if i == 0 || class_of(reached) != class_of(q) {
+                    return (class_of(reached), None);
+                }
+                // iterate the profile from q until a state repeats, the least priority on the
+                // resulting cycle is the one that is seen infinitely often
+                let mut run = vec![q];
+                loop {
+                    let current = *run.last().unwrap();
+                    let (next, _) = profile[position[&current]];
+                    if let Some(start) = run.iter().position(|p| *p == next) {
+                        let least = run[start..]
+                            .iter()
+                            .map(|p| profile[position[p]].1)
+                            .min()
+                            .expect("cycle cannot be empty");
+                        return (class_of(reached), Some(least % 2 == 0));
+                    }
+                    run.push(next);
+                }
+            })
+            .collect()
+    }
+
+    /// Partitions the profiles into the classes of the coarsest congruence that refines `output`,
+    /// which is computed by Moore's algorithm. The classes are numbered in the order in which
+    /// their first profile is explored.
+    fn refine(&self, output: &[(usize, Option<bool>)]) -> Vec<usize> {
+        fn number<S: std::hash::Hash + Eq>(signatures: impl Iterator<Item = S>) -> Vec<usize> {
+            let mut index = math::Map::default();
+            signatures
+                .map(|signature| {
+                    let next = index.len();
+                    *index.entry(signature).or_insert(next)
+                })
+                .collect()
+        }
+
+        let mut block = number(output.iter());
+        loop {
+            let refined = number((0..self.len()).map(|i| {
+                let successors = self.successors[i].iter().map(|j| block[*j]).collect_vec();
+                (block[i], successors)
+            }));
+            if refined.iter().max() == block.iter().max() {
+                return refined;
+            }
+            block = refined;
+        }
+    }
+
+    /// Returns the least word on which the profiles `i` and `j` lead to different outputs, or
+    /// `None` if there is no such word. As `block` is a congruence, it suffices to explore pairs
+    /// of its classes.
+    fn separate(
+        &self,
+        output: &[(usize, Option<bool>)],
+        block: &[usize],
+        i: usize,
+        j: usize,
+    ) -> Option<Vec<char>> {
+        let mut seen = math::Set::from_iter([(block[i], block[j])]);
+        let mut queue = VecDeque::from([(i, j, vec![])]);
+        while let Some((i, j, word)) = queue.pop_front() {
+            if output[i] != output[j] {
+                return Some(word);
+            }
+            for (a, sym) in self.symbols.iter().enumerate() {
+                let (p, q) = (self.successors[i][a], self.successors[j][a]);
+                if seen.insert((block[p], block[q])) {
+                    let mut extended = word.clone();
+                    extended.push(*sym);
+                    queue.push_back((p, q, extended));
+                }
+            }
+        }
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::passive::dpa_rpni;
     use automata::TransitionSystem;
-    use automata::core::alphabet::CharAlphabet;
+    use automata::core::alphabet::{CharAlphabet, NamedAlphabet};
     use automata::core::upw;
+    use automata::core::word::ReducedOmegaWord;
+    use automata::random::{generate_random_dba, generate_random_dpa, generate_random_omega_words};
     use automata::representation::CollectTs;
+    use automata::ts::TSBuilder;
     use tracing::info;
 
     use super::{OmegaSample, sample};
 
+    #[test]
+    fn characterize_dpa() {
+        // the least priority seen infinitely often is 0 if infinitely many `a`s appear at even
+        // positions, which is not a property of the suffix alone
+        let even_a = TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 0, 1),
+                (0, 'b', 1, 1),
+                (1, 'a', 1, 0),
+                (1, 'b', 2, 0),
+            ])
+            .into_dpa(0);
+        let inf_aa = TSBuilder::without_state_colors()
+            .with_edges([
+                (0, 'a', 1, 1),
+                (0, 'b', 1, 0),
+                (1, 'a', 0, 1),
+                (1, 'b', 1, 0),
+            ])
+            .into_dpa(0);
+        for target in [even_a, inf_aa] {
+            let sample = super::characterize_dpa(&target);
+            assert!(dpa_rpni(&sample).language_equivalent(&target));
+        }
+
+        for size in 1..=4 {
+            for _ in 0..10 {
+                let target = generate_random_dpa(2, size, 4, 0.1);
+                let sample = super::characterize_dpa(&target);
+                let learned = dpa_rpni(&sample);
+                assert!(
+                    learned.language_equivalent(&target),
+                    "learned {learned:?} for {target:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dba_rpni_infinitely_many_a() {
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            CharAlphabet::of_size(2),
+            [upw!("a"), upw!("ab"), upw!("b", "a"), upw!("bba", "ab")],
+            [upw!("b"), upw!("a", "b"), upw!("ab", "b"), upw!("aab", "b")],
+        );
+        let dba = super::dba_rpni(&sample);
+        for w in sample.positive_words() {
+            assert!(dba.accepts(w), "{w:?} should be accepted");
+        }
+        for w in sample.negative_words() {
+            assert!(!dba.accepts(w), "{w:?} should be rejected");
+        }
+        assert!(dba.is_complete());
+        assert!(dba.accepts(upw!("bbbbab")));
+        assert!(!dba.accepts(upw!("abab", "b")));
+    }
+
+    #[test]
+    fn dba_rpni_over_named_alphabet() {
+        // infinitely many `grant`s
+        let alphabet = NamedAlphabet::new(vec!["req".to_string(), "grant".to_string()]);
+        let word = |spoke: &str, cycle: &str| {
+            ReducedOmegaWord::try_from_str_over(&format!("{spoke}, {cycle}"), &alphabet).unwrap()
+        };
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            alphabet.clone(),
+            [
+                word("", "grant"),
+                word("", "req grant"),
+                word("req", "grant"),
+                word("req req grant", "grant req"),
+            ],
+            [
+                word("", "req"),
+                word("grant", "req"),
+                word("grant req", "req"),
+                word("grant grant req", "req"),
+            ],
+        );
+        let dba = super::dba_rpni(&sample);
+        for w in sample.positive_words() {
+            assert!(dba.accepts(w), "{w:?} should be accepted");
+        }
+        for w in sample.negative_words() {
+            assert!(!dba.accepts(w), "{w:?} should be rejected");
+        }
+        assert!(dba.accepts(word("req req req req grant", "req grant")));
+    }
+
+    #[test]
+    fn dba_rpni_on_generated_tasks() {
+        // mirrors how the omega-learning-tasks binary generates and labels its training sets
+        let alphabet = CharAlphabet::of_size(2);
+        for size in 2..=4 {
+            let target = generate_random_dba(2, size, 0.5);
+            let words = generate_random_omega_words(&alphabet, 0, 6, 1, 6, 60);
+            let (positive, negative): (Vec<_>, Vec<_>) =
+                words.into_iter().partition(|w| target.accepts(w));
+            let sample = OmegaSample::new_omega_from_pos_neg(alphabet.clone(), positive, negative);
+
+            let learned = super::dba_rpni(&sample);
+            for w in sample.positive_words() {
+                assert!(learned.accepts(w), "{w:?} should be accepted");
+            }
+            for w in sample.negative_words() {
+                assert!(!learned.accepts(w), "{w:?} should be rejected");
+            }
+        }
+    }
+
     #[test]
     fn infer_precise_dpa_with_al_inf_aa() {
         let alphabet = CharAlphabet::of_size(3);
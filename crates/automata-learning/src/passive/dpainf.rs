@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeSet, VecDeque},
+    collections::{BTreeSet, HashMap, VecDeque, hash_map::Entry},
     fmt::Display,
 };
 
@@ -8,13 +8,15 @@ use itertools::Itertools;
 use tracing::{error, trace, warn};
 
 use crate::{
-    passive::{ClassOmegaSample, FiniteSample, OmegaSample, SetSample, SplitOmegaSample},
-    prefixtree::prefix_tree,
+    passive::{
+        ClassOmegaSample, FiniteSample, OmegaSample, OutputSample, SetSample, SplitOmegaSample,
+    },
+    prefixtree::{periodic_prefix_tree, prefix_tree},
 };
 
 use automata::core::alphabet::Alphabet;
 use automata::core::word::FiniteWord;
-use automata::core::{Show, Void, math};
+use automata::core::{Color, Show, Void, math};
 use automata::representation::{CollectTs, IntoTs};
 use automata::ts::operations::Product;
 use automata::ts::predecessors::PredecessorIterable;
@@ -49,19 +51,16 @@ impl<A: Alphabet, CC: ConsistencyCheck<A>> ConsistencyCheck<A> for &CC {
 
 impl<A: Alphabet> ConsistencyCheck<A> for FiniteSample<A> {
     fn consistent(&self, cong: &RightCongruence<A>) -> bool {
-        let positive_indices: math::OrderedSet<_> = self
-            .positive_words()
-            .filter_map(|w| cong.reached_state_index(w))
-            .collect();
-        let negative_indices: math::OrderedSet<_> = self
-            .negative_words()
-            .filter_map(|w| cong.reached_state_index(w))
-            .collect();
-        positive_indices.is_disjoint(&negative_indices)
+        consistent_runs(
+            cong,
+            self.entries().map(|(w, positive)| (w.as_slice(), positive)),
+        )
     }
 
+    /// Each state that is added corresponds to a distinct prefix of a sample word, so the number
+    /// of states never exceeds the total length of the sample words plus one.
     fn threshold(&self) -> usize {
-        self.max_word_len() * 2
+        self.words().map(|w| w.len()).sum::<usize>() + 1
     }
 
     fn alphabet(&self) -> &A {
@@ -69,6 +68,59 @@ impl<A: Alphabet> ConsistencyCheck<A> for FiniteSample<A> {
     }
 }
 
+impl<A: Alphabet, C: Color> ConsistencyCheck<A> for OutputSample<A, C> {
+    /// Verifies that `cong` does not merge two words with different outputs, where the output of
+    /// a word is understood as the color of the state that it reaches, as in a Moore machine.
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        consistent_runs(cong, self.entries().map(|(w, c)| (w.as_slice(), c)))
+    }
+
+    fn threshold(&self) -> usize {
+        self.entries().map(|(w, _)| w.len()).sum::<usize>() + 1
+    }
+
+    fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+}
+
+/// Checks that no two of the given words with different outputs are necessarily merged by every
+/// extension of `cong`. Each word is run as far as possible, and two words conflict if they reach
+/// the same state with the same remaining suffix, as their runs coincide from there on. For
+/// words whose run is successful, the remaining suffix is empty, so in particular no two words
+/// with different outputs may reach the same class.
+fn consistent_runs<'a, A: Alphabet, C: Eq>(
+    cong: &RightCongruence<A>,
+    words: impl IntoIterator<Item = (&'a [A::Symbol], C)>,
+) -> bool
+where
+    A::Symbol: 'a,
+{
+    let mut reached: HashMap<(StateIndex, &[A::Symbol]), C> = HashMap::default();
+    for (word, output) in words {
+        let mut state = cong.initial();
+        let mut read = 0;
+        while let Some(next) = word
+            .get(read)
+            .and_then(|sym| cong.successor_index(state, *sym))
+        {
+            state = next;
+            read += 1;
+        }
+        match reached.entry((state, &word[read..])) {
+            Entry::Occupied(existing) => {
+                if existing.get() != &output {
+                    return false;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(output);
+            }
+        }
+    }
+    true
+}
+
 /// Stores two DFAs and a math::Set of conflicts between them.
 #[derive(Clone)]
 pub struct ConflictRelation<A: Alphabet> {
@@ -151,7 +203,7 @@ pub fn iteration_consistency_conflicts<A: Alphabet>(
 
     let looping_words = samples.cong().looping_words(idx);
 
-    let left_pta = prefix_tree(sample.alphabet.clone(), periodic_sample.positive())
+    let left_pta = periodic_prefix_tree(sample.alphabet.clone(), periodic_sample.positive())
         .map_state_colors(|mr| {
             !mr.is_empty() && periodic_sample.classify(mr.omega_power()) == Some(true)
         })
@@ -159,7 +211,7 @@ pub fn iteration_consistency_conflicts<A: Alphabet>(
         .intersection(&looping_words)
         .collect_dfa();
 
-    let right_pta = prefix_tree(sample.alphabet.clone(), periodic_sample.negative())
+    let right_pta = periodic_prefix_tree(sample.alphabet.clone(), periodic_sample.negative())
         .map_state_colors(|mr| {
             !mr.is_empty() && periodic_sample.classify(mr.omega_power()) == Some(false)
         })
@@ -229,33 +281,37 @@ pub fn prefix_consistency_conflicts<A: Alphabet>(sample: &OmegaSample<A>) -> Con
     let dfa = (&left_pta).ts_product(&right_pta);
 
     trace!("built prefix tree product");
-    let sccs = dfa.sccs();
-    trace!("computed scc decomposition");
 
-    let states_with_infinite_run: Vec<_> = sccs
+    // a pair is conflicting if there is an infinite run from it, so we repeatedly remove the
+    // states all of whose successors have been removed, what remains are the conflicts
+    let mut predecessors: HashMap<_, Vec<_>> = HashMap::new();
+    let mut degree: HashMap<_, usize> = HashMap::new();
+    for source in dfa.state_indices() {
+        let mut count = 0;
+        for edge in dfa.edges_from(source).unwrap() {
+            predecessors.entry(edge.target()).or_default().push(source);
+            count += 1;
+        }
+        degree.insert(source, count);
+    }
+    let mut queue = degree
         .iter()
-        .filter_map(|(_, scc)| {
-            if !scc.is_transient() {
-                Some(scc.clone().into_iter().map(Into::into))
-            } else {
-                None
+        .filter_map(|(q, count)| (*count == 0).then_some(*q))
+        .collect::<VecDeque<_>>();
+    while let Some(q) = queue.pop_front() {
+        for p in predecessors.get(&q).into_iter().flatten() {
+            let count = degree.get_mut(p).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                queue.push_back(*p);
             }
-        })
-        .flatten()
-        .collect();
-
-    let mut conflicts = math::OrderedSet::default();
-    for ProductIndex(l, r) in dfa.state_indices() {
-        let reachable = dfa
-            .reachable_state_indices_from(ProductIndex(l, r))
-            .collect_vec();
-        if reachable
-            .iter()
-            .any(|ProductIndex(p, q)| states_with_infinite_run.contains(&(*p, *q)))
-        {
-            conflicts.insert((l, r));
         }
     }
+    let conflicts: math::OrderedSet<_> = degree
+        .into_iter()
+        .filter_map(|(ProductIndex(l, r), count)| (count > 0).then_some((l, r)))
+        .collect();
+    trace!("computed {} conflicts", conflicts.len());
 
     ConflictRelation {
         dfas: [
@@ -307,6 +363,58 @@ impl<A: Alphabet> ConsistencyCheck<A> for SeparatesIdempotents<'_, A> {
     }
 }
 
+/// This constraint ensures that a progress congruence refines the leading congruence, meaning
+/// that any two words `x` and `y` which lead to the same progress class also lead to the same
+/// leading class from the class that the progress congruence is associated with.
+#[derive(Clone)]
+pub struct RefinesLeading<'a, A: Alphabet> {
+    leading: &'a RightCongruence<A>,
+    class: StateIndex,
+}
+
+impl<'a, A: Alphabet> RefinesLeading<'a, A> {
+    /// Creates a new instance of the constraint for the progress congruence of `class`.
+    pub fn new(leading: &'a RightCongruence<A>, class: StateIndex) -> Self {
+        Self { leading, class }
+    }
+}
+
+impl<A: Alphabet> ConsistencyCheck<A> for RefinesLeading<'_, A> {
+    fn consistent(&self, cong: &RightCongruence<A>) -> bool {
+        let mut reached = math::Map::from_iter([(cong.initial(), self.class)]);
+        let mut queue = VecDeque::from([(cong.initial(), self.class)]);
+        while let Some((progress, leading)) = queue.pop_front() {
+            for sym in self.leading.alphabet().universe() {
+                let Some(p) = cong.successor_index(progress, sym) else {
+                    continue;
+                };
+                let l = self
+                    .leading
+                    .successor_index(leading, sym)
+                    .expect("leading congruence must be complete");
+                match reached.get(&p) {
+                    Some(existing) if *existing != l => return false,
+                    Some(_) => {}
+                    None => {
+                        reached.insert(p, l);
+                        queue.push_back((p, l));
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// The constraint does not bound the number of classes on its own.
+    fn threshold(&self) -> usize {
+        usize::MAX
+    }
+
+    fn alphabet(&self) -> &A {
+        self.leading.alphabet()
+    }
+}
+
 #[derive(Debug)]
 pub enum DpaInfError<A: Alphabet> {
     /// The threshold has been exceeded, returns constructed right congruence and threshold value
@@ -316,9 +424,9 @@ pub enum DpaInfError<A: Alphabet> {
 }
 
 /// Runs the omega-sprout algorithm on a given conflict relation.
-pub fn dpainf<A, C>(
+pub fn dpainf<'a, A, C>(
     conflicts: C,
-    additional_constraints: Vec<Box<dyn ConsistencyCheck<A>>>,
+    additional_constraints: Vec<Box<dyn ConsistencyCheck<A> + 'a>>,
     allow_transitions_into_epsilon: bool,
     timeout_seconds: Option<u64>,
 ) -> Result<RightCongruence<A>, DpaInfError<A>>
@@ -393,6 +501,7 @@ where
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use super::{ConsistencyCheck, RefinesLeading};
     use crate::passive::{SetSample, dpainf::ConflictRelation, sample::OmegaSample};
     use automata::core::alphabet::CharAlphabet;
     use automata::core::upw;
@@ -400,7 +509,7 @@ pub(crate) mod tests {
     use itertools::Itertools;
 
     pub fn inf_aba_sample() -> (CharAlphabet, OmegaSample<CharAlphabet>) {
-        let Ok(sample) = OmegaSample::try_from_str(
+        let Ok(sample) = OmegaSample::<CharAlphabet>::try_from_str(
             r#"omega
             alphabet: a,b
             positive:
@@ -428,7 +537,7 @@ pub(crate) mod tests {
     }
 
     pub fn testing_larger_forc_sample() -> (CharAlphabet, OmegaSample<CharAlphabet>) {
-        let Ok(sample) = OmegaSample::try_from_str(
+        let Ok(sample) = OmegaSample::<CharAlphabet>::try_from_str(
             r#"omega
         alphabet: a,b
         positive:
@@ -529,4 +638,20 @@ pub(crate) mod tests {
         let prc_eps = forc[0].clone();
         assert_eq!(prc_eps.size(), 13);
     }
+
+    #[test]
+    fn forc_refines_leading() {
+        // after the first b, infinitely many a's
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            CharAlphabet::of_size(2),
+            [upw!("b", "a")],
+            [upw!("a"), upw!("b")],
+        );
+        let cong = sample.infer_prefix_congruence().unwrap();
+        assert_eq!(cong.size(), 2);
+        let forc = sample.split(&cong).infer_forc();
+        for idx in cong.state_indices() {
+            assert!(RefinesLeading::new(&cong, idx).consistent(&forc[idx]));
+        }
+    }
 }
@@ -0,0 +1,470 @@
+use automata::automaton::DFA;
+use automata::core::alphabet::Alphabet;
+use automata::core::math;
+use automata::ts::Sproutable;
+use tracing::trace;
+
+use super::FiniteSample;
+
+/// Statistics that are gathered while two states of an [`AugmentedPta`] are merged. These are
+/// the basis on which a [`MergeScore`] decides how promising a merge is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeStatistics {
+    /// The number of pairs of states that were identified, including the initial pair and all
+    /// pairs that had to be merged subsequently to restore determinism.
+    pub identified: usize,
+    /// The number of identified pairs in which both states are accepting.
+    pub accepting_matches: usize,
+    /// The number of identified pairs in which both states are rejecting.
+    pub rejecting_matches: usize,
+}
+
+/// Assigns a score to a merge based on its [`MergeStatistics`]. A higher score means the
+/// merge is more promising, while a score of `None` rules out the merge entirely.
+pub trait MergeScore {
+    /// Computes the score of a merge with the given statistics.
+    fn score(&self, statistics: &MergeStatistics) -> Option<isize>;
+}
+
+impl<F: Fn(&MergeStatistics) -> Option<isize>> MergeScore for F {
+    fn score(&self, statistics: &MergeStatistics) -> Option<isize> {
+        (self)(statistics)
+    }
+}
+
+/// The classical score of the evidence-driven state merging algorithm, which counts the
+/// number of identified pairs of states that carry the same label.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EdsmScore;
+
+impl MergeScore for EdsmScore {
+    fn score(&self, statistics: &MergeStatistics) -> Option<isize> {
+        Some((statistics.accepting_matches + statistics.rejecting_matches) as isize)
+    }
+}
+
+/// Scores a merge by the number of pairs of states that are identified, i.e. by how much the
+/// hypothesis shrinks, as done in the counting heuristic of Lang.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LangCount;
+
+impl MergeScore for LangCount {
+    fn score(&self, statistics: &MergeStatistics) -> Option<isize> {
+        Some(statistics.identified as isize)
+    }
+}
+
+/// A single step taken by the [`Edsm`] learner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStep {
+    /// The blue state could not be merged with any red state and was promoted to be red.
+    Promote {
+        /// The state of the prefix tree that was promoted.
+        state: usize,
+    },
+    /// The blue state was merged into the red state, which was the best merge with the given score.
+    Merge {
+        /// The red state into which `blue` was merged.
+        red: usize,
+        /// The blue state that was merged.
+        blue: usize,
+        /// The score of the merge.
+        score: isize,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Change<S> {
+    Parent(usize),
+    Label(usize),
+    Successor(usize, S),
+}
+
+/// An augmented prefix tree acceptor, which stores for each prefix of a sample word whether it
+/// is accepted, rejected or unknown. States are numbered in breadth-first order. Merging states
+/// is done with a union-find structure and every modification is recorded, such that a merge
+/// can be undone cheaply with [`Self::rollback`].
+#[derive(Debug, Clone)]
+pub struct AugmentedPta<A: Alphabet> {
+    alphabet: A,
+    parent: Vec<usize>,
+    labels: Vec<Option<bool>>,
+    successors: Vec<math::OrderedMap<A::Symbol, usize>>,
+    changes: Vec<Change<A::Symbol>>,
+}
+
+impl<A: Alphabet> AugmentedPta<A> {
+    /// Builds the augmented prefix tree acceptor of the given sample.
+    pub fn new(sample: &FiniteSample<A>) -> Self {
+        let mut words: Vec<_> = sample.entries().collect();
+        words.sort_by(|(u, _), (v, _)| u.len().cmp(&v.len()).then(u.cmp(v)));
+
+        let mut pta = Self {
+            alphabet: sample.alphabet().clone(),
+            parent: vec![0],
+            labels: vec![None],
+            successors: vec![math::OrderedMap::default()],
+            changes: vec![],
+        };
+        for (word, label) in words {
+            let mut state = 0;
+            for sym in word {
+                state = match pta.successors[state].get(sym) {
+                    Some(target) => *target,
+                    None => {
+                        let target = pta.parent.len();
+                        pta.parent.push(target);
+                        pta.labels.push(None);
+                        pta.successors.push(math::OrderedMap::default());
+                        pta.successors[state].insert(*sym, target);
+                        target
+                    }
+                };
+            }
+            pta.labels[state] = Some(label);
+        }
+        pta.renumber();
+        pta
+    }
+
+    /// Renumbers the states in breadth-first order, where successors are visited in the order
+    /// of their symbols.
+    fn renumber(&mut self) {
+        let mut order = vec![0];
+        let mut i = 0;
+        while i < order.len() {
+            order.extend(self.successors[order[i]].values().copied());
+            i += 1;
+        }
+        let mut position = vec![0; order.len()];
+        for (i, q) in order.iter().enumerate() {
+            position[*q] = i;
+        }
+        self.labels = order.iter().map(|q| self.labels[*q]).collect();
+        self.successors = order
+            .iter()
+            .map(|q| {
+                self.successors[*q]
+                    .iter()
+                    .map(|(sym, target)| (*sym, position[*target]))
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Returns the number of states of the prefix tree, including those that have been merged.
+    pub fn size(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Returns the representative of the class that `state` belongs to.
+    pub fn find(&self, mut state: usize) -> usize {
+        while self.parent[state] != state {
+            state = self.parent[state];
+        }
+        state
+    }
+
+    /// Returns the label of the class that `state` belongs to.
+    pub fn label(&self, state: usize) -> Option<bool> {
+        self.labels[self.find(state)]
+    }
+
+    /// Returns the representative of the class that is reached from the class of `state` on `sym`.
+    pub fn successor(&self, state: usize, sym: A::Symbol) -> Option<usize> {
+        self.successors[self.find(state)]
+            .get(&sym)
+            .map(|target| self.find(*target))
+    }
+
+    /// Returns a marker for the current state of the prefix tree, to which it can be reset
+    /// with [`Self::rollback`].
+    pub fn checkpoint(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Undoes all merges that have been performed since the given `checkpoint` was taken.
+    pub fn rollback(&mut self, checkpoint: usize) {
+        while self.changes.len() > checkpoint {
+            match self.changes.pop().unwrap() {
+                Change::Parent(q) => self.parent[q] = q,
+                Change::Label(q) => self.labels[q] = None,
+                Change::Successor(q, sym) => {
+                    self.successors[q].remove(&sym);
+                }
+            }
+        }
+    }
+
+    /// Merges the class of `drop` into the class of `keep` and subsequently merges successors
+    /// until the result is deterministic again. Returns the statistics of the merge or `None` if
+    /// an accepting and a rejecting state would have been identified. In the latter case, the
+    /// prefix tree is left in an intermediate state and should be reset with [`Self::rollback`].
+    pub fn merge(&mut self, keep: usize, drop: usize) -> Option<MergeStatistics> {
+        let mut statistics = MergeStatistics::default();
+        let mut pending = vec![(keep, drop)];
+        while let Some((keep, drop)) = pending.pop() {
+            let (keep, drop) = (self.find(keep), self.find(drop));
+            if keep == drop {
+                continue;
+            }
+            match (self.labels[keep], self.labels[drop]) {
+                (Some(l), Some(r)) if l != r => return None,
+                (Some(true), Some(true)) => statistics.accepting_matches += 1,
+                (Some(false), Some(false)) => statistics.rejecting_matches += 1,
+                (None, Some(label)) => {
+                    self.labels[keep] = Some(label);
+                    self.changes.push(Change::Label(keep));
+                }
+                _ => {}
+            }
+            self.parent[drop] = keep;
+            self.changes.push(Change::Parent(drop));
+            statistics.identified += 1;
+
+            let moved: Vec<_> = self.successors[drop]
+                .iter()
+                .map(|(sym, target)| (*sym, *target))
+                .collect();
+            for (sym, target) in moved {
+                match self.successors[keep].get(&sym) {
+                    Some(existing) => pending.push((*existing, target)),
+                    None => {
+                        self.changes.push(Change::Successor(keep, sym));
+                        self.successors[keep].insert(sym, target);
+                    }
+                }
+            }
+        }
+        Some(statistics)
+    }
+
+    /// Tries to merge `drop` into `keep`, returning the statistics of the merge if it is
+    /// consistent. The prefix tree is left unchanged.
+    pub fn try_merge(&mut self, keep: usize, drop: usize) -> Option<MergeStatistics> {
+        let checkpoint = self.checkpoint();
+        let statistics = self.merge(keep, drop);
+        self.rollback(checkpoint);
+        statistics
+    }
+
+    /// Collects the quotient that is induced by the current merges into a [`DFA`]. The states
+    /// are the given `states`, which must be closed under successors, and unlabeled states
+    /// are rejecting.
+    fn quotient(&self, states: &[usize]) -> DFA<A> {
+        let mut dfa = DFA::new_with_initial_color(
+            self.alphabet.clone(),
+            self.labels[states[0]].unwrap_or(false),
+        );
+        let index: math::Map<usize, u32> = states
+            .iter()
+            .enumerate()
+            .map(|(i, q)| {
+                let idx = if i == 0 {
+                    0
+                } else {
+                    dfa.add_state(self.labels[*q].unwrap_or(false))
+                };
+                (*q, idx)
+            })
+            .collect();
+        for q in states {
+            for sym in self.successors[*q].keys() {
+                let target = self.successor(*q, *sym).unwrap();
+                dfa.add_edge((
+                    index[q],
+                    self.alphabet.make_expression(*sym),
+                    index[&target],
+                ));
+            }
+        }
+        dfa
+    }
+}
+
+/// The result of running the [`Edsm`] learner.
+#[derive(Debug, Clone)]
+pub struct EdsmOutcome<A: Alphabet> {
+    /// The learned automaton, which is consistent with the sample.
+    pub automaton: DFA<A>,
+    /// The steps that were taken, in order.
+    pub trace: Vec<MergeStep>,
+}
+
+/// Blue-fringe evidence-driven state merging for learning DFAs from a [`FiniteSample`].
+///
+/// The algorithm maintains a set of red states, which are fixed, and the set of blue states,
+/// which are the successors of red states that are not red themselves. In each step, all
+/// pairs of a red and a blue state are scored with the given [`MergeScore`]. If some blue
+/// state can not be merged with any red state, it is promoted. Otherwise the merge with the
+/// highest score is performed, where ties are broken in favor of the pair that comes first in
+/// breadth-first order. As only merges that do not identify accepting and rejecting states
+/// are performed, the result is consistent with the sample.
+#[derive(Debug, Clone, Default)]
+pub struct Edsm<S = EdsmScore> {
+    scoring: S,
+}
+
+impl<S: MergeScore> Edsm<S> {
+    /// Creates a new learner that uses the given `scoring` to rank merges.
+    pub fn new(scoring: S) -> Self {
+        Self { scoring }
+    }
+
+    /// Runs the learner on the given `sample`.
+    pub fn learn<A: Alphabet>(&self, sample: &FiniteSample<A>) -> EdsmOutcome<A> {
+        self.learn_from(AugmentedPta::new(sample))
+    }
+
+    /// Runs the learner starting from the given prefix tree, which may already contain merges.
+    pub fn learn_from<A: Alphabet>(&self, mut pta: AugmentedPta<A>) -> EdsmOutcome<A> {
+        let mut red = vec![pta.find(0)];
+        let mut trace = vec![];
+
+        loop {
+            let blue = blue_states(&pta, &red);
+            if blue.is_empty() {
+                break;
+            }
+
+            let mut best: Option<(isize, usize, usize)> = None;
+            let mut promote = None;
+            for b in &blue {
+                let mut mergeable = false;
+                for r in &red {
+                    let Some(score) = pta
+                        .try_merge(*r, *b)
+                        .and_then(|statistics| self.scoring.score(&statistics))
+                    else {
+                        continue;
+                    };
+                    mergeable = true;
+                    if best.is_none_or(|(s, _, _)| score > s) {
+                        best = Some((score, *r, *b));
+                    }
+                }
+                if !mergeable {
+                    promote = Some(*b);
+                    break;
+                }
+            }
+
+            if let Some(state) = promote {
+                trace!("promoting {state} to red");
+                red.push(state);
+                trace.push(MergeStep::Promote { state });
+            } else {
+                let (score, r, b) = best.expect("there is at least one blue state");
+                trace!("merging {b} into {r} with score {score}");
+                pta.merge(r, b).expect("merge was checked before");
+                trace.push(MergeStep::Merge {
+                    red: r,
+                    blue: b,
+                    score,
+                });
+            }
+        }
+
+        EdsmOutcome {
+            automaton: pta.quotient(&red),
+            trace,
+        }
+    }
+}
+
+/// Computes the blue states, i.e. the successors of red states that are not red themselves,
+/// in the order in which they are encountered.
+fn blue_states<A: Alphabet>(pta: &AugmentedPta<A>, red: &[usize]) -> Vec<usize> {
+    let mut blue = vec![];
+    for r in red {
+        for sym in pta.successors[*r].keys() {
+            let target = pta.successor(*r, *sym).unwrap();
+            if !red.contains(&target) && !blue.contains(&target) {
+                blue.push(target);
+            }
+        }
+    }
+    blue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AugmentedPta, Edsm, EdsmScore, LangCount, MergeStatistics, MergeStep};
+    use crate::passive::FiniteSample;
+    use automata::TransitionSystem;
+    use automata::core::alphabet::CharAlphabet;
+
+    fn even_as() -> FiniteSample {
+        FiniteSample::new_finite(
+            CharAlphabet::of_size(2),
+            [
+                ("", true),
+                ("a", false),
+                ("b", true),
+                ("aa", true),
+                ("ab", false),
+                ("ba", false),
+                ("bb", true),
+                ("aba", true),
+                ("aab", true),
+                ("baa", true),
+                ("bab", false),
+                ("abb", false),
+                ("aaa", false),
+            ]
+            .map(|(w, c)| (w.chars(), c)),
+        )
+    }
+
+    #[test]
+    fn augmented_pta_merge_and_undo() {
+        let mut pta = AugmentedPta::new(&even_as());
+        assert_eq!(pta.size(), 13);
+        assert_eq!(pta.successor(0, 'a'), Some(1));
+        assert_eq!(pta.label(1), Some(false));
+
+        let checkpoint = pta.checkpoint();
+        // merging the root with `b` is consistent, while merging it with `a` is not
+        assert!(pta.merge(0, 2).is_some());
+        assert_eq!(pta.successor(0, 'b'), Some(0));
+        pta.rollback(checkpoint);
+        assert_eq!(pta.successor(0, 'b'), Some(2));
+        assert_eq!(pta.try_merge(0, 1), None);
+        assert_eq!(pta.checkpoint(), checkpoint);
+    }
+
+    #[test]
+    fn edsm_learns_even_number_of_as() {
+        let sample = even_as();
+        for outcome in [
+            Edsm::new(EdsmScore).learn(&sample),
+            Edsm::new(LangCount).learn(&sample),
+        ] {
+            let dfa = outcome.automaton;
+            for w in sample.positive_words() {
+                assert!(dfa.accepts(w));
+            }
+            for w in sample.negative_words() {
+                assert!(!dfa.accepts(w));
+            }
+            assert_eq!(dfa.size(), 2);
+            assert!(dfa.accepts("abbbabbaa"));
+            assert!(
+                outcome
+                    .trace
+                    .iter()
+                    .any(|step| matches!(step, MergeStep::Promote { state: 1 }))
+            );
+        }
+
+        // a scoring that rules out all merges yields the prefix tree
+        let outcome = Edsm::new(|_: &MergeStatistics| None).learn(&sample);
+        assert_eq!(outcome.automaton.size(), 13);
+        assert!(
+            outcome
+                .trace
+                .iter()
+                .all(|step| matches!(step, MergeStep::Promote { .. }))
+        );
+    }
+}
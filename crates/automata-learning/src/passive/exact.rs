@@ -0,0 +1,541 @@
+//! Exact identification of minimal automata that are consistent with a sample. For a fixed
+//! number of states `n`, the existence of a consistent automaton with `n` states is encoded
+//! as a boolean formula in conjunctive normal form, which is then solved with [`varisat`].
+//! Starting from a single state, `n` is increased until the formula becomes satisfiable. As
+//! all smaller sizes have been refuted by the solver, the automaton that is found is minimal.
+//!
+//! The encoding follows Heule and Verwer, "Exact DFA Identification Using SAT Solvers": every
+//! node of a prefix tree of the sample is colored with a state of the automaton. To break the
+//! symmetries that stem from permuting states, the states are required to be numbered in the
+//! order in which a breadth-first search that explores symbols in ascending order visits them,
+//! which is the order that [`TransitionSystem::canonical_naming`](automata::TransitionSystem::canonical_naming)
+//! produces (Ulyantsev, Zakirzyanov and Shalyto, "BFS-Based Symmetry Breaking Predicates for DFA
+//! Identification").
+//!
+//! For omega-words `u v^ω`, we use that a deterministic automaton with `n` states reaches a state
+//! on the cycle of its run after reading `u v^n`, and the cycle is traversed completely while
+//! reading the next `n` copies of `v`. Thus, the transitions taken infinitely often are exactly
+//! those that are taken on the suffix `v^n` of the finite word `u v^{2n}`.
+
+use automata::DTS;
+use automata::automaton::{DBA, DFA, DPA};
+use automata::core::alphabet::Alphabet;
+use automata::core::word::OmegaWord;
+use automata::core::{Int, Void, math};
+use automata::ts::{ForAlphabet, Sproutable};
+use tracing::debug;
+use varisat::{ExtendFormula, Lit, Solver};
+
+use super::{FiniteSample, OmegaSample};
+
+/// The result of an exact identification, consisting of the automaton that was found together
+/// with a certificate for its minimality.
+#[derive(Debug, Clone)]
+pub struct ExactOutcome<M> {
+    /// The automaton, which is consistent with the sample.
+    pub automaton: M,
+    /// The number of states of `automaton`.
+    pub size: usize,
+    /// The sizes for which the solver has shown that no consistent automaton exists. These are
+    /// all sizes from one up to `size - 1`, which proves that `automaton` is minimal.
+    pub refuted: Vec<usize>,
+}
+
+/// Finds a minimal complete [`DFA`] that is consistent with the given `sample`. Sizes up to
+/// `max_size` are considered, and `None` is returned if no consistent DFA of that size exists.
+///
+/// # Example
+/// ```
+/// use automata::TransitionSystem;
+/// use automata::core::alphabet::CharAlphabet;
+/// use automata_learning::passive::{FiniteSample, exact::exact_dfa};
+///
+/// // words over {a, b} that end with `b`
+/// let sample = FiniteSample::new_finite(
+///     CharAlphabet::of_size(2),
+///     [("b", true), ("ab", true), ("", false), ("a", false), ("ba", false)]
+///         .map(|(w, c)| (w.chars(), c)),
+/// );
+/// let outcome = exact_dfa(&sample, 5).unwrap();
+/// assert_eq!(outcome.size, 2);
+/// assert_eq!(outcome.refuted, vec![1]);
+/// assert!(outcome.automaton.accepts("aab"));
+/// ```
+pub fn exact_dfa<A: Alphabet>(
+    sample: &FiniteSample<A>,
+    max_size: usize,
+) -> Option<ExactOutcome<DFA<A>>> {
+    let symbols: Vec<_> = sample.alphabet().universe().collect();
+    let mut tree = Tree::default();
+    let labels: Vec<_> = sample
+        .entries()
+        .map(|(word, label)| (*tree.insert(&symbols, word).last().unwrap_or(&0), label))
+        .collect();
+
+    identify(max_size, |n| {
+        let mut enc = Encoding::new(n, symbols.len(), &tree);
+        let accepting: Vec<_> = (0..n).map(|_| enc.new_lit()).collect();
+        for (node, label) in &labels {
+            for (q, z) in accepting.iter().enumerate() {
+                let z = if *label { *z } else { !*z };
+                enc.solver.add_clause(&[!enc.colors[*node][q], z]);
+            }
+        }
+
+        let model = enc.solve()?;
+        let mut ts = DTS::for_alphabet(sample.alphabet().clone());
+        for z in &accepting {
+            ts.add_state(model[z.index()]);
+        }
+        for (p, a, q) in enc.transitions(&model) {
+            ts.add_edge((p, sample.alphabet().make_expression(symbols[a]), q));
+        }
+        Some(DFA::from_parts(ts, 0))
+    })
+}
+
+/// Finds a minimal complete [`DBA`] with transition-based acceptance that is consistent with
+/// the given `sample`. Sizes up to `max_size` are considered.
+pub fn exact_dba<A: Alphabet>(
+    sample: &OmegaSample<A>,
+    max_size: usize,
+) -> Option<ExactOutcome<DBA<A>>> {
+    identify(max_size, |n| {
+        let (ts, initial) = solve_omega(sample, n, 2)?;
+        Some(DBA::from_parts(
+            ts.linked_map_edges(|_, e, c, _| (e, c == 0)),
+            initial,
+        ))
+    })
+}
+
+/// Finds a minimal complete [`DPA`] that uses the min-even parity condition with priorities in
+/// `0..priorities` and is consistent with the given `sample`. Sizes up to `max_size` are
+/// considered.
+pub fn exact_dpa<A: Alphabet>(
+    sample: &OmegaSample<A>,
+    priorities: Int,
+    max_size: usize,
+) -> Option<ExactOutcome<DPA<A>>> {
+    assert!(priorities > 0, "at least one priority is needed");
+    identify(max_size, |n| {
+        let (ts, initial) = solve_omega(sample, n, priorities as usize)?;
+        Some(DPA::from_parts(ts, initial))
+    })
+}
+
+/// Calls `attempt` for increasing sizes, until it produces an automaton.
+fn identify<M>(
+    max_size: usize,
+    mut attempt: impl FnMut(usize) -> Option<M>,
+) -> Option<ExactOutcome<M>> {
+    let mut refuted = vec![];
+    for size in 1..=max_size {
+        if let Some(automaton) = attempt(size) {
+            debug!("found consistent automaton with {size} states");
+            return Some(ExactOutcome {
+                automaton,
+                size,
+                refuted,
+            });
+        }
+        debug!("no consistent automaton with {size} states exists");
+        refuted.push(size);
+    }
+    None
+}
+
+/// Encodes the existence of a deterministic parity automaton with `n` states and the given
+/// number of `priorities` that is consistent with `sample`, and decodes a solution.
+fn solve_omega<A: Alphabet>(
+    sample: &OmegaSample<A>,
+    n: usize,
+    priorities: usize,
+) -> Option<(DTS<A, Void, Int>, u32)> {
+    let symbols: Vec<_> = sample.alphabet().universe().collect();
+    let mut tree = Tree::default();
+    // for each word, the edges of the prefix tree that are taken infinitely often
+    let words: Vec<(Vec<(usize, usize)>, bool)> = sample
+        .entries()
+        .map(|(word, label)| {
+            let (spoke, cycle) = word.raw_word().split_at(word.loop_index());
+            let mut finite = spoke.to_vec();
+            for _ in 0..2 * n {
+                finite.extend_from_slice(cycle);
+            }
+            let path = tree.insert(&symbols, &finite);
+            let start = spoke.len() + n * cycle.len();
+            let edges = (start..finite.len())
+                .map(|i| {
+                    let source = if i == 0 { 0 } else { path[i - 1] };
+                    (source, index_of(&symbols, &finite[i]))
+                })
+                .collect();
+            (edges, label)
+        })
+        .collect();
+
+    let mut enc = Encoding::new(n, symbols.len(), &tree);
+    // priority[q][a][d] holds iff the transition from q on a has priority d
+    let priority: Vec<Vec<Vec<Lit>>> = (0..n)
+        .map(|_| {
+            (0..symbols.len())
+                .map(|_| {
+                    let lits: Vec<_> = (0..priorities).map(|_| enc.new_lit()).collect();
+                    enc.exactly_one(&lits);
+                    lits
+                })
+                .collect()
+        })
+        .collect();
+
+    // edge_priority[(v, a)][d] holds iff the edge of the prefix tree has priority d
+    let mut edge_priority: math::Map<(usize, usize), Vec<Lit>> = math::Map::default();
+    for (edges, label) in &words {
+        // occurs[d] holds iff priority d occurs infinitely often
+        let occurs: Vec<_> = (0..priorities).map(|_| enc.new_lit()).collect();
+        let mut witnesses: Vec<Vec<Lit>> = occurs.iter().map(|o| vec![!*o]).collect();
+
+        for (v, a) in edges {
+            if !edge_priority.contains_key(&(*v, *a)) {
+                let lits: Vec<_> = (0..priorities).map(|_| enc.new_lit()).collect();
+                for (q, color) in enc.colors[*v].clone().into_iter().enumerate() {
+                    for d in 0..priorities {
+                        enc.solver
+                            .add_clause(&[!color, !priority[q][*a][d], lits[d]]);
+                        enc.solver
+                            .add_clause(&[!color, !lits[d], priority[q][*a][d]]);
+                    }
+                }
+                edge_priority.insert((*v, *a), lits);
+            }
+            for d in 0..priorities {
+                let lit = edge_priority[&(*v, *a)][d];
+                enc.solver.add_clause(&[!lit, occurs[d]]);
+                witnesses[d].push(lit);
+            }
+        }
+        for witness in witnesses {
+            enc.solver.add_clause(&witness);
+        }
+
+        // the least priority that occurs infinitely often must be even iff the word is positive
+        for d in 0..priorities {
+            if (d % 2 == 0) != *label {
+                let mut clause = vec![!occurs[d]];
+                clause.extend((0..d).filter(|e| (e % 2 == 0) == *label).map(|e| occurs[e]));
+                enc.solver.add_clause(&clause);
+            }
+        }
+    }
+
+    let model = enc.solve()?;
+    let mut ts = DTS::for_alphabet(sample.alphabet().clone());
+    for _ in 0..n {
+        ts.add_state(Void);
+    }
+    for (p, a, q) in enc.transitions(&model) {
+        let color = (0..priorities)
+            .find(|d| model[priority[p as usize][a][*d].index()])
+            .expect("every transition has a priority") as Int;
+        ts.add_edge((p, sample.alphabet().make_expression(symbols[a]), color, q));
+    }
+    Some((ts, 0))
+}
+
+fn index_of<S: PartialEq>(symbols: &[S], sym: &S) -> usize {
+    symbols
+        .iter()
+        .position(|s| s == sym)
+        .expect("symbol must be in the alphabet")
+}
+
+/// A prefix tree over symbol indices, whose root is the node `0`.
+#[derive(Debug, Default)]
+struct Tree {
+    successors: Vec<math::OrderedMap<usize, usize>>,
+}
+
+impl Tree {
+    /// Inserts the given `word` and returns the nodes that are visited along the way, excluding
+    /// the root.
+    fn insert<S: PartialEq>(&mut self, symbols: &[S], word: &[S]) -> Vec<usize> {
+        if self.successors.is_empty() {
+            self.successors.push(math::OrderedMap::default());
+        }
+        let mut node = 0;
+        let mut path = vec![];
+        for sym in word {
+            let a = index_of(symbols, sym);
+            node = match self.successors[node].get(&a) {
+                Some(next) => *next,
+                None => {
+                    let next = self.successors.len();
+                    self.successors.push(math::OrderedMap::default());
+                    self.successors[node].insert(a, next);
+                    next
+                }
+            };
+            path.push(node);
+        }
+        path
+    }
+}
+
+/// The part of the encoding that is shared between all types of automata. It consists of the
+/// transition structure of a complete deterministic automaton with `n` states, a coloring of
+/// the nodes of a prefix tree with states that respects the transitions, and the constraints
+/// for breaking symmetries.
+struct Encoding {
+    solver: Solver<'static>,
+    n: usize,
+    /// `transition[p][a][q]` holds iff the transition from `p` on the symbol `a` leads to `q`.
+    transition: Vec<Vec<Vec<Lit>>>,
+    /// `colors[v][q]` holds iff the node `v` of the prefix tree is colored with state `q`.
+    colors: Vec<Vec<Lit>>,
+}
+
+impl Encoding {
+    fn new(n: usize, symbols: usize, tree: &Tree) -> Self {
+        let mut enc = Self {
+            solver: Solver::new(),
+            n,
+            transition: vec![],
+            colors: vec![],
+        };
+        enc.transition = (0..n)
+            .map(|_| {
+                (0..symbols)
+                    .map(|_| {
+                        let lits: Vec<_> = (0..n).map(|_| enc.new_lit()).collect();
+                        enc.exactly_one(&lits);
+                        lits
+                    })
+                    .collect()
+            })
+            .collect();
+
+        enc.colors = (0..tree.successors.len().max(1))
+            .map(|_| {
+                let lits: Vec<_> = (0..n).map(|_| enc.new_lit()).collect();
+                enc.exactly_one(&lits);
+                lits
+            })
+            .collect();
+        let root = enc.colors[0][0];
+        enc.solver.add_clause(&[root]);
+        for (v, successors) in tree.successors.iter().enumerate() {
+            for (a, w) in successors {
+                for p in 0..n {
+                    for q in 0..n {
+                        let clause = [
+                            !enc.colors[v][p],
+                            !enc.colors[*w][q],
+                            enc.transition[p][*a][q],
+                        ];
+                        enc.solver.add_clause(&clause);
+                    }
+                }
+            }
+        }
+
+        enc.break_symmetries(symbols);
+        enc
+    }
+
+    fn new_lit(&mut self) -> Lit {
+        self.solver.new_lit()
+    }
+
+    fn exactly_one(&mut self, lits: &[Lit]) {
+        self.solver.add_clause(lits);
+        for (i, l) in lits.iter().enumerate() {
+            for k in &lits[i + 1..] {
+                self.solver.add_clause(&[!*l, !*k]);
+            }
+        }
+    }
+
+    /// Enforces that states are numbered in breadth-first order, which makes every solution
+    /// unique up to the acceptance condition.
+    fn break_symmetries(&mut self, symbols: usize) {
+        let n = self.n;
+        // connected[p][q] holds iff there is some transition from p to q
+        let connected: Vec<Vec<Lit>> = (0..n)
+            .map(|p| {
+                (0..n)
+                    .map(|q| {
+                        let t = self.new_lit();
+                        let mut clause = vec![!t];
+                        for a in 0..symbols {
+                            self.solver.add_clause(&[!self.transition[p][a][q], t]);
+                            clause.push(self.transition[p][a][q]);
+                        }
+                        self.solver.add_clause(&clause);
+                        t
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // parent[q][p] holds iff p is the least state with a transition to q
+        let mut parent: Vec<Vec<Lit>> = vec![vec![]; n];
+        for q in 1..n {
+            for p in 0..q {
+                let lit = self.new_lit();
+                self.solver.add_clause(&[!lit, connected[p][q]]);
+                let mut clause = vec![lit, !connected[p][q]];
+                for earlier in &connected[..p] {
+                    self.solver.add_clause(&[!lit, !earlier[q]]);
+                    clause.push(earlier[q]);
+                }
+                self.solver.add_clause(&clause);
+                parent[q].push(lit);
+            }
+            let clause = parent[q].clone();
+            self.solver.add_clause(&clause);
+        }
+
+        // parents are non-decreasing
+        for q in 1..n.saturating_sub(1) {
+            for p in 0..q {
+                for r in 0..p {
+                    self.solver.add_clause(&[!parent[q][p], !parent[q + 1][r]]);
+                }
+            }
+        }
+
+        // minimal[p][a][q] holds iff a is the least symbol on which p leads to q
+        let minimal: Vec<Vec<Vec<Lit>>> = (0..n)
+            .map(|p| {
+                (0..symbols)
+                    .map(|a| {
+                        (0..n)
+                            .map(|q| {
+                                let lit = self.new_lit();
+                                self.solver.add_clause(&[!lit, self.transition[p][a][q]]);
+                                let mut clause = vec![lit, !self.transition[p][a][q]];
+                                for b in 0..a {
+                                    self.solver.add_clause(&[!lit, !self.transition[p][b][q]]);
+                                    clause.push(self.transition[p][b][q]);
+                                }
+                                self.solver.add_clause(&clause);
+                                lit
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // states with the same parent are ordered by the symbol on which they are reached
+        for q in 1..n.saturating_sub(1) {
+            for p in 0..q {
+                for a in 0..symbols {
+                    for b in 0..a {
+                        self.solver.add_clause(&[
+                            !parent[q][p],
+                            !parent[q + 1][p],
+                            !minimal[p][a][q],
+                            !minimal[p][b][q + 1],
+                        ]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Solves the formula and returns the assignment of each variable, or `None` if the
+    /// formula is unsatisfiable.
+    fn solve(&mut self) -> Option<Vec<bool>> {
+        if !self.solver.solve().expect("solver does not fail") {
+            return None;
+        }
+        let model = self.solver.model().expect("formula is satisfiable");
+        let mut values = vec![false; model.len()];
+        for lit in model {
+            values[lit.index()] = lit.is_positive();
+        }
+        Some(values)
+    }
+
+    /// Returns the transitions `(p, a, q)` of the automaton that is described by `model`.
+    fn transitions(&self, model: &[bool]) -> Vec<(u32, usize, u32)> {
+        let mut out = vec![];
+        for (p, by_symbol) in self.transition.iter().enumerate() {
+            for (a, targets) in by_symbol.iter().enumerate() {
+                let q = targets
+                    .iter()
+                    .position(|t| model[t.index()])
+                    .expect("automaton is complete");
+                out.push((p as u32, a, q as u32));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exact_dba, exact_dfa, exact_dpa};
+    use crate::passive::{FiniteSample, OmegaSample};
+    use automata::TransitionSystem;
+    use automata::core::alphabet::CharAlphabet;
+    use automata::core::upw;
+
+    #[test]
+    fn exact_dfa_for_modulo_three() {
+        // words over {a, b} whose number of `a`s is divisible by three
+        let words = [
+            "", "a", "aa", "aaa", "b", "ab", "ba", "aab", "aba", "baa", "abaa", "bbb",
+        ];
+        let sample = FiniteSample::new_finite(
+            CharAlphabet::of_size(2),
+            words.map(|w| (w.chars(), w.chars().filter(|c| *c == 'a').count() % 3 == 0)),
+        );
+        let outcome = exact_dfa(&sample, 5).unwrap();
+        assert_eq!(outcome.size, 3);
+        assert_eq!(outcome.refuted, vec![1, 2]);
+        for (word, label) in sample.entries() {
+            assert_eq!(outcome.automaton.accepts(word), label);
+        }
+        assert_eq!(
+            outcome
+                .automaton
+                .minimal_representatives_iter()
+                .map(|rep| rep.decompose().0)
+                .collect::<Vec<_>>(),
+            vec![vec![], vec!['a'], vec!['a', 'a']]
+        );
+        assert!(exact_dfa(&sample, 2).is_none());
+    }
+
+    #[test]
+    fn exact_omega_automata() {
+        // infinitely many `a`s
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            CharAlphabet::of_size(2),
+            [upw!("a"), upw!("ab"), upw!("b", "a")],
+            [upw!("b"), upw!("a", "b")],
+        );
+        let dba = exact_dba(&sample, 3).unwrap();
+        assert_eq!(dba.size, 1);
+        let dpa = exact_dpa(&sample, 2, 3).unwrap();
+        assert_eq!(dpa.size, 1);
+
+        // finitely many `a`s, which is not recognizable by a DBA
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            CharAlphabet::of_size(2),
+            [upw!("b"), upw!("a", "b"), upw!("aba", "b")],
+            [upw!("a"), upw!("ab"), upw!("b", "a"), upw!("bb", "ab")],
+        );
+        let dba = exact_dba(&sample, 4).unwrap();
+        assert_eq!(dba.size, 2);
+        let dpa = exact_dpa(&sample, 3, 3).unwrap();
+        assert_eq!(dpa.size, 1);
+        for (word, label) in sample.entries() {
+            assert_eq!(dba.automaton.accepts(word), label);
+            assert_eq!(dpa.automaton.accepts(word), label);
+        }
+    }
+}
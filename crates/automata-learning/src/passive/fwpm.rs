@@ -27,9 +27,13 @@ impl<A: Alphabet> FWPM<A> {
         }
     }
 
+    /// Returns the number of priorities that are needed for building a DPA from `self`, which is
+    /// one more than the largest color of any mapping. Colors below it count even if they are not
+    /// used, since shifting the colors down would change their parity.
     pub fn complexity(&self) -> usize {
         self.pms()
-            .map(|(_, pm)| pm.color_range().len())
+            .filter_map(|(_, pm)| pm.color_range().into_iter().max())
+            .map(|max| max as usize + 1)
             .max()
             .unwrap_or(0)
     }
@@ -15,7 +15,8 @@ use automata::{
 use itertools::Itertools;
 use tracing::{debug, info};
 
-const MAX_PRIORITIES: usize = 8;
+/// The maximal number of priorities for which [`build_precise_dpa_for`] can build a DPA.
+pub(crate) const MAX_PRIORITIES: usize = 8;
 
 pub fn build_precise_dpa_for<A: Alphabet>(fwpm: FWPM<A>) -> DPA<A> {
     match fwpm.complexity() {
@@ -351,11 +352,8 @@ impl<A: Alphabet, const N: usize> PreciseDPA<A, N> {
     /// Creates a new precise DPA from the given leading congruence and sequence of sequences of DFAs.
     pub fn new(cong: RightCongruence<A>, dfas: Vec<[DFA<A>; N]>) -> Self {
         let e = cong.initial();
-        let initial = PState::from_iters(
-            e,
-            [e; N],
-            (0..dfas.len()).map(|i| dfas[i][e as usize].initial()),
-        );
+        let initial =
+            PState::from_iters(e, [e; N], dfas[e as usize].iter().map(|dfa| dfa.initial()));
         Self {
             states: vec![initial],
             expressions: cong.alphabet().expression_map(),
@@ -456,7 +454,10 @@ impl<A: Alphabet, const N: usize> From<FWPM<A>> for PreciseDPA<A, N> {
         let padding_dfa = padding_universal_dfa(leading.alphabet());
         let mut prc_dfas = Vec::with_capacity(leading.size());
         for (idx, mm) in value.pms() {
-            let mut dfas = mm.decompose_dfa();
+            // the priority of an edge is the level of the least accepting DFA, so there must be
+            // a DFA for every color up to the maximal one, even if some colors are not used
+            let max = mm.color_range().into_iter().max().unwrap_or(0);
+            let mut dfas = (0..=max).map(|c| mm.color_or_below_dfa(c)).collect_vec();
             assert!(dfas.len() <= N);
             while dfas.len() < N {
                 dfas.push(padding_dfa.clone());
@@ -519,9 +520,62 @@ impl<A: Alphabet, const N: usize> Dottable for PreciseDPA<A, N> {
 mod tests {
     use automata::core::Void;
     use automata::core::alphabet::CharAlphabet;
+    use automata::ts::TSBuilder;
     use automata::{DTS, TransitionSystem};
 
-    use super::PreciseDPA;
+    use super::{PreciseDPA, build_precise_dpa_for};
+    use crate::passive::fwpm::FWPM;
+
+    #[test]
+    fn precise_dpa_with_unused_colors() {
+        let leading = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(())
+            .into_right_congruence_bare(0);
+        // periods that only consist of a have priority 2, all others have priority 1, so color 0
+        // is never used but the priorities must not be shifted down
+        let pm = TSBuilder::without_edge_colors()
+            .with_state_colors([2, 1])
+            .with_edges([(0, 'a', 0), (0, 'b', 1), (1, 'a', 1), (1, 'b', 1)])
+            .into_moore(0);
+        let mut fwpm = FWPM::empty(leading);
+        fwpm.insert_pm(0, pm);
+        assert_eq!(fwpm.complexity(), 3);
+
+        // finitely many b
+        let expected = TSBuilder::without_state_colors()
+            .with_edges([(0, 'a', 2, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        assert!(build_precise_dpa_for(fwpm).language_equivalent(&expected));
+    }
+
+    #[test]
+    fn precise_dpa_with_more_classes_than_priorities() {
+        // the initial state takes one progress state for each priority from the DFAs of the
+        // initial class, independently of the number of classes
+        let leading = DTS::builder()
+            .with_transitions([
+                (0, 'a', Void, 1),
+                (0, 'b', Void, 0),
+                (1, 'a', Void, 0),
+                (1, 'b', Void, 1),
+            ])
+            .default_color(())
+            .into_right_congruence_bare(0);
+        let pm = TSBuilder::without_edge_colors()
+            .with_state_colors([0])
+            .with_edges([(0, 'a', 0), (0, 'b', 0)])
+            .into_moore(0);
+        let mut fwpm = FWPM::empty(leading);
+        fwpm.insert_pm(0, pm.clone());
+        fwpm.insert_pm(1, pm);
+        assert_eq!(fwpm.complexity(), 1);
+
+        let expected = TSBuilder::without_state_colors()
+            .with_edges([(0, 'a', 0, 0), (0, 'b', 0, 0)])
+            .into_dpa(0);
+        assert!(build_precise_dpa_for(fwpm).language_equivalent(&expected));
+    }
 
     #[test]
     fn precise_dpa() {
@@ -19,13 +19,24 @@ mod split;
 pub use split::{ClassOmegaSample, SplitOmegaSample};
 
 mod omega;
-pub use omega::{OmegaSampleParseError, PeriodicOmegaSample};
+pub use omega::{OmegaSampleParseError, PeriodicOmegaSample, SampleAlphabet};
 
 mod finite;
 pub use finite::FiniteSampleParseError;
 
+mod generate;
+pub use generate::{RandomWord, SampleGenerator};
+
 mod canonic_coloring;
 
+mod output;
+pub use output::OutputSample;
+
+mod characterize;
+pub use characterize::{
+    actively_exchanged_words_dfa, actively_exchanged_words_mealy, characterize_moore,
+};
+
 pub trait Sample<A: Alphabet> {
     type Word: Word<Symbol = A::Symbol>;
     type PositiveIter<'this>: Iterator<Item = &'this Self::Word>
@@ -93,6 +104,22 @@ impl<A: Alphabet, W: Word<Symbol = A::Symbol>> SetSample<A, W> {
         self.negative.iter()
     }
 
+    /// Creates a new sample from the given `words`, each of which is classified by `target`,
+    /// for example by the acceptance of a target automaton.
+    pub fn labelled_by<I, F>(alphabet: A, words: I, mut target: F) -> Self
+    where
+        W: Hash + Eq,
+        I: IntoIterator<Item = W>,
+        F: FnMut(&W) -> bool,
+    {
+        let (positive, negative) = words.into_iter().partition(|word| target(word));
+        Self {
+            alphabet,
+            positive,
+            negative,
+        }
+    }
+
     /// Create a new empty sample for the given alphabet
     pub fn new_for_alphabet(alphabet: A) -> Self {
         Self {
@@ -230,7 +257,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::passive::SetSample;
+    use crate::passive::{OmegaSample, SetSample};
     use automata::TransitionSystem;
     use automata::core::alphabet::CharAlphabet;
     use automata::core::upw;
@@ -255,7 +282,7 @@ mod tests {
         ab
         abb"#;
 
-        let sample = match SetSample::try_from_str(sample_str) {
+        let sample = match OmegaSample::<CharAlphabet>::try_from_str(sample_str) {
             Ok(s) => s,
             Err(e) => panic!("Error parsing sample: {:?}", e),
         };
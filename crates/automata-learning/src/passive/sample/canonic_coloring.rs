@@ -1,8 +1,9 @@
 use crate::priority_mapping::ClassifiesIdempotents;
 use automata::core::alphabet::{Alphabet, Symbol};
-use automata::core::word::FiniteWord;
+use automata::core::word::{FiniteWord, ReducedOmegaWord};
+use automata::ts::Deterministic;
 
-use super::PeriodicOmegaSample;
+use super::{ClassOmegaSample, PeriodicOmegaSample};
 
 impl<A: Alphabet> ClassifiesIdempotents<A> for PeriodicOmegaSample<A> {
     fn classify(&self, class: impl FiniteWord<Symbol = <A as Alphabet>::Symbol>) -> Option<bool> {
@@ -10,6 +11,20 @@ impl<A: Alphabet> ClassifiesIdempotents<A> for PeriodicOmegaSample<A> {
     }
 }
 
+/// Only idempotents that loop on the class of the sample are classified. A periodic word that
+/// leaves the class says nothing about the progress of the class, so it must not contribute
+/// to the coloring.
+impl<A: Alphabet> ClassifiesIdempotents<A> for ClassOmegaSample<'_, A> {
+    fn classify(&self, class: impl FiniteWord<Symbol = <A as Alphabet>::Symbol>) -> Option<bool> {
+        let idx = self.congruence().reached_state_index(self.class())?;
+        if self.congruence().reached_state_index_from(idx, &class) != Some(idx) {
+            return None;
+        }
+        self.sample()
+            .classify(&ReducedOmegaWord::from(class.omega_power()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use automata::TransitionSystem;
@@ -1,506 +1,291 @@
-use std::{
-    borrow::Borrow,
-    cell::{Cell, RefCell},
-    collections::{BTreeSet, VecDeque},
-    fmt::{Debug, Display},
-    hash::Hash,
-    sync::Arc,
-};
-
-use automata::{prelude::*, transition_system::operations::Quotient};
-use bimap::BiMap;
+use std::{cell::RefCell, collections::VecDeque};
+
+use automata::automaton::{DFA, MealyLike, MealyMachine};
+use automata::core::alphabet::Alphabet;
+use automata::core::word::FiniteWord;
+use automata::core::{Color, Void, math};
+use automata::ts::{Deterministic, StateColor, StateIndex, SymbolOf};
+use automata::{Pointed, TransitionSystem};
 use itertools::Itertools;
-use tracing::{debug, info, trace};
-
-use super::{FiniteSample, OmegaSample, Sample};
-type DFASample<D> = FiniteSample<<D as TransitionSystem>::Alphabet, bool>;
-
-fn dpa<M: Deterministic<EdgeColor = Int>>(dpa: IntoDPA<M>) -> OmegaSample<M::Alphabet, bool>
+use tracing::debug;
+
+use crate::active::{Counterexample, DFAOracle, Hypothesis, LStar, MealyOracle, Oracle};
+
+use super::{FiniteSample, OutputSample};
+
+/// Computes a characteristic sample for the given complete Moore machine, which annotates each
+/// word with the color of the state that it reaches. A [`crate::passive::DFA`] is a Moore machine
+/// with boolean colors, so the sample can be turned into a [`FiniteSample`] for it.
+///
+/// Consider the states of the minimal machine in length-lexicographic order of their minimal
+/// representatives `u_q`. The sample contains
+/// - the words `u_q` and `u_q a` for every state `q` and symbol `a`, and
+/// - the words `u_p v` and `u_q a v` for every transition from `q` on `a` and every state `p` that
+///   is different from its target, where `v` is the least word separating the two.
+///
+/// Thus it has polynomial size. When [`dpainf`](crate::passive::dpainf::dpainf) considers the
+/// transitions in breadth-first order, it rejects all wrong targets of a transition because of
+/// such a pair, and it creates states in the order of their minimal representatives. So the
+/// result of [`crate::passive::dfa_rpni`] or [`crate::passive::moore_rpni`] on the sample is
+/// isomorphic to the minimal machine.
+pub fn characterize_moore<M>(moore: M) -> OutputSample<M::Alphabet, StateColor<M>>
 where
-    M: Clone,
+    M: Deterministic + Pointed,
+    StateColor<M>: Color,
 {
-    // let start = std::time::Instant::now();
-    // let quot = dpa.prefix_congruence();
-    // let cong = quot.collect_right_congruence_bare();
-    // let mut sample = right_congruence_by_omega_words(&quot);
-
-    // for (i, class) in quot.partition().iter().enumerate() {
-    //     let class_pm = priority_mapping_set_backed(&dpa, class);
-    //     let cpm = CongruentPriorityMapping::new(&cong, i, class_pm.push_colors_to_outgoing_edges());
-    //     let class_sample = priority_mapping_by_omega_words(cpm);
-    //     sample.append(class_sample);
-    // }
-
-    // info!(
-    //     "Computation of right congruence by prefix words took {}μs",
-    //     start.elapsed().as_micros()
-    // );
-    // sample
-    todo!()
-}
-
-fn right_congruence_by_omega_words<M: Congruence<EdgeColor = Int>>(
-    cong: &Quotient<M>,
-) -> OmegaSample<M::Alphabet, bool> {
-    let start = std::time::Instant::now();
-
-    let mut pos = math::Set::default();
-    let mut neg = math::Set::default();
-
-    let class_count = cong.partition().size();
-    for mtr in cong.minimal_transition_representatives() {
-        let reached = cong
-            .reached_state_index(&mtr)
-            .expect("State must be present");
-        trace!(
-            "Considering transition representative {} with id {}",
-            mtr.show(),
-            reached
-        );
-        for mr in cong.minimal_representatives() {
-            trace!(
-                "Considering minimal representative {} with id {}",
-                mr.show(),
-                mr.state_index()
-            );
-            if reached == mr.state_index() {
-                continue;
-            }
-            let q = cong.unwrap_class_representative(reached);
-            let p = cong.unwrap_class_representative(separate.state_index());
-            assert!(p != q, "Same state cannot be in two different classes");
-
-            let suffix = cong
-                .ts()
-                .into_dpa()
-                .separate(p, q)
-                .expect("Separation must be possible");
-
-            let w1 = Concat(&mtr, &suffix).reduced();
-            let w2 = Concat(&mr, suffix).reduced();
-
-            let b1 = cong.ts().into_dpa().accepts(&w1);
-            let b2 = cong.ts().into_dpa().accepts(&w2);
-            assert_ne!(b1, b2);
+    let color = |word: &[SymbolOf<M>]| {
+        moore
+            .reached_state_color(word)
+            .expect("Moore machine must be complete")
+    };
 
-            if b1 {
-                pos.insert(w1);
-            } else {
-                neg.insert(w1);
-            }
-            if b2 {
-                pos.insert(w2);
-            } else {
-                neg.insert(w2);
-            }
+    // one state for each class of equivalent states, in the order of their minimal representatives
+    let mut representatives: Vec<(Vec<SymbolOf<M>>, StateIndex<M>)> = vec![];
+    for rep in moore.minimal_representatives_iter() {
+        let q = rep.state_index();
+        if representatives
+            .iter()
+            .all(|(_, p)| separate(&moore, *p, q).is_some())
+        {
+            representatives.push((rep.into_inner(), q));
         }
     }
-
     debug!(
-        "Computation of right congruence by omega words took {}μs",
-        start.elapsed().as_micros()
+        "characterizing Moore machine with {} classes",
+        representatives.len()
     );
-    OmegaSample::new_omega_from_pos_neg(cong.alphabet().clone(), pos, neg)
-}
 
-fn for_mtr_mr_pairs<D: Deterministic + Pointed, F>(cong: D, mut f: F)
-where
-    F: FnMut(&[SymbolOf<D>], D::StateIndex, &[SymbolOf<D>], D::StateIndex),
-{
-    for mtr in cong.minimal_transition_representatives() {
-        let reached = cong
-            .reached_state_index(&mtr)
-            .expect("State must be present");
-        for (mr, idx) in cong.minimal_representatives() {
-            if reached == idx {
-                continue;
+    let mut sample = OutputSample::new_for_alphabet(moore.alphabet().clone());
+    for (u, _) in &representatives {
+        sample.insert(u.clone(), color(u));
+    }
+    for (u, q) in &representatives {
+        for a in moore.symbols() {
+            let ua = u.iter().copied().chain([a]).collect_vec();
+            let target = moore
+                .successor_index(*q, a)
+                .expect("Moore machine must be complete");
+            sample.insert(ua.clone(), color(&ua));
+
+            for (v, p) in &representatives {
+                let Some(suffix) = separate(&moore, *p, target) else {
+                    continue;
+                };
+                for word in [v, &ua] {
+                    let word = word.iter().chain(&suffix).copied().collect_vec();
+                    let c = color(&word);
+                    sample.insert(word, c);
+                }
             }
-            (f)(&mtr, reached, &mr, idx);
         }
     }
+    sample
 }
 
-fn priority_mapping_by_omega_words<A: Alphabet>(
-    cpm: CongruentPriorityMapping<'_, A>,
-) -> OmegaSample<A, bool> {
-    let start = std::time::Instant::now();
-
-    let idempotents = cpm.mm().minimal_representatives().filter_map(|(rep, q)| {
-        let Some(reached) = cpm.mm().reached_state_index_from(&rep, q) else {
-            panic!("Input must be deterministic");
-        };
-        if q == reached {
-            Some(rep)
-        } else {
-            None
+/// Returns the length-lexicographically least word on which the states `p` and `q` of `moore`
+/// reach states of different colors, or `None` if the two states are equivalent.
+fn separate<M>(moore: &M, p: StateIndex<M>, q: StateIndex<M>) -> Option<Vec<SymbolOf<M>>>
+where
+    M: Deterministic,
+    StateColor<M>: Color,
+{
+    let mut seen = math::Set::from_iter([(p, q)]);
+    let mut queue = VecDeque::from([(p, q, vec![])]);
+    while let Some((p, q, word)) = queue.pop_front() {
+        if moore.state_color(p) != moore.state_color(q) {
+            return Some(word);
         }
-    });
-
-    for_mtr_mr_pairs(cpm.mm(), |mtr, reached, mr, idx| {
-        if reached == idx || mtr == mr {
-            return; // from closure
+        for a in moore.symbols() {
+            let (Some(p), Some(q)) = (moore.successor_index(p, a), moore.successor_index(q, a))
+            else {
+                panic!("Moore machine must be complete");
+            };
+            if seen.insert((p, q)) {
+                queue.push_back((p, q, word.iter().copied().chain([a]).collect()));
+            }
         }
-        trace!(
-            "Considering pair {}@{reached} and {}@{idx}",
-            Show::show(mtr),
-            mr.show()
-        );
-        todo!()
-    });
-
-    todo!()
-}
-
-trait StateCollection<Idx, C>: Hash + Eq + Clone {
-    fn pair_iter(&self) -> impl Iterator<Item = (Idx, C)>;
-    fn from_pair_iter(iter: impl IntoIterator<Item = (Idx, C)>) -> Self;
-    fn sink() -> Self {
-        Self::from_pair_iter(std::iter::empty())
-    }
-    fn show(&self) -> String
-    where
-        Idx: Show,
-        C: Show,
-    {
-        format!(
-            "{{{}}}",
-            self.pair_iter()
-                .map(|(q, c)| format!("q{}|{}", q.show(), c.show()))
-                .join(", ")
-        )
     }
+    None
 }
 
-impl<Idx: IndexType, C: Color> StateCollection<Idx, C> for Vec<(Idx, C)> {
-    fn pair_iter(&self) -> impl Iterator<Item = (Idx, C)> {
-        self.iter().cloned()
-    }
-
-    fn from_pair_iter(iter: impl IntoIterator<Item = (Idx, C)>) -> Self {
-        iter.into_iter().collect()
-    }
+/// An [`Oracle`] wrapper that records all membership queries and their answers, as well as the
+/// counterexamples that are returned for equivalence queries.
+struct Exchange<T: Oracle> {
+    oracle: T,
+    words: RefCell<OutputSample<T::Alphabet, T::Output>>,
 }
 
-impl<Idx: IndexType, C: Color> StateCollection<Idx, C> for BTreeSet<(Idx, C)> {
-    fn pair_iter(&self) -> impl Iterator<Item = (Idx, C)> {
-        self.iter().cloned()
+impl<T: Oracle> Exchange<T> {
+    fn new(oracle: T) -> Self {
+        let words = RefCell::new(OutputSample::new_for_alphabet(oracle.alphabet().clone()));
+        Self { oracle, words }
     }
 
-    fn from_pair_iter(iter: impl IntoIterator<Item = (Idx, C)>) -> Self {
-        iter.into_iter().collect()
+    fn into_words(self) -> OutputSample<T::Alphabet, T::Output> {
+        self.words.into_inner()
     }
 }
 
-fn priority_mapping_set_backed<
-    D: Congruence<EdgeColor = usize>,
-    X: Borrow<D::StateIndex>,
-    I: IntoIterator<Item = X>,
->(
-    dpa: D,
-    initial: I,
-) -> MooreMachine<D::Alphabet, D::EdgeColor> {
-    let neutral_high = dpa
-        .edge_colors()
-        .max()
-        .expect("There needs to be at least one color");
-    build_generic_priority_mapping(
-        dpa,
-        initial
-            .into_iter()
-            .map(|q| (*q.borrow(), neutral_high))
-            .collect::<BTreeSet<_>>(),
-    )
-}
-fn priority_mapping_vec_backed<
-    D: Congruence<EdgeColor = usize>,
-    I: IntoIterator<Item = D::StateIndex>,
->(
-    dpa: D,
-    initial: I,
-) -> MooreMachine<D::Alphabet, D::EdgeColor> {
-    let neutral_high = dpa
-        .edge_colors()
-        .max()
-        .expect("There needs to be at least one color");
-    build_generic_priority_mapping(
-        dpa,
-        initial
-            .into_iter()
-            .map(|q| (q, neutral_high))
-            .collect::<Vec<_>>(),
-    )
-}
-
-fn build_generic_priority_mapping<D, Coll>(
-    dpa: D,
-    initial: Coll,
-) -> MooreMachine<D::Alphabet, D::EdgeColor>
-where
-    D: Congruence<EdgeColor = usize>,
-    Coll: StateCollection<D::StateIndex, D::EdgeColor>,
-{
-    let start = std::time::Instant::now();
-
-    let neutral_high = dpa
-        .edge_colors()
-        .max()
-        .expect("There needs to be at least one color");
-    let neutral_low = dpa.edge_colors().min().unwrap();
+impl<T: Oracle> Oracle for Exchange<T> {
+    type Alphabet = T::Alphabet;
+    type Output = T::Output;
 
-    let mut ts = DTS::with_capacity(dpa.alphabet().clone(), dpa.size());
-    let initial_idx: usize = ts.add_state(neutral_high);
-    assert_eq!(initial_idx, 0);
-
-    let mut cong = MooreMachine::from_parts(
-        ts.with_initial(initial_idx),
-        initial_idx,
-        MooreSemantics::default(),
-    );
-    let mut states = vec![initial];
-    let show_state = |state: &BTreeSet<(D::StateIndex, usize)>| {};
+    fn alphabet(&self) -> &Self::Alphabet {
+        self.oracle.alphabet()
+    }
 
-    let mut sink = None;
+    fn output<W: FiniteWord<Symbol = <Self::Alphabet as Alphabet>::Symbol>>(
+        &self,
+        word: W,
+    ) -> Self::Output {
+        let word = word.collect_vec();
+        let output = self.oracle.output(&word);
+        self.words.borrow_mut().insert(word, output.clone());
+        output
+    }
 
-    let mut iteration = 0;
-    let mut queue = VecDeque::from_iter([initial_idx]);
-    while let Some(source) = queue.pop_front() {
-        iteration += 1;
-        if iteration >= 300 {
-            panic!("Too many iterations");
+    fn output_sequence(&self, word: &[<Self::Alphabet as Alphabet>::Symbol]) -> Vec<Self::Output> {
+        let outputs = self.oracle.output_sequence(word);
+        let mut words = self.words.borrow_mut();
+        for (i, output) in outputs.iter().enumerate() {
+            words.insert(word[..=i].to_vec(), output.clone());
         }
+        outputs
+    }
 
-        assert!(source < states.len(), "Wrong state index in queue");
-        let state_with_color = states.get(source).cloned().unwrap();
-        trace!(
-            "Processing state {} with signature {}",
-            owo_colors::OwoColorize::blue(&source.show()),
-            state_with_color.show(),
-        );
-
-        'symbols: for sym in dpa.symbols() {
-            // this updates a set of states
-            let successor = Coll::from_pair_iter(state_with_color.pair_iter().map(|(q, c)| {
-                let t = dpa
-                    .transition(q, sym)
-                    .expect("DPA must be deterministic and complete");
-                (t.target(), std::cmp::min(c, t.color()))
-            }));
-            trace!("Computed successor on {}: {}", sym.show(), successor.show());
-            let max_color = successor.pair_iter().map(|(_, c)| c).max().unwrap();
-
-            if max_color == neutral_low {
-                // this can be redirected to the sink state, if it exists
-                if let Some(idx) = sink {
-                    trace!(
-                        "Adding edge to existing sink {idx}! {source}:{} --{}|{max_color}--> {idx}{}",
-                        state_with_color.show(),
-                        sym.show(),
-                        successor.show()
-                    );
-                    cong.add_edge(source, dpa.make_expression(sym), idx, max_color);
-                    continue 'symbols;
-                }
-
-                // we create the sink state
-                let idx = cong.add_state(max_color);
-                let s = Coll::sink();
-                assert!(!states.contains(&s));
-                states.push(s);
-
-                trace!("Creating sink state {idx} and adding transition {source}:{} --{}|{max_color}--> {idx}{}",
-                    state_with_color.show(),
-                    sym.show(),
-                    successor.show()
-                );
-                cong.add_edge(source, dpa.make_expression(sym), idx, max_color);
-                sink = Some(idx);
-                continue 'symbols;
-            }
-
-            if let Some(idx) = states.iter().position(|s| s == &successor) {
-                trace!(
-                    "Knwon target {idx}. Adding transition {source}:{} --{}|{max_color}--> {idx}{}",
-                    state_with_color.show(),
-                    sym.show(),
-                    successor.show()
-                );
-                cong.add_edge(source, dpa.make_expression(sym), idx, max_color);
-                continue 'symbols;
-            }
-
-            let idx = cong.add_state(max_color);
-            trace!(
-                "Adding transition {source}:{} --{}|{max_color}--> {idx}{}",
-                state_with_color.show(),
-                sym.show(),
-                successor.show()
-            );
-            cong.add_edge(source, dpa.make_expression(sym), idx, max_color);
-
-            assert_eq!(idx, states.len(), "Wrong state index");
-            states.push(successor);
-            queue.push_back(idx);
+    fn output_batch(
+        &self,
+        words: &[Vec<<Self::Alphabet as Alphabet>::Symbol>],
+    ) -> Vec<Self::Output> {
+        let outputs = self.oracle.output_batch(words);
+        let mut recorded = self.words.borrow_mut();
+        for (word, output) in words.iter().zip(&outputs) {
+            recorded.insert(word.clone(), output.clone());
         }
+        outputs
     }
 
-    if let Some(idx) = sink {
-        trace!("Completed sink {idx} with loops on all symbols, emitting colour {neutral_low}");
-        for sym in dpa.symbols() {
-            cong.add_edge(idx, dpa.make_expression(sym), idx, neutral_low);
+    fn equivalence<H>(
+        &self,
+        hypothesis: &H,
+    ) -> Result<(), Counterexample<Self::Alphabet, Self::Output>>
+    where
+        H: Hypothesis<Alphabet = Self::Alphabet, Output = Self::Output>,
+    {
+        let result = self.oracle.equivalence(hypothesis);
+        if let Err((word, output)) = &result {
+            self.words.borrow_mut().insert(word.clone(), output.clone());
         }
+        result
     }
-
-    debug!(
-        "Computation of priority mapping took {}μs",
-        start.elapsed().as_micros()
-    );
-    cong
-}
-
-pub fn characterize_moore<M>(dfa: M) -> DFASample<M>
-where
-    M: Congruence,
-    StateColor<M>: Color,
-{
-    todo!()
 }
 
-pub fn actively_exchanged_words_dfa<D: Congruence<EdgeColor = usize>>(dfa: D) -> DFASample<D> {
-    todo!()
+/// Runs [`LStar`] on a [`DFAOracle`] for `dfa` and collects all words that are exchanged between
+/// the learner and the oracle, i.e. the membership queries and the counterexamples.
+///
+/// The counterexamples of the oracle are length-lexicographically least, and a
+/// [`crate::active::SampleOracle`] for the returned sample answers equivalence queries with the
+/// least word of the sample that is misclassified. As every word of the sample is classified
+/// correctly, this is the same counterexample. So running [`LStar`] on such an oracle poses the
+/// same queries and obtains the same answers, which means it learns the same DFA.
+pub fn actively_exchanged_words_dfa<A: Alphabet>(dfa: &DFA<A>) -> FiniteSample<A> {
+    let exchange = Exchange::new(DFAOracle::new(dfa.clone()));
+    let learned: DFA<A> = LStar::new(dfa.alphabet().clone(), &exchange).infer();
+    debug!("learned DFA with {} states", learned.size());
+    exchange.into_words().into()
 }
 
-type MealySample<D> =
-    Sample<<D as TransitionSystem>::Alphabet, Vec<SymbolOf<D>>, <D as TransitionSystem>::EdgeColor>;
-
-pub fn actively_exchanged_words_mealy<D>(mm: D) -> MealySample<D>
+/// Runs [`LStar`] on a [`MealyOracle`] for `mm` and collects all words that are exchanged between
+/// the learner and the oracle, annotated with the output of the last transition that they take.
+/// Like for [`actively_exchanged_words_dfa`], the returned sample can be used as an oracle from
+/// which [`LStar`] learns the same Mealy machine.
+pub fn actively_exchanged_words_mealy<M>(mm: M) -> OutputSample<M::Alphabet, M::EdgeColor>
 where
-    D: Congruence,
-    EdgeColor<D>: Color,
+    M: MealyLike,
+    M::EdgeColor: Color,
 {
     let alphabet = mm.alphabet().clone();
-    let oracle = MealyOracle::new(mm, None);
-    // let mut lstar = LStar::logged(oracle, alphabet);
-    // let learned = lstar.learn();
-    // let mut sample = MealySample::new(alphabet);
-    todo!()
+    let exchange = Exchange::new(MealyOracle::new(mm));
+    let learned: MealyMachine<M::Alphabet, Void, M::EdgeColor> =
+        LStar::new(alphabet, &exchange).infer();
+    debug!("learned Mealy machine with {} states", learned.size());
+    exchange.into_words()
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Instant;
-
-    use automata::{
-        automaton::DPA,
-        transition_system::{Congruence, Deterministic, Dottable, NTS},
-        TransitionSystem,
-    };
-
-    use crate::passive::{
-        fwpm::FWPM,
-        precise::build_precise_dpa_for,
-        sample::characterize::{
-            build_generic_priority_mapping, priority_mapping_set_backed,
-            priority_mapping_vec_backed, right_congruence_by_omega_words,
-        },
-    };
-
-    #[test]
-    #[ignore]
-    fn priority_mapping() {
-        let mm = NTS::builder()
-            .with_transitions([
-                (0, 'a', 2, 1),
-                (0, 'b', 1, 0),
-                (1, 'a', 2, 1),
-                (1, 'b', 1, 2),
-                (2, 'a', 0, 0),
-                (2, 'b', 1, 0),
-            ])
-            .into_dpa(0);
-
-        let pmset = priority_mapping_set_backed(&mm, mm.state_indices());
-        let pmvec = priority_mapping_vec_backed(&mm, mm.state_indices()).minimize();
-        pmvec.display_rendered().unwrap();
-
-        let fwpm = FWPM::new(
-            mm.prefix_congruence()
-                .erase_edge_colors()
-                .erase_state_colors()
-                .right_congruence(),
-            [(0, pmset)].into_iter().collect(),
-        );
-
-        let precise = build_precise_dpa_for(fwpm);
-        mm.display_rendered().unwrap();
+    use automata::TransitionSystem;
+    use automata::automaton::{DFA, MealyMachine, MooreMachine};
+    use automata::core::alphabet::CharAlphabet;
+    use automata::random::{generate_random_mealy, generate_random_moore};
+    use automata::representation::IntoTs;
+    use automata::ts::TSBuilder;
+
+    use crate::active::{LStar, SampleOracle};
+    use crate::passive::{FiniteSample, dfa_rpni, moore_rpni};
+
+    fn random_dfa(size: usize) -> DFA {
+        generate_random_moore(2, 1, size)
+            .map_state_colors(|c| c == 1)
+            .into_dfa()
     }
 
-    #[test]
-    #[ignore]
-    fn priority_mapping_all_colors() {
-        let dpa = NTS::builder()
-            .with_transitions([
-                (0, 'a', 0, 1),
-                (0, 'b', 1, 0),
-                (0, 'c', 1, 0),
-                (1, 'a', 1, 1),
-                (1, 'b', 0, 2),
-                (1, 'c', 1, 1),
-                (2, 'a', 1, 2),
-                (2, 'b', 1, 2),
-                (2, 'c', 0, 0),
-            ])
-            .into_dpa(0);
-        let start = Instant::now();
-        let mm = priority_mapping_vec_backed(&dpa, dpa.state_indices());
-        debug!(
-            "Vec backed took {} microseconds",
-            start.elapsed().as_micros()
-        );
-        let start = Instant::now();
-        let mm = priority_mapping_set_backed(&dpa, dpa.state_indices());
-        debug!(
-            "Set backed took {} microseconds",
-            start.elapsed().as_micros()
-        );
-        mm.display_rendered();
-        mm.push_colors_to_outgoing_edges()
-            .minimize()
-            .display_rendered()
-            .unwrap();
+    fn equivalent(left: &DFA, right: &DFA) -> bool {
+        left.equivalent(right) && right.equivalent(left)
     }
 
-    fn simple_dpa() -> DPA {
-        NTS::builder()
-            .with_transitions([
-                (0, 'a', 0, 0),
-                (0, 'b', 1, 1),
-                (1, 'a', 3, 1),
-                (1, 'b', 1, 0),
+    #[test]
+    fn characterize_dfa() {
+        // accepts words in which the number of `a`s is divisible by three
+        let dfa = TSBuilder::without_edge_colors()
+            .with_state_colors([true, false, false])
+            .with_edges([
+                (0, 'a', 1),
+                (0, 'b', 0),
+                (1, 'a', 2),
+                (1, 'b', 1),
+                (2, 'a', 0),
+                (2, 'b', 2),
             ])
-            .into_dpa(0)
+            .into_dfa(0);
+        let sample: FiniteSample = super::characterize_moore(&dfa).into();
+        let learned = dfa_rpni(&sample);
+        assert_eq!(learned.size(), 3);
+        assert!(equivalent(&learned, &dfa));
+
+        for size in 1..12 {
+            let target = random_dfa(size);
+            let sample: FiniteSample = super::characterize_moore(&target).into();
+            let learned = dfa_rpni(&sample);
+            assert_eq!(learned.size(), target.size());
+            assert!(equivalent(&learned, &target));
+        }
     }
 
     #[test]
-    fn characterize_prefix_cong() {
-        let dpa = simple_dpa();
-
-        assert!(dpa
-            .as_ref()
-            .with_initial(0)
-            .into_dpa()
-            .witness_inequivalence(&dpa.as_ref().with_initial(1).into_dpa())
-            .is_some());
-
-        let cong = dpa.prefix_congruence();
-        assert_eq!(cong.size(), 2);
-        let sample = right_congruence_by_omega_words(&cong);
+    fn characterize_moore() {
+        for size in 1..12 {
+            let target = generate_random_moore(3, 3, size);
+            let sample = super::characterize_moore(&target);
+            let learned = moore_rpni(&sample);
+            assert_eq!(learned.size(), target.size());
+            assert!(learned.bisimilar(&target));
+        }
     }
 
     #[test]
-    #[ignore]
-    fn characterize_dpa() {
-        let dpa = simple_dpa();
-        let sample = super::dpa(dpa);
-        info!("{:?}", sample);
+    fn actively_exchanged_words() {
+        for size in 1..12 {
+            let target = random_dfa(size);
+            let sample = super::actively_exchanged_words_dfa(&target);
+            let oracle = SampleOracle::new(sample, false);
+            let learned: DFA = LStar::new(CharAlphabet::of_size(2), oracle).infer();
+            assert!(equivalent(&learned, &target));
+
+            let target = generate_random_mealy(3, 4, size);
+            let sample = super::actively_exchanged_words_mealy(&target);
+            let learned: MealyMachine = LStar::new(CharAlphabet::of_size(3), &sample).infer();
+            assert!(learned.witness_inequivalence(&target).is_none());
+        }
     }
 }
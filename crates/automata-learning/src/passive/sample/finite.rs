@@ -1,10 +1,84 @@
+use std::io::{BufRead, Write};
+
 use super::FiniteSample;
-use automata::core::alphabet::Alphabet;
+use automata::core::alphabet::{Alphabet, CharAlphabet};
+use automata::core::math;
 use itertools::Itertools;
 use thiserror::Error;
+use tracing::{debug, trace};
+
+/// Abstracts the types of errors that can occur when parsing a [`FiniteSample`]. Each error
+/// that can be attributed to a specific line carries its (one-based) number.
+#[derive(Debug, Clone, Eq, PartialEq, Error)]
+#[allow(missing_docs)]
+pub enum FiniteSampleParseError {
+    #[error("line {0}: missing sample header")]
+    MissingHeader(usize),
+    #[error("line {0}: missing sample alphabet")]
+    MissingAlphabet(usize),
+    #[error("line {line}: encountered malformed alphabet symbol `{symbol}`")]
+    MalformedAlphabetSymbol { line: usize, symbol: String },
+    #[error("line {0}: missing `positive:` or `negative:` block")]
+    MalformedSample(usize),
+    #[error("line {line}: symbol `{symbol}` is not part of the alphabet")]
+    UnknownSymbol { line: usize, symbol: String },
+    #[error("line {line}: sample is inconsistent, `{word}` is classified as positive and negative")]
+    Inconsistent { line: usize, word: String },
+    #[error("line {line}: malformed header `{header}`, expected number of words and alphabet size")]
+    MalformedHeader { line: usize, header: String },
+    #[error("line {line}: alphabet of size {size} is too large")]
+    AlphabetTooLarge { line: usize, size: usize },
+    #[error("line {line}: invalid label `{label}`")]
+    InvalidLabel { line: usize, label: String },
+    #[error("line {line}: malformed word `{word}`")]
+    MalformedWord { line: usize, word: String },
+    #[error("line {line}: word has {found} symbols but its length is given as {expected}")]
+    LengthMismatch {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("header announces {expected} words, but {found} were given")]
+    CountMismatch { expected: usize, found: usize },
+    #[error("line {line}: could not read input, reason: {reason}")]
+    Io { line: usize, reason: String },
+}
+
+/// The formats in which the words are given as sequences of symbol indices, preceded by a
+/// header with the number of words and the alphabet size. They only differ in the label that
+/// marks words whose classification is unknown, such words are skipped when parsing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum IndexedFormat {
+    Abbadingo,
+    Stamina,
+}
+
+impl IndexedFormat {
+    fn unknown_label(&self) -> &'static str {
+        match self {
+            IndexedFormat::Abbadingo => "-1",
+            IndexedFormat::Stamina => "?",
+        }
+    }
+}
 
-#[derive(Debug, Clone, Error)]
-pub enum FiniteSampleParseError {}
+/// Symbols used for the indices of the indexed formats, which suffice for the alphabets of
+/// the Abbadingo and StaMinA benchmarks.
+const INDEXED_SYMBOLS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Reads all lines from `read` and pairs them with their (one-based) number.
+fn numbered_lines<R: BufRead>(read: R) -> Result<Vec<(usize, String)>, FiniteSampleParseError> {
+    read.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.map(|line| (i + 1, line))
+                .map_err(|e| FiniteSampleParseError::Io {
+                    line: i + 1,
+                    reason: e.to_string(),
+                })
+        })
+        .collect()
+}
 
 impl<A: Alphabet> FiniteSample<A> {
     /// Create a new sample of finite words from the given alphabet and iterator over annotated words. The sample is given
@@ -32,3 +106,498 @@ impl<A: Alphabet> FiniteSample<A> {
         self.words().map(|w| w.len()).max().unwrap_or(0)
     }
 }
+
+impl FiniteSample<CharAlphabet> {
+    /// Parses a sample from the given numbered lines in the same format as an
+    /// [`crate::passive::OmegaSample`], except that the header is `finite` and that the empty
+    /// word is written as `ε`. Empty lines and lines starting with `#` are ignored.
+    /// ```text
+    /// finite
+    /// alphabet: a, b
+    /// positive:
+    /// ε
+    /// aab
+    /// negative:
+    /// b
+    /// ```
+    pub fn try_from_lines<I: IntoIterator<Item = (usize, String)>>(
+        lines: I,
+    ) -> Result<Self, FiniteSampleParseError> {
+        let mut lines = lines.into_iter().filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        });
+        // the number of the last line that was read, used for reporting missing lines
+        let mut last = 0;
+        let mut next = |last: &mut usize| {
+            lines
+                .next()
+                .inspect(|(number, _)| *last = *number)
+                .ok_or(*last + 1)
+        };
+
+        let (number, header) = next(&mut last).map_err(FiniteSampleParseError::MissingHeader)?;
+        if header.trim() != "finite" {
+            return Err(FiniteSampleParseError::MissingHeader(number));
+        }
+
+        let (number, line) = next(&mut last).map_err(FiniteSampleParseError::MissingAlphabet)?;
+        let symbols = match line.split_once(':') {
+            Some((directive, symbols)) if directive.trim() == "alphabet" => symbols,
+            _ => return Err(FiniteSampleParseError::MissingAlphabet(number)),
+        };
+        let alphabet = symbols
+            .split(',')
+            .map(|symbol| {
+                let symbol = symbol.trim();
+                match symbol.chars().exactly_one() {
+                    Ok(sym) if sym != 'ε' => Ok(sym),
+                    _ => Err(FiniteSampleParseError::MalformedAlphabetSymbol {
+                        line: number,
+                        symbol: symbol.to_string(),
+                    }),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(CharAlphabet::new)?;
+
+        let (number, line) = next(&mut last).map_err(FiniteSampleParseError::MalformedSample)?;
+        if line.trim() != "positive:" {
+            return Err(FiniteSampleParseError::MalformedSample(number));
+        }
+
+        let mut words: math::Map<Vec<char>, bool> = math::Map::default();
+        let mut classification = true;
+        while let Ok((number, line)) = next(&mut last) {
+            let line = line.trim();
+            if line == "negative:" {
+                if !classification {
+                    return Err(FiniteSampleParseError::MalformedSample(number));
+                }
+                classification = false;
+                continue;
+            }
+            trace!("Parsing word \"{line}\" classified as {classification}");
+            let word = if line == "ε" {
+                vec![]
+            } else {
+                line.chars()
+                    .map(|sym| {
+                        if alphabet.contains(sym) {
+                            Ok(sym)
+                        } else {
+                            Err(FiniteSampleParseError::UnknownSymbol {
+                                line: number,
+                                symbol: sym.to_string(),
+                            })
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            Self::insert_parsed(&mut words, word, classification, number, line)?;
+        }
+        if classification {
+            return Err(FiniteSampleParseError::MalformedSample(last + 1));
+        }
+
+        Ok(Self::new_finite(alphabet, words))
+    }
+
+    /// Parses a sample from a string, see [`Self::try_from_lines`] for the format.
+    pub fn try_from_str(input: &str) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_lines(
+            input
+                .lines()
+                .map(|l| l.to_string())
+                .enumerate()
+                .map(|(i, l)| (i + 1, l)),
+        )
+    }
+
+    /// Parses a sample from a reader, see [`Self::try_from_lines`] for the format.
+    pub fn try_from_read<R: BufRead>(read: R) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_lines(numbered_lines(read)?)
+    }
+
+    /// Writes the sample in the format that is understood by [`Self::try_from_lines`]. Symbols
+    /// that this format reserves, i.e. whitespace, `,`, `:`, `#` and `ε`, would not be read back
+    /// correctly, so for alphabets containing them an error of kind
+    /// [`std::io::ErrorKind::InvalidInput`] is returned.
+    pub fn write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        if let Some(sym) = self
+            .alphabet
+            .universe()
+            .find(|sym| sym.is_whitespace() || [',', ':', '#', 'ε'].contains(sym))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("symbol `{sym}` cannot be written in the text format"),
+            ));
+        }
+        writeln!(w, "finite")?;
+        writeln!(w, "alphabet: {}", self.alphabet.universe().join(", "))?;
+        writeln!(w, "positive:")?;
+        for word in self.positive_words() {
+            writeln!(w, "{}", Self::show_word(word))?;
+        }
+        writeln!(w, "negative:")?;
+        for word in self.negative_words() {
+            writeln!(w, "{}", Self::show_word(word))?;
+        }
+        Ok(())
+    }
+
+    /// Parses a sample in the format of the Abbadingo One competition. The first line holds the
+    /// number of words and the size of the alphabet, each following line a word, given by its
+    /// label (`1` for positive, `0` for negative), its length and the indices of its symbols.
+    /// Words with the label `-1` are unlabeled and therefore skipped. The symbol with index `i`
+    /// is the `i`-th of `a` to `z`, `A` to `Z` and `0` to `9`.
+    /// ```text
+    /// 3 2
+    /// 1 3 0 1 1
+    /// 0 0
+    /// 0 2 1 0
+    /// ```
+    pub fn try_from_abbadingo_str(input: &str) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_indexed(
+            input
+                .lines()
+                .map(|l| l.to_string())
+                .enumerate()
+                .map(|(i, l)| (i + 1, l)),
+            IndexedFormat::Abbadingo,
+        )
+    }
+
+    /// Parses a sample in the Abbadingo format from a reader, see
+    /// [`Self::try_from_abbadingo_str`].
+    pub fn try_from_abbadingo_read<R: BufRead>(read: R) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_indexed(numbered_lines(read)?, IndexedFormat::Abbadingo)
+    }
+
+    /// Writes the sample in the Abbadingo format, see [`Self::try_from_abbadingo_str`]. Symbols
+    /// are written as their index in the alphabet. As the index `i` is read back as the `i`-th of
+    /// `a` to `z`, `A` to `Z` and `0` to `9`, the alphabet must be a prefix of this sequence,
+    /// otherwise an error of kind [`std::io::ErrorKind::InvalidInput`] is returned.
+    pub fn write_abbadingo<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.write_indexed(w)
+    }
+
+    /// Parses a sample in the format of the StaMinA competition. It coincides with the
+    /// Abbadingo format described in [`Self::try_from_abbadingo_str`], except that unlabeled
+    /// words are marked with `?`.
+    pub fn try_from_stamina_str(input: &str) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_indexed(
+            input
+                .lines()
+                .map(|l| l.to_string())
+                .enumerate()
+                .map(|(i, l)| (i + 1, l)),
+            IndexedFormat::Stamina,
+        )
+    }
+
+    /// Parses a sample in the StaMinA format from a reader, see [`Self::try_from_stamina_str`].
+    pub fn try_from_stamina_read<R: BufRead>(read: R) -> Result<Self, FiniteSampleParseError> {
+        Self::try_from_indexed(numbered_lines(read)?, IndexedFormat::Stamina)
+    }
+
+    /// Writes the sample in the StaMinA format, see [`Self::try_from_stamina_str`]. The alphabet
+    /// is subject to the same restriction as for [`Self::write_abbadingo`].
+    pub fn write_stamina<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        self.write_indexed(w)
+    }
+
+    fn try_from_indexed<I: IntoIterator<Item = (usize, String)>>(
+        lines: I,
+        format: IndexedFormat,
+    ) -> Result<Self, FiniteSampleParseError> {
+        let mut lines = lines
+            .into_iter()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let Some((number, header)) = lines.next() else {
+            return Err(FiniteSampleParseError::MissingHeader(1));
+        };
+        let malformed_header = || FiniteSampleParseError::MalformedHeader {
+            line: number,
+            header: header.clone(),
+        };
+        let (expected, size) = header
+            .split_whitespace()
+            .map(|n| n.parse::<usize>())
+            .collect_tuple()
+            .ok_or_else(malformed_header)?;
+        let (expected, size) = (
+            expected.map_err(|_| malformed_header())?,
+            size.map_err(|_| malformed_header())?,
+        );
+        if size > INDEXED_SYMBOLS.len() {
+            return Err(FiniteSampleParseError::AlphabetTooLarge { line: number, size });
+        }
+        let alphabet = CharAlphabet::new(INDEXED_SYMBOLS.chars().take(size).collect());
+
+        let mut words: math::Map<Vec<char>, bool> = math::Map::default();
+        let mut found = 0;
+        for (number, line) in lines {
+            found += 1;
+            let mut tokens = line.split_whitespace();
+            let label = tokens.next().expect("line is not empty");
+            let classification = match label {
+                "1" => true,
+                "0" => false,
+                unknown if unknown == format.unknown_label() => {
+                    debug!("Skipping unlabeled word in line {number}");
+                    continue;
+                }
+                _ => {
+                    return Err(FiniteSampleParseError::InvalidLabel {
+                        line: number,
+                        label: label.to_string(),
+                    });
+                }
+            };
+            let malformed_word = || FiniteSampleParseError::MalformedWord {
+                line: number,
+                word: line.trim().to_string(),
+            };
+            let length = tokens
+                .next()
+                .ok_or_else(malformed_word)?
+                .parse::<usize>()
+                .map_err(|_| malformed_word())?;
+            let word = tokens
+                .map(|token| {
+                    let index = token.parse::<usize>().map_err(|_| malformed_word())?;
+                    if index < size {
+                        Ok(alphabet[index])
+                    } else {
+                        Err(FiniteSampleParseError::UnknownSymbol {
+                            line: number,
+                            symbol: token.to_string(),
+                        })
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if word.len() != length {
+                return Err(FiniteSampleParseError::LengthMismatch {
+                    line: number,
+                    expected: length,
+                    found: word.len(),
+                });
+            }
+            Self::insert_parsed(&mut words, word, classification, number, line.trim())?;
+        }
+        if found != expected {
+            return Err(FiniteSampleParseError::CountMismatch { expected, found });
+        }
+
+        Ok(Self::new_finite(alphabet, words))
+    }
+
+    fn write_indexed<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        if self.alphabet.size() > INDEXED_SYMBOLS.len()
+            || !self
+                .alphabet
+                .universe()
+                .zip(INDEXED_SYMBOLS.chars())
+                .all(|(a, b)| a == b)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "alphabet {{{}}} cannot be written with indexed symbols",
+                    self.alphabet.universe().join(", ")
+                ),
+            ));
+        }
+        let index: math::Map<char, usize> = self
+            .alphabet
+            .universe()
+            .enumerate()
+            .map(|(i, sym)| (sym, i))
+            .collect();
+        writeln!(w, "{} {}", self.count_words(), self.alphabet.size())?;
+        for (word, classification) in self
+            .positive_words()
+            .map(|word| (word, 1))
+            .chain(self.negative_words().map(|word| (word, 0)))
+        {
+            write!(w, "{classification} {}", word.len())?;
+            for sym in word {
+                write!(w, " {}", index[sym])?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    /// Inserts a parsed word, reporting an error if it was already classified differently.
+    fn insert_parsed(
+        words: &mut math::Map<Vec<char>, bool>,
+        word: Vec<char>,
+        classification: bool,
+        line: usize,
+        shown: &str,
+    ) -> Result<(), FiniteSampleParseError> {
+        match words.insert(word, classification) {
+            Some(old) if old != classification => Err(FiniteSampleParseError::Inconsistent {
+                line,
+                word: shown.to_string(),
+            }),
+            Some(_) => {
+                debug!("Duplicate word found in line {line}");
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn show_word(word: &[char]) -> String {
+        if word.is_empty() {
+            "ε".to_string()
+        } else {
+            word.iter().collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::core::alphabet::CharAlphabet;
+
+    use super::FiniteSampleParseError;
+    use crate::passive::FiniteSample;
+
+    #[test]
+    fn parse_and_write_finite_sample() {
+        let sample = FiniteSample::try_from_str(
+            r#"finite
+            alphabet: a, b
+            # the empty word is positive
+            positive:
+            ε
+            aab
+
+            negative:
+            b
+            ba"#,
+        )
+        .unwrap();
+        assert_eq!(sample.alphabet, CharAlphabet::of_size(2));
+        assert_eq!(sample.count_positive_words(), 2);
+        assert_eq!(sample.count_negative_words(), 2);
+        assert_eq!(sample.classify(&vec![]), Some(true));
+        assert_eq!(sample.classify(&vec!['b', 'a']), Some(false));
+
+        let mut written = vec![];
+        sample.write(&mut written).unwrap();
+        let reparsed = FiniteSample::try_from_read(written.as_slice()).unwrap();
+        assert_eq!(reparsed, sample);
+
+        // symbols that are reserved by the format would not be read back
+        for sym in [',', ':', '#', 'ε', ' ', '\t'] {
+            let sample =
+                FiniteSample::new_finite(CharAlphabet::new(vec!['a', sym]), [(vec![sym], true)]);
+            let error = sample.write(&mut vec![]).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn finite_sample_errors() {
+        let parse = |input: &str| FiniteSample::try_from_str(input).unwrap_err();
+        assert_eq!(parse("omega"), FiniteSampleParseError::MissingHeader(1));
+        assert_eq!(
+            parse("finite\nalphabet: a, bc"),
+            FiniteSampleParseError::MalformedAlphabetSymbol {
+                line: 2,
+                symbol: "bc".to_string()
+            }
+        );
+        assert_eq!(
+            parse("finite\nalphabet: a, b\npositive:\naab\nnegative:\nac"),
+            FiniteSampleParseError::UnknownSymbol {
+                line: 6,
+                symbol: "c".to_string()
+            }
+        );
+        assert_eq!(
+            parse("finite\nalphabet: a\npositive:\naa\n\nnegative:\na\naa"),
+            FiniteSampleParseError::Inconsistent {
+                line: 8,
+                word: "aa".to_string()
+            }
+        );
+        assert_eq!(
+            parse("finite\nalphabet: a\npositive:\na"),
+            FiniteSampleParseError::MalformedSample(5)
+        );
+    }
+
+    #[test]
+    fn abbadingo_and_stamina() {
+        let sample =
+            FiniteSample::try_from_abbadingo_str("4 2\n1 3 0 1 1\n0 0\n-1 1 0\n0 2 1 0\n").unwrap();
+        assert_eq!(sample.alphabet, CharAlphabet::of_size(2));
+        assert_eq!(sample.count_words(), 3);
+        assert_eq!(sample.classify(&vec!['a', 'b', 'b']), Some(true));
+        assert_eq!(sample.classify(&vec![]), Some(false));
+        assert_eq!(sample.classify(&vec!['a']), None);
+
+        let mut written = vec![];
+        sample.write_abbadingo(&mut written).unwrap();
+        let reparsed = FiniteSample::try_from_abbadingo_read(written.as_slice()).unwrap();
+        assert_eq!(reparsed, sample);
+
+        let stamina = FiniteSample::try_from_stamina_str("2 3\n1 2 2 0\n? 1 1\n").unwrap();
+        assert_eq!(stamina.alphabet, CharAlphabet::of_size(3));
+        assert_eq!(stamina.classify(&vec!['c', 'a']), Some(true));
+        let mut written = vec![];
+        stamina.write_stamina(&mut written).unwrap();
+        assert_eq!(
+            FiniteSample::try_from_stamina_read(written.as_slice()).unwrap(),
+            stamina
+        );
+
+        // symbols are read back by their index, so other alphabets would be relabeled
+        let sample = FiniteSample::new_finite(
+            CharAlphabet::new(vec!['x', 'y']),
+            [(vec!['x', 'y'], true), (vec!['y'], false)],
+        );
+        let error = sample.write_abbadingo(&mut vec![]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        let error = sample.write_stamina(&mut vec![]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        assert_eq!(
+            FiniteSample::try_from_stamina_str("2 2\n1 1 0\n-1 1 1\n").unwrap_err(),
+            FiniteSampleParseError::InvalidLabel {
+                line: 3,
+                label: "-1".to_string()
+            }
+        );
+        assert_eq!(
+            FiniteSample::try_from_abbadingo_str("1 2\n1 2 0 2\n").unwrap_err(),
+            FiniteSampleParseError::UnknownSymbol {
+                line: 2,
+                symbol: "2".to_string()
+            }
+        );
+        assert_eq!(
+            FiniteSample::try_from_abbadingo_str("1 2\n1 3 0 1\n").unwrap_err(),
+            FiniteSampleParseError::LengthMismatch {
+                line: 2,
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            FiniteSample::try_from_abbadingo_str("3 2\n1 1 0\n").unwrap_err(),
+            FiniteSampleParseError::CountMismatch {
+                expected: 3,
+                found: 1
+            }
+        );
+    }
+}
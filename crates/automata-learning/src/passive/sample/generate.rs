@@ -0,0 +1,413 @@
+use std::{hash::Hash, ops::RangeInclusive};
+
+use automata::core::alphabet::{Alphabet, Symbol};
+use automata::core::math;
+use automata::core::word::{ReducedOmegaWord, Word};
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use tracing::debug;
+
+use super::SetSample;
+
+/// Words that can be drawn at random. Each symbol is chosen uniformly from the given
+/// symbols, the shape of the word is controlled by [`Self::Lengths`].
+pub trait RandomWord: Word + Hash + Eq + Sized {
+    /// Determines the range from which the length (or lengths) of a word is drawn uniformly.
+    type Lengths;
+
+    /// Draws a random word over `symbols`. Panics if there are no symbols.
+    fn random<R: Rng + ?Sized>(
+        symbols: &[Self::Symbol],
+        lengths: &Self::Lengths,
+        rng: &mut R,
+    ) -> Self;
+}
+
+/// Draws a finite word whose length is chosen uniformly from `lengths`.
+fn random_symbols<S: Symbol, R: Rng + ?Sized>(
+    symbols: &[S],
+    lengths: &RangeInclusive<usize>,
+    rng: &mut R,
+) -> Vec<S> {
+    assert!(!symbols.is_empty(), "cannot draw words without symbols");
+    let length = rng.gen_range(lengths.clone());
+    (0..length).map(|_| *symbols.choose(rng).unwrap()).collect()
+}
+
+impl<S: Symbol> RandomWord for Vec<S> {
+    type Lengths = RangeInclusive<usize>;
+
+    fn random<R: Rng + ?Sized>(symbols: &[S], lengths: &Self::Lengths, rng: &mut R) -> Self {
+        random_symbols(symbols, lengths, rng)
+    }
+}
+
+impl<S: Symbol> RandomWord for ReducedOmegaWord<S> {
+    /// The lengths of the spoke and of the cycle, the latter must not contain `0`.
+    type Lengths = (RangeInclusive<usize>, RangeInclusive<usize>);
+
+    fn random<R: Rng + ?Sized>(symbols: &[S], (spoke, cycle): &Self::Lengths, rng: &mut R) -> Self {
+        assert!(*cycle.start() > 0, "the cycle of a word cannot be empty");
+        let spoke = random_symbols(symbols, spoke, rng);
+        ReducedOmegaWord::ultimately_periodic(spoke, random_symbols(symbols, cycle, rng))
+    }
+}
+
+/// Generates random samples from a target and splits or perturbs existing samples. All random
+/// choices are made by an RNG that is seeded on creation, so the same sequence of calls on a
+/// generator with the same seed always gives the same results.
+///
+/// Since words are drawn until enough distinct (or suitably classified) words are found,
+/// each method gives up after a fixed number of draws per requested word, which can be set
+/// with [`Self::with_attempts_per_word`].
+///
+/// # Example
+/// ```
+/// use automata::core::alphabet::CharAlphabet;
+/// use automata_learning::passive::sample::SampleGenerator;
+///
+/// let mut generator = SampleGenerator::new(42);
+/// // words starting with an `a`
+/// let sample = generator.balanced_sample(
+///     CharAlphabet::of_size(2),
+///     &(1..=6),
+///     10,
+///     |word: &Vec<char>| word.first() == Some(&'a'),
+/// );
+/// assert_eq!(sample.count_positive_words(), 10);
+/// assert_eq!(sample.count_negative_words(), 10);
+///
+/// let (train, test) = generator.train_test_split(&sample, 0.2);
+/// assert_eq!(train.count_words(), 16);
+/// assert_eq!(test.count_words(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SampleGenerator {
+    rng: StdRng,
+    attempts_per_word: usize,
+}
+
+impl SampleGenerator {
+    /// Creates a new generator whose RNG is seeded with `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            attempts_per_word: 100,
+        }
+    }
+
+    /// Sets the number of draws per requested word after which generation is given up.
+    pub fn with_attempts_per_word(self, attempts_per_word: usize) -> Self {
+        assert!(attempts_per_word > 0, "at least one attempt is necessary");
+        Self {
+            attempts_per_word,
+            ..self
+        }
+    }
+
+    /// Draws `count` distinct random words over `alphabet`. If not enough distinct words exist
+    /// with the given `lengths`, fewer words are returned.
+    pub fn words<A: Alphabet, W: RandomWord<Symbol = A::Symbol>>(
+        &mut self,
+        alphabet: &A,
+        lengths: &W::Lengths,
+        count: usize,
+    ) -> math::Set<W> {
+        let symbols = alphabet.universe().collect_vec();
+        let mut words = math::Set::with_capacity(count);
+        for _ in 0..count * self.attempts_per_word {
+            if words.len() >= count {
+                break;
+            }
+            words.insert(W::random(&symbols, lengths, &mut self.rng));
+        }
+        if words.len() < count {
+            debug!("only found {} of {count} distinct words", words.len());
+        }
+        words
+    }
+
+    /// Draws `count` distinct random words (see [`Self::words`]) and classifies them with
+    /// `target`, which is typically the acceptance of a target automaton.
+    pub fn sample<A, W, F>(
+        &mut self,
+        alphabet: A,
+        lengths: &W::Lengths,
+        count: usize,
+        target: F,
+    ) -> SetSample<A, W>
+    where
+        A: Alphabet,
+        W: RandomWord<Symbol = A::Symbol>,
+        F: FnMut(&W) -> bool,
+    {
+        let words = self.words(&alphabet, lengths, count);
+        SetSample::labelled_by(alphabet, words, target)
+    }
+
+    /// Draws distinct random words until `per_class` of them are classified as positive and
+    /// `per_class` as negative by `target`. If not enough words of one class are found, the
+    /// other class is cut down to the same size, so the sample is balanced in any case.
+    pub fn balanced_sample<A, W, F>(
+        &mut self,
+        alphabet: A,
+        lengths: &W::Lengths,
+        per_class: usize,
+        mut target: F,
+    ) -> SetSample<A, W>
+    where
+        A: Alphabet,
+        W: RandomWord<Symbol = A::Symbol>,
+        F: FnMut(&W) -> bool,
+    {
+        let symbols = alphabet.universe().collect_vec();
+        let mut sample = SetSample::new_for_alphabet(alphabet);
+        for _ in 0..2 * per_class * self.attempts_per_word {
+            if sample.count_positive_words() >= per_class
+                && sample.count_negative_words() >= per_class
+            {
+                break;
+            }
+            let word = W::random(&symbols, lengths, &mut self.rng);
+            if sample.contains(&word) {
+                continue;
+            }
+            let classification = target(&word);
+            let found = if classification {
+                sample.count_positive_words()
+            } else {
+                sample.count_negative_words()
+            };
+            if found < per_class {
+                sample.insert(word, classification);
+            }
+        }
+        let size = sample
+            .count_positive_words()
+            .min(sample.count_negative_words());
+        if size < per_class {
+            debug!("only found {size} of {per_class} words per class");
+        }
+        sample.positive.truncate(size);
+        sample.negative.truncate(size);
+        sample
+    }
+
+    /// Randomly removes words from the larger class of `sample` until both classes have the
+    /// same size.
+    pub fn balance<A: Alphabet, W: Word<Symbol = A::Symbol> + Hash + Eq + Clone>(
+        &mut self,
+        sample: &SetSample<A, W>,
+    ) -> SetSample<A, W> {
+        let size = sample
+            .count_positive_words()
+            .min(sample.count_negative_words());
+        let mut choose = |words: &math::Set<W>| {
+            let mut words = words.iter().cloned().collect_vec();
+            words.shuffle(&mut self.rng);
+            words.truncate(size);
+            words.into_iter().collect()
+        };
+        SetSample {
+            alphabet: sample.alphabet.clone(),
+            positive: choose(&sample.positive),
+            negative: choose(&sample.negative),
+        }
+    }
+
+    /// Randomly splits `sample` into a training and a test sample, such that the latter
+    /// contains (roughly) the fraction `test_fraction` of the words. The split is stratified,
+    /// so both parts have (roughly) the same ratio of positive and negative words as `sample`.
+    pub fn train_test_split<A: Alphabet, W: Word<Symbol = A::Symbol> + Hash + Eq + Clone>(
+        &mut self,
+        sample: &SetSample<A, W>,
+        test_fraction: f64,
+    ) -> (SetSample<A, W>, SetSample<A, W>) {
+        assert!(
+            (0.0..=1.0).contains(&test_fraction),
+            "test fraction must be in [0, 1]"
+        );
+        let mut train = SetSample::new_for_alphabet(sample.alphabet.clone());
+        let mut test = SetSample::new_for_alphabet(sample.alphabet.clone());
+        for (words, classification) in [(&sample.positive, true), (&sample.negative, false)] {
+            let mut words = words.iter().cloned().collect_vec();
+            words.shuffle(&mut self.rng);
+            let test_size = (words.len() as f64 * test_fraction).round() as usize;
+            for (i, word) in words.into_iter().enumerate() {
+                if i < test_size {
+                    test.insert(word, classification);
+                } else {
+                    train.insert(word, classification);
+                }
+            }
+        }
+        (train, test)
+    }
+
+    /// Randomly partitions `sample` into `k` folds and returns, for each fold, the pair
+    /// consisting of the training sample made up of all other folds and the fold itself as test
+    /// sample. The folds are stratified, i.e. positive and negative words are distributed
+    /// evenly among them.
+    pub fn k_folds<A: Alphabet, W: Word<Symbol = A::Symbol> + Hash + Eq + Clone>(
+        &mut self,
+        sample: &SetSample<A, W>,
+        k: usize,
+    ) -> Vec<(SetSample<A, W>, SetSample<A, W>)> {
+        assert!(k > 1, "at least two folds are necessary");
+        let mut folds = vec![SetSample::new_for_alphabet(sample.alphabet.clone()); k];
+        // continue with the next fold, so that the folds differ in size by at most one word
+        let mut next = 0;
+        for (words, classification) in [(&sample.positive, true), (&sample.negative, false)] {
+            let mut words = words.iter().cloned().collect_vec();
+            words.shuffle(&mut self.rng);
+            for word in words {
+                folds[next].insert(word, classification);
+                next = (next + 1) % k;
+            }
+        }
+        (0..k)
+            .map(|i| {
+                let mut train = SetSample::new_for_alphabet(sample.alphabet.clone());
+                for fold in folds.iter().enumerate().filter(|(j, _)| *j != i) {
+                    train.append(fold.1.clone());
+                }
+                (train, folds[i].clone())
+            })
+            .collect()
+    }
+
+    /// Flips the classification of each word in `sample` independently with the given
+    /// `probability`.
+    pub fn with_label_noise<A: Alphabet, W: Word<Symbol = A::Symbol> + Hash + Eq + Clone>(
+        &mut self,
+        sample: &SetSample<A, W>,
+        probability: f64,
+    ) -> SetSample<A, W> {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "probability must be in [0, 1]"
+        );
+        let mut noisy = SetSample::new_for_alphabet(sample.alphabet.clone());
+        for (word, classification) in sample.entries() {
+            let flip = self.rng.gen_bool(probability);
+            noisy.insert(word.clone(), classification != flip);
+        }
+        noisy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use automata::TransitionSystem;
+    use automata::automaton::DFA;
+    use automata::core::alphabet::CharAlphabet;
+    use automata::core::word::{OmegaWord, ReducedOmegaWord};
+    use itertools::Itertools;
+
+    use super::SampleGenerator;
+    use crate::passive::{FiniteSample, OmegaSample};
+
+    #[test]
+    fn generation_is_reproducible() {
+        let draw = |seed| {
+            SampleGenerator::new(seed).sample(
+                CharAlphabet::of_size(2),
+                &(0..=5, 1..=3),
+                20,
+                |word: &ReducedOmegaWord<char>| word.cycle().contains(&'a'),
+            )
+        };
+        let sample: OmegaSample = draw(7);
+        assert_eq!(sample.count_words(), 20);
+        assert_eq!(sample, draw(7));
+        assert!(sample.positive_words().all(|w| w.cycle().contains(&'a')));
+        assert!(sample.negative_words().all(|w| !w.cycle().contains(&'a')));
+
+        // there are only 7 words of length at most 2
+        let words =
+            SampleGenerator::new(0).words::<_, Vec<char>>(&CharAlphabet::of_size(2), &(0..=2), 10);
+        assert_eq!(words.len(), 7);
+    }
+
+    #[test]
+    fn balanced_samples_from_dfa() {
+        // accepts words with an even number of `a`s
+        let dfa = DFA::builder()
+            .with_state_colors([true, false])
+            .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 0), (1, 'b', 1)])
+            .into_dfa(0);
+
+        let mut generator = SampleGenerator::new(3);
+        let sample: FiniteSample =
+            generator.balanced_sample(dfa.alphabet().clone(), &(0..=8), 25, |word: &Vec<char>| {
+                dfa.accepts(word)
+            });
+        assert_eq!(sample.count_positive_words(), 25);
+        assert_eq!(sample.count_negative_words(), 25);
+        assert!(sample.entries().all(|(w, c)| dfa.accepts(w) == c));
+
+        // only `a` is rejected among words of length at most one
+        let sample: FiniteSample =
+            generator.balanced_sample(dfa.alphabet().clone(), &(0..=1), 5, |word: &Vec<char>| {
+                dfa.accepts(word)
+            });
+        assert_eq!(sample.count_positive_words(), 1);
+        assert_eq!(sample.negative_words().collect_vec(), [&vec!['a']]);
+
+        let unbalanced: FiniteSample =
+            generator.sample(dfa.alphabet().clone(), &(3..=3), 8, |word: &Vec<char>| {
+                dfa.accepts(word)
+            });
+        let balanced = generator.balance(&unbalanced);
+        assert_eq!(balanced.count_positive_words(), 4);
+        assert_eq!(balanced.count_negative_words(), 4);
+    }
+
+    #[test]
+    fn splits_and_noise() {
+        let mut generator = SampleGenerator::new(11);
+        let sample: FiniteSample = generator.sample(
+            CharAlphabet::of_size(2),
+            &(0..=10),
+            100,
+            |word: &Vec<char>| word.first() == Some(&'a'),
+        );
+        let positive = sample.count_positive_words();
+
+        let (train, test) = generator.train_test_split(&sample, 0.25);
+        assert_eq!(train.count_words() + test.count_words(), 100);
+        assert!(test.count_words().abs_diff(25) <= 1);
+        assert!(test.count_positive_words().abs_diff(positive / 4) <= 1);
+        assert!(train.words().all(|w| !test.contains(w)));
+        assert_eq!(train.as_joined(&test).count_words(), 100);
+
+        let folds = generator.k_folds(&sample, 5);
+        assert_eq!(folds.len(), 5);
+        for (train, test) in &folds {
+            assert!(test.count_words().abs_diff(20) <= 1);
+            assert_eq!(train.count_words() + test.count_words(), 100);
+            assert!(train.entries().all(|(w, c)| sample.classify(w) == Some(c)));
+        }
+        let tested = folds
+            .iter()
+            .map(|(_, test)| test.count_words())
+            .sum::<usize>();
+        assert_eq!(tested, 100);
+
+        assert_eq!(generator.with_label_noise(&sample, 0.0), sample);
+        let flipped = generator.with_label_noise(&sample, 1.0);
+        assert!(
+            flipped
+                .entries()
+                .all(|(w, c)| sample.classify(w) == Some(!c))
+        );
+        let noisy = generator.with_label_noise(&sample, 0.3);
+        let changed = noisy
+            .entries()
+            .filter(|(w, c)| sample.classify(*w) != Some(*c))
+            .count();
+        assert!((10..=50).contains(&changed));
+    }
+}
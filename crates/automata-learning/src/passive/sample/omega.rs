@@ -1,9 +1,14 @@
-use std::{collections::VecDeque, io::BufRead};
+use std::{
+    collections::VecDeque,
+    io::{BufRead, Write},
+};
 
-use automata::core::alphabet::{Alphabet, CharAlphabet};
+use automata::core::alphabet::{
+    Alphabet, CharAlphabet, NamedAlphabet, PropAlphabet, RawSymbolRepr, Symbol, SymbolSyntax,
+};
 use automata::core::math;
 use automata::core::word::{
-    OmegaWord, PeriodicOmegaWord, ReducedOmegaWord, ReducedParseError, Word,
+    OmegaWord, PeriodicOmegaWord, ReducedOmegaWord, ReducedParseError, Rotate, Word,
 };
 use automata::representation::CollectTs;
 use automata::ts::Deterministic;
@@ -44,6 +49,10 @@ pub enum OmegaSampleParseError {
     MalformedSample,
     #[error("could not parse given omega word, reason: {0}")]
     OmegaWordParseError(ReducedParseError),
+    #[error("malformed record `{0}`, expected spoke, cycle and classification")]
+    MalformedRecord(String),
+    #[error("could not read sample, reason: {0}")]
+    Io(String),
 }
 
 impl<A: Alphabet> OmegaSample<A> {
@@ -53,7 +62,125 @@ impl<A: Alphabet> OmegaSample<A> {
             .collect_right_congruence()
     }
 }
-impl OmegaSample<CharAlphabet> {
+/// An alphabet over which an [`OmegaSample`] can be read and written. Apart from the
+/// [`SymbolSyntax`] which is used for the words, such an alphabet knows how to declare itself in
+/// the second line of a sample.
+pub trait SampleAlphabet: SymbolSyntax + Sized {
+    /// Parses the alphabet from its declaration.
+    fn parse_declaration(line: &str) -> Result<Self, OmegaSampleParseError>;
+    /// Produces the declaration of the alphabet, such that [`Self::parse_declaration`] reads it
+    /// back.
+    fn declaration(&self) -> String;
+}
+
+/// Splits a declaration of the form `directive: x, y, z` into its trimmed entries and
+/// ensures that each of them is non-empty and can not be confused with the syntax of words.
+fn declared_entries<'a>(
+    line: &'a str,
+    directive: &str,
+) -> Result<Vec<&'a str>, OmegaSampleParseError> {
+    let (header, entries) = line
+        .split_once(':')
+        .ok_or(OmegaSampleParseError::MissingDelimiter)?;
+    if header.trim() != directive {
+        return Err(OmegaSampleParseError::MissingAlphabet);
+    }
+    entries
+        .split(',')
+        .map(|entry| {
+            let entry = entry.trim();
+            if entry.is_empty() || entry.contains(|c: char| c.is_whitespace() || "{}!".contains(c))
+            {
+                Err(OmegaSampleParseError::MalformedAlphabetSymbol(
+                    entry.to_string(),
+                ))
+            } else {
+                Ok(entry)
+            }
+        })
+        .collect()
+}
+
+impl SampleAlphabet for CharAlphabet {
+    /// Expects a declaration `alphabet: a, b` of single characters.
+    fn parse_declaration(line: &str) -> Result<Self, OmegaSampleParseError> {
+        declared_entries(line, "alphabet")?
+            .into_iter()
+            .map(|symbol| {
+                symbol
+                    .chars()
+                    .exactly_one()
+                    .map_err(|_| OmegaSampleParseError::MalformedAlphabetSymbol(symbol.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(CharAlphabet::new)
+    }
+
+    fn declaration(&self) -> String {
+        format!("alphabet: {}", self.universe().join(", "))
+    }
+}
+
+impl SampleAlphabet for NamedAlphabet {
+    /// Expects a declaration `alphabet: req, grant` of distinct symbol names.
+    fn parse_declaration(line: &str) -> Result<Self, OmegaSampleParseError> {
+        let names = declared_entries(line, "alphabet")?;
+        if let Some(duplicate) = names.iter().duplicates().next() {
+            return Err(OmegaSampleParseError::MalformedAlphabetSymbol(
+                duplicate.to_string(),
+            ));
+        }
+        Ok(NamedAlphabet::new(
+            names.into_iter().map(|name| name.to_string()).collect(),
+        ))
+    }
+
+    fn declaration(&self) -> String {
+        format!("alphabet: {}", self.names().join(", "))
+    }
+}
+
+impl SampleAlphabet for PropAlphabet {
+    /// Expects a declaration `propositions: p, q` of distinct atomic propositions.
+    fn parse_declaration(line: &str) -> Result<Self, OmegaSampleParseError> {
+        let aps = declared_entries(line, "propositions")?;
+        if let Some(duplicate) = aps.iter().duplicates().next() {
+            return Err(OmegaSampleParseError::MalformedAlphabetSymbol(
+                duplicate.to_string(),
+            ));
+        }
+        if aps.len() >= <u32 as RawSymbolRepr>::max_aps() {
+            return Err(OmegaSampleParseError::MalformedAlphabetSymbol(
+                aps.last().unwrap().to_string(),
+            ));
+        }
+        Ok(PropAlphabet::from_apnames(aps))
+    }
+
+    fn declaration(&self) -> String {
+        format!("propositions: {}", self.apnames().join(", "))
+    }
+}
+
+impl<A: SampleAlphabet> OmegaSample<A> {
+    /// Parses a sample from the given lines. The first line is the header `omega`, the second
+    /// one declares the alphabet (see [`SampleAlphabet`]) and is followed by a `positive:` and
+    /// a `negative:` block, each listing one word per line. Words are read with
+    /// [`ReducedOmegaWord::try_from_str_over`], so they are given by their cycle or by
+    /// their spoke and cycle, separated by a comma. For a [`CharAlphabet`] this looks like
+    /// ```text
+    /// omega
+    /// alphabet: a, b
+    /// positive:
+    /// a
+    /// b, ab
+    /// negative:
+    /// b
+    /// ```
+    /// over a [`NamedAlphabet`] the symbols are names separated by whitespace, e.g.
+    /// `req, grant req`. Over a [`PropAlphabet`], which is declared by `propositions: p, q`,
+    /// each symbol is a valuation such as `{p,!q}`, where unmentioned propositions are false,
+    /// or a bit-vector such as `10`.
     pub fn try_from_lines<I: Iterator<Item = String>>(
         mut lines: I,
     ) -> Result<Self, OmegaSampleParseError> {
@@ -61,38 +188,17 @@ impl OmegaSample<CharAlphabet> {
             return Err(OmegaSampleParseError::MissingHeader);
         }
 
-        let alphabet = lines
-            .next()
-            .and_then(|line| {
-                line.split_once(':')
-                    .map(|(header, symbols)| (header.to_string(), symbols.to_string()))
-            })
-            .map(|(header, symbols)| {
-                if header.trim() == "alphabet" {
-                    symbols
-                        .split(',')
-                        .try_fold(Vec::new(), |mut acc, x| {
-                            let sym = x.trim();
-                            if sym.len() != 1 {
-                                Err(OmegaSampleParseError::MalformedAlphabetSymbol(
-                                    sym.to_string(),
-                                ))
-                            } else {
-                                acc.push(sym.chars().next().unwrap());
-                                Ok(acc)
-                            }
-                        })
-                        .map(CharAlphabet::new)
-                } else {
-                    Err(OmegaSampleParseError::MissingAlphabet)
-                }
-            })
-            .ok_or(OmegaSampleParseError::MissingDelimiter)??;
+        let alphabet =
+            A::parse_declaration(&lines.next().ok_or(OmegaSampleParseError::MissingAlphabet)?)?;
 
         if lines.next().unwrap_or_default().trim() != "positive:" {
             return Err(OmegaSampleParseError::MalformedSample);
         }
 
+        let parse = |word: &str| {
+            ReducedOmegaWord::try_from_str_over(word, &alphabet)
+                .map_err(OmegaSampleParseError::OmegaWordParseError)
+        };
         let mut words = math::Map::default();
         'positive: loop {
             match lines.next() {
@@ -102,9 +208,7 @@ impl OmegaSample<CharAlphabet> {
                     if word.is_empty() || word.starts_with('#') || word == "negative:" {
                         break 'positive;
                     }
-                    let parsed = ReducedOmegaWord::try_from_str(word)
-                        .map_err(OmegaSampleParseError::OmegaWordParseError)?;
-                    if let Some(old_classification) = words.insert(parsed, true) {
+                    if let Some(old_classification) = words.insert(parse(word)?, true) {
                         debug!("Duplicate positive word found");
                     }
                 }
@@ -117,9 +221,7 @@ impl OmegaSample<CharAlphabet> {
                 continue;
             }
             trace!("Parsing negative word \"{word}\"");
-            let parsed = ReducedOmegaWord::try_from_str(word)
-                .map_err(OmegaSampleParseError::OmegaWordParseError)?;
-            if let Some(old_classification) = words.insert(parsed, false) {
+            if let Some(old_classification) = words.insert(parse(word)?, false) {
                 if old_classification {
                     return Err(OmegaSampleParseError::Inconsistent(word.to_string()));
                 }
@@ -130,21 +232,113 @@ impl OmegaSample<CharAlphabet> {
         Ok(SetSample::new_omega(alphabet, words))
     }
 
+    /// Parses a sample from a string, see [`Self::try_from_lines`] for the format.
     pub fn try_from_str(input: &str) -> Result<Self, OmegaSampleParseError> {
         Self::try_from_lines(input.lines().map(|l| l.to_string()))
     }
 
-    pub fn try_from_read<R: BufRead>(mut read: R) -> Result<Self, OmegaSampleParseError> {
-        let mut lines = read.lines().filter_map(|line| {
+    /// Parses a sample from a reader, see [`Self::try_from_lines`] for the format. Empty lines
+    /// and lines starting with `#` are skipped.
+    pub fn try_from_read<R: BufRead>(read: R) -> Result<Self, OmegaSampleParseError> {
+        let lines = read.lines().filter_map(|line| {
             let line = line.expect("unable to parse line!").trim().to_string();
-            if !line.is_empty() && !line.starts_with('#') {
+            if line.is_empty() || line.starts_with('#') {
                 None
             } else {
-                Some(line.to_string())
+                Some(line)
             }
         });
         Self::try_from_lines(lines)
     }
+
+    /// Writes the sample in the format that is understood by [`Self::try_from_lines`].
+    pub fn write<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "omega")?;
+        writeln!(w, "{}", self.alphabet.declaration())?;
+        writeln!(w, "positive:")?;
+        for word in self.positive_words() {
+            writeln!(w, "{}", word.show_over(&self.alphabet))?;
+        }
+        writeln!(w, "negative:")?;
+        for word in self.negative_words() {
+            writeln!(w, "{}", word.show_over(&self.alphabet))?;
+        }
+        Ok(())
+    }
+}
+
+/// The header of samples that are given as comma separated values.
+const CSV_HEADER: &str = "spoke,cycle,acceptance";
+
+impl OmegaSample<CharAlphabet> {
+    /// Parses a sample over `alphabet` from comma separated values. Each record consists of the
+    /// spoke and the cycle of a word, followed by its classification `true` or `false`. A
+    /// header line `spoke,cycle,acceptance` is optional.
+    /// ```text
+    /// spoke,cycle,acceptance
+    /// ,a,true
+    /// ab,b,false
+    /// ```
+    pub fn try_from_csv_read<R: BufRead>(
+        read: R,
+        alphabet: CharAlphabet,
+    ) -> Result<Self, OmegaSampleParseError> {
+        let mut words = math::Map::default();
+        for line in read.lines() {
+            let line = line.map_err(|e| OmegaSampleParseError::Io(e.to_string()))?;
+            let line = line.trim();
+            if line.is_empty() || line == CSV_HEADER {
+                continue;
+            }
+            let Some((spoke, cycle, classification)) = line
+                .split(',')
+                .map(|field| field.trim().trim_matches('"'))
+                .collect_tuple()
+            else {
+                return Err(OmegaSampleParseError::MalformedRecord(line.to_string()));
+            };
+            let classification = classification
+                .parse::<bool>()
+                .map_err(|_| OmegaSampleParseError::MalformedRecord(line.to_string()))?;
+            let parse = |symbols: &str| {
+                alphabet.parse_symbols(symbols).map_err(|reason| {
+                    OmegaSampleParseError::OmegaWordParseError(ReducedParseError::MalformedSymbol(
+                        reason,
+                    ))
+                })
+            };
+            let cycle = parse(cycle)?;
+            if cycle.is_empty() {
+                return Err(OmegaSampleParseError::OmegaWordParseError(
+                    ReducedParseError::EmptyLoop,
+                ));
+            }
+            let word = ReducedOmegaWord::ultimately_periodic(parse(spoke)?, cycle);
+            if words.insert(word, classification) == Some(!classification) {
+                return Err(OmegaSampleParseError::Inconsistent(line.to_string()));
+            }
+        }
+        Ok(SetSample::new_omega(alphabet, words))
+    }
+
+    /// Writes the sample as comma separated values with a header, see
+    /// [`Self::try_from_csv_read`].
+    pub fn write_csv<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        writeln!(w, "{CSV_HEADER}")?;
+        for (word, classification) in self
+            .positive_words()
+            .map(|word| (word, true))
+            .chain(self.negative_words().map(|word| (word, false)))
+        {
+            writeln!(
+                w,
+                "{},{},{classification}",
+                self.alphabet.show_symbols(word.spoke()),
+                self.alphabet.show_symbols(word.cycle())
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl<A: Alphabet> OmegaSample<A> {
@@ -187,12 +381,45 @@ impl<A: Alphabet> OmegaSample<A> {
         }
     }
 
+    /// Returns a copy of the sample in which each class contains at most one word per rotation
+    /// class. Only purely periodic words can be rotations of each other, and of those whose
+    /// cycles are rotations of each other, like `ab` and `ba`, only the one that comes first is
+    /// kept. Words with a non-empty spoke are always kept, as they differ from every other word.
+    pub fn deduplicated_up_to_rotation(&self) -> Self {
+        let deduplicate = |words: &math::Set<ReducedOmegaWord<A::Symbol>>| {
+            words
+                .iter()
+                .unique_by(|word| rotation_class(*word))
+                .cloned()
+                .collect()
+        };
+        Self {
+            alphabet: self.alphabet.clone(),
+            positive: deduplicate(&self.positive),
+            negative: deduplicate(&self.negative),
+        }
+    }
+
     /// Computes the [`RightCongruence`] underlying the sample.
     pub fn infer_prefix_congruence(&self) -> Result<RightCongruence<A>, DpaInfError<A>> {
         dpainf(prefix_consistency_conflicts(self), vec![], true, None)
     }
 }
 
+/// Identifies `word` up to rotation. A purely periodic word is represented by the least rotation
+/// of its cycle, any other word only by itself.
+fn rotation_class<S: Symbol>(
+    word: &ReducedOmegaWord<S>,
+) -> Either<PeriodicOmegaWord<S>, &ReducedOmegaWord<S>> {
+    if word.loop_index() > 0 {
+        return Either::Right(word);
+    }
+    let rotations = PeriodicOmegaWord::new(word.cycle()).rotations();
+    Either::Left(PeriodicOmegaWord::new(
+        rotations.min().expect("cycles are not empty"),
+    ))
+}
+
 /// A [`PeriodicOmegaSample`] is an omega sample containing only periodic words.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct PeriodicOmegaSample<A: Alphabet> {
@@ -316,3 +543,167 @@ impl<A: Alphabet> OmegaSample<A> {
         SplitOmegaSample::new(cong, split)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use automata::core::Show;
+    use automata::core::alphabet::{CharAlphabet, NamedAlphabet, PropAlphabet};
+    use automata::core::word::ReducedOmegaWord;
+    use itertools::Itertools;
+
+    use super::OmegaSampleParseError;
+    use crate::passive::OmegaSample;
+
+    fn round_trip<A: super::SampleAlphabet>(sample: &OmegaSample<A>) -> OmegaSample<A> {
+        let mut written = Vec::new();
+        sample.write(&mut written).unwrap();
+        OmegaSample::try_from_read(written.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn char_sample_round_trip() {
+        let sample = OmegaSample::<CharAlphabet>::try_from_str(
+            "omega\nalphabet: a, b\npositive:\na\nb, ab\nnegative:\nb\nab, b",
+        )
+        .unwrap();
+        assert_eq!(sample.count_positive_words(), 2);
+        assert_eq!(sample.count_negative_words(), 2);
+        assert_eq!(round_trip(&sample), sample);
+    }
+
+    #[test]
+    fn named_sample_round_trip() {
+        let sample = OmegaSample::<NamedAlphabet>::try_from_str(
+            "omega\nalphabet: req, grant, idle\npositive:\nreq grant\nidle, req idle grant\nnegative:\nreq, idle",
+        )
+        .unwrap();
+        assert_eq!(sample.alphabet.names(), ["req", "grant", "idle"]);
+        assert!(
+            sample
+                .positive_words()
+                .contains(&ReducedOmegaWord::periodic([0, 1]))
+        );
+        assert!(
+            sample
+                .negative_words()
+                .contains(&ReducedOmegaWord::ultimately_periodic([0], [2]))
+        );
+        assert_eq!(round_trip(&sample), sample);
+
+        assert_eq!(
+            OmegaSample::<NamedAlphabet>::try_from_str(
+                "omega\nalphabet: req, req\npositive:\nnegative:"
+            ),
+            Err(OmegaSampleParseError::MalformedAlphabetSymbol(
+                "req".to_string()
+            ))
+        );
+        assert!(matches!(
+            OmegaSample::<NamedAlphabet>::try_from_str(
+                "omega\nalphabet: req\npositive:\ngrant\nnegative:"
+            ),
+            Err(OmegaSampleParseError::OmegaWordParseError(_))
+        ));
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let sample = OmegaSample::<CharAlphabet>::try_from_csv_read(
+            "spoke,cycle,acceptance\n,a,true\nab,b,false\nb,\"ab\",true\n".as_bytes(),
+            CharAlphabet::of_size(2),
+        )
+        .unwrap();
+        assert_eq!(sample.count_positive_words(), 2);
+        assert_eq!(
+            sample.classify(&ReducedOmegaWord::ultimately_periodic("ab", "b")),
+            Some(false)
+        );
+
+        let mut written = Vec::new();
+        sample.write_csv(&mut written).unwrap();
+        assert_eq!(
+            OmegaSample::try_from_csv_read(written.as_slice(), CharAlphabet::of_size(2)),
+            Ok(sample)
+        );
+
+        for malformed in ["a,b", ",a,maybe", ",a,true\n,a,false", ",,true", ",c,true"] {
+            assert!(
+                OmegaSample::try_from_csv_read(malformed.as_bytes(), CharAlphabet::of_size(2))
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn deduplicate_rotations() {
+        let sample = OmegaSample::new_omega(
+            CharAlphabet::of_size(2),
+            [
+                (ReducedOmegaWord::periodic("ab"), true),
+                (ReducedOmegaWord::ultimately_periodic("a", "ab"), true),
+                (ReducedOmegaWord::ultimately_periodic("b", "ab"), true),
+                (ReducedOmegaWord::ultimately_periodic("aa", "aab"), true),
+                (ReducedOmegaWord::periodic("aba"), true),
+                (ReducedOmegaWord::ultimately_periodic("bb", "ab"), false),
+                (ReducedOmegaWord::periodic("b"), false),
+            ],
+        );
+        assert_eq!(sample.count_positive_words(), 5);
+        // b(ab)^ω is (ba)^ω, a rotation of (ab)^ω, all other words differ
+        let deduplicated = sample.deduplicated_up_to_rotation();
+        assert_eq!(
+            deduplicated.positive_words().collect_vec(),
+            [
+                &ReducedOmegaWord::periodic("ab"),
+                &ReducedOmegaWord::ultimately_periodic("a", "ab"),
+                &ReducedOmegaWord::ultimately_periodic("aa", "aab"),
+                &ReducedOmegaWord::periodic("aba"),
+            ]
+        );
+        assert_eq!(deduplicated.negative, sample.negative);
+    }
+
+    #[test]
+    fn propositional_sample_round_trip() {
+        let sample = OmegaSample::<PropAlphabet>::try_from_str(
+            "omega\npropositions: p, q\npositive:\n{p,!q}\n00, {q} 11\nnegative:\n{}, {p}{p,q}",
+        )
+        .unwrap();
+        assert_eq!(sample.alphabet.apnames(), ["p", "q"]);
+        let shown = |words: Vec<&ReducedOmegaWord<_>>| {
+            words
+                .into_iter()
+                .map(|word| word.show_over(&sample.alphabet))
+                .collect_vec()
+        };
+        assert_eq!(
+            shown(sample.positive_words().collect()),
+            ["{p,!q}", "{!p,!q}, {!p,q}{p,q}"]
+        );
+        assert_eq!(
+            shown(sample.negative_words().collect()),
+            ["{!p,!q}, {p,!q}{p,q}"]
+        );
+        assert_eq!(
+            sample.positive_words().next().unwrap().raw_word()[0].show(),
+            "10"
+        );
+
+        let read = round_trip(&sample);
+        assert_eq!(read.alphabet.apnames(), sample.alphabet.apnames());
+        assert_eq!(read.positive, sample.positive);
+        assert_eq!(read.negative, sample.negative);
+
+        assert_eq!(
+            OmegaSample::<PropAlphabet>::try_from_str("omega\nalphabet: p\npositive:\nnegative:")
+                .err(),
+            Some(OmegaSampleParseError::MissingAlphabet)
+        );
+        assert!(
+            OmegaSample::<PropAlphabet>::try_from_str(
+                "omega\npropositions: p\npositive:\n{q}\nnegative:"
+            )
+            .is_err()
+        );
+    }
+}
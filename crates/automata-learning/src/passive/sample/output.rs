@@ -0,0 +1,96 @@
+use std::fmt::Debug;
+
+use automata::core::alphabet::Alphabet;
+use automata::core::math;
+use automata::core::word::FiniteWord;
+use itertools::Itertools;
+
+use super::FiniteSample;
+
+/// A sample of finite words, each of which is annotated with an output of type `C`. While a
+/// [`FiniteSample`] only classifies words as positive or negative, such a sample can describe the
+/// behaviour of a Moore or Mealy machine with arbitrary outputs. Words are kept in the order in
+/// which they are inserted.
+#[derive(Clone, Eq, PartialEq)]
+#[allow(missing_docs)]
+pub struct OutputSample<A: Alphabet, C> {
+    pub alphabet: A,
+    pub outputs: math::Map<Vec<A::Symbol>, C>,
+}
+
+impl<A: Alphabet, C: Eq> OutputSample<A, C> {
+    /// Create a new empty sample for the given alphabet.
+    pub fn new_for_alphabet(alphabet: A) -> Self {
+        Self {
+            alphabet,
+            outputs: math::Map::default(),
+        }
+    }
+
+    /// Returns a reference to the underlying alphabet.
+    pub fn alphabet(&self) -> &A {
+        &self.alphabet
+    }
+
+    /// Returns the number of words in the sample.
+    pub fn len(&self) -> usize {
+        self.outputs.len()
+    }
+
+    /// Returns `true` if the sample does not contain any words.
+    pub fn is_empty(&self) -> bool {
+        self.outputs.is_empty()
+    }
+
+    /// Returns the output that is associated with `word`, if it is part of the sample.
+    pub fn output(&self, word: &[A::Symbol]) -> Option<&C> {
+        self.outputs.get(word)
+    }
+
+    /// Returns an iterator over all words in the sample together with their outputs.
+    pub fn entries(&self) -> impl Iterator<Item = (&'_ Vec<A::Symbol>, &'_ C)> + '_ {
+        self.outputs.iter()
+    }
+
+    /// Inserts `word` with the given `output`. Returns `true` if the word was not present
+    /// before. Panics if the word is already present with a different output.
+    pub fn insert(&mut self, word: Vec<A::Symbol>, output: C) -> bool {
+        if let Some(existing) = self.outputs.get(&word) {
+            assert!(
+                existing == &output,
+                "word cannot be annotated with two different outputs!"
+            );
+            return false;
+        }
+        self.outputs.insert(word, output);
+        true
+    }
+
+    /// Returns the maximum length of any word in the sample. Gives back `0` if the sample is empty.
+    pub fn max_word_len(&self) -> usize {
+        self.outputs.keys().map(|w| w.len()).max().unwrap_or(0)
+    }
+}
+
+impl<A: Alphabet> From<OutputSample<A, bool>> for FiniteSample<A> {
+    fn from(value: OutputSample<A, bool>) -> Self {
+        FiniteSample::new_finite(value.alphabet, value.outputs)
+    }
+}
+
+impl<A, C> Debug for OutputSample<A, C>
+where
+    A: Alphabet,
+    C: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.outputs
+                .iter()
+                .map(|(w, c)| format!("{} -> {c:?}", w.as_string()))
+                .join(", ")
+        )
+    }
+}
@@ -1,4 +1,4 @@
-use crate::passive::dpainf::{dpainf, iteration_consistency_conflicts};
+use crate::passive::dpainf::{RefinesLeading, dpainf, iteration_consistency_conflicts};
 use automata::core::alphabet::Alphabet;
 use automata::core::math;
 use automata::ts::{Deterministic, StateIndex};
@@ -43,6 +43,16 @@ impl<'a, A: Alphabet> ClassOmegaSample<'a, A> {
         }
     }
 
+    /// Returns a reference to the underlying congruence.
+    pub fn congruence(&self) -> &'a RightCongruence<A> {
+        self.congruence
+    }
+
+    /// Returns a reference to the class that the sample belongs to.
+    pub fn class(&self) -> &Class<A::Symbol> {
+        &self.class
+    }
+
     /// Returns a reference to the underlying sample.
     pub fn sample(&self) -> &OmegaSample<A> {
         &self.sample
@@ -112,11 +122,12 @@ impl<A: Alphabet> SplitOmegaSample<'_, A> {
         let progress = conflict_relations
             .into_iter()
             .map(|(c, conflicts)| {
+                let idx = self.cong().reached_state_index(c).unwrap();
                 (
-                    self.cong().reached_state_index(c).unwrap(),
+                    idx,
                     dpainf(
                         conflicts,
-                        vec![],
+                        vec![Box::new(RefinesLeading::new(self.cong(), idx))],
                         // SeparatesIdempotents::new(split_sample.get(&c).expect("This must exist")),
                         false,
                         None,
@@ -2,6 +2,9 @@
 mod consistency;
 pub use consistency::*;
 
+mod incremental;
+use incremental::SproutRuns;
+
 use automata::{DTS, Pointed, TransitionSystem, automaton::WithoutCondition, random, ts::path};
 use itertools::Itertools;
 use tracing::{error, info, trace, warn};
@@ -12,18 +15,189 @@ use automata::core::Void;
 use automata::core::alphabet::CharAlphabet;
 use automata::core::word::OmegaWord;
 use automata::ts::{Deterministic, Shrinkable, Sproutable, run};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use std::{collections::HashSet, fmt::Debug, path::Iter};
 
 use super::OmegaSample;
 
+/// The transition system that [`sprout`] is building, which is returned when it stops early.
+pub type SproutTs = Automaton<CharAlphabet, WithoutCondition, Void, Void>;
+
+/// A token that can be used to cooperatively cancel a running [`sprout`] invocation, for example
+/// from another thread. Clones of a token share their state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of all computations that observe this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The progress of [`sprout`], which is reported to the callback set with
+/// [`SproutOptions::with_progress`] before each step.
+#[derive(Debug, Clone, Copy)]
+pub struct SproutProgress<'a> {
+    /// The number of steps, i.e. escape prefixes that have been handled so far.
+    pub steps: usize,
+    /// The number of states of the current transition system.
+    pub size: usize,
+    /// The sample words that are still escaping from the current transition system.
+    pub escaping: &'a OmegaSample,
+}
+
+/// Statistics about a run of [`sprout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SproutStatistics {
+    /// The number of steps, i.e. escape prefixes that have been handled.
+    pub steps: usize,
+    /// The number of states of the transition system when the algorithm stopped.
+    pub size: usize,
+    /// The threshold for the length of escape prefixes.
+    pub threshold: usize,
+    /// The wall-clock time that has passed.
+    pub elapsed: Duration,
+}
+
+type ProgressCallback<'a> = Box<dyn FnMut(SproutProgress<'_>) + 'a>;
+
+/// Configures the budget of [`sprout_with_options`]. By default, the algorithm times out after
+/// 30 minutes, has no step limit and uses the threshold from the paper, which is
+/// `|u| + |v|^2 + 1` for the longest spoke `u` and longest cycle `v` in the sample.
+pub struct SproutOptions<'a> {
+    timeout: Option<Duration>,
+    step_limit: Option<usize>,
+    threshold: Box<dyn Fn(&OmegaSample) -> usize + 'a>,
+    cancellation: Option<CancellationToken>,
+    progress: Option<ProgressCallback<'a>>,
+    incremental: bool,
+}
+
+impl Default for SproutOptions<'_> {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(60 * 30)),
+            step_limit: None,
+            threshold: Box::new(default_threshold),
+            cancellation: None,
+            progress: None,
+            incremental: true,
+        }
+    }
+}
+
+impl<'a> SproutOptions<'a> {
+    /// Sets the wall-clock timeout, `None` disables it.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the maximal number of steps, `None` disables the limit.
+    pub fn with_step_limit(mut self, step_limit: Option<usize>) -> Self {
+        self.step_limit = step_limit;
+        self
+    }
+
+    /// Sets the function computing the threshold for the length of escape prefixes from the
+    /// sample. If an escape prefix is more than two symbols longer, the default automaton is
+    /// returned.
+    pub fn with_threshold(mut self, threshold: impl Fn(&OmegaSample) -> usize + 'a) -> Self {
+        self.threshold = Box::new(threshold);
+        self
+    }
+
+    /// Sets a token through which the computation can be cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets a callback that is invoked with the current progress before each step.
+    pub fn with_progress(mut self, progress: impl FnMut(SproutProgress<'_>) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Sets whether consistency is checked incrementally, which is the default. In that case,
+    /// the partial runs of escaping sample words are kept and adding a transition only
+    /// re-evaluates the words that escape through it. Otherwise, the whole remaining sample is
+    /// run for every candidate transition.
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+}
+
+/// Computes the threshold `|u| + |v|^2 + 1` from the paper, where `u` is the longest spoke and
+/// `v` is the longest cycle of a word in the sample.
+pub fn default_threshold(sample: &OmegaSample) -> usize {
+    let (lb, le) = sample
+        .words()
+        .map(|w| (w.spoke().len(), w.cycle().len()))
+        .fold((0, 0), |(a0, a1), (b0, b1)| (a0.max(b0), a1.max(b1)));
+    lb + le.pow(2) + 1
+}
+
 #[derive(thiserror::Error)]
 pub enum SproutError<A: ConsistencyCheck<WithInitial<DTS>>> {
     #[error("timeout was exceeded, bailing with ts of size {}", aut.size())]
     Timeout {
-        aut: Automaton<CharAlphabet, WithoutCondition, Void, Void>,
+        aut: SproutTs,
+        stats: SproutStatistics,
+    },
+    #[error("step limit was reached, bailing with ts of size {}", aut.size())]
+    StepLimit {
+        aut: SproutTs,
+        stats: SproutStatistics,
+    },
+    #[error("computation was cancelled, bailing with ts of size {}", aut.size())]
+    Cancelled {
+        aut: SproutTs,
+        stats: SproutStatistics,
+    },
+    #[error("escape prefix threshold `{thres}` exceeded, bailing with ts of size {}", partial.size())]
+    Threshold {
+        thres: usize,
+        aut: A::Aut,
+        partial: SproutTs,
+        stats: SproutStatistics,
     },
-    #[error("escape prefix threshold `{0}` exceeded, bailing with ts of size {}", aut.size())]
-    Threshold { thres: usize, aut: A::Aut },
+}
+
+impl<A: ConsistencyCheck<WithInitial<DTS>>> SproutError<A> {
+    /// Returns the transition system that was built before the algorithm stopped.
+    pub fn partial(&self) -> &SproutTs {
+        match self {
+            SproutError::Timeout { aut, .. }
+            | SproutError::StepLimit { aut, .. }
+            | SproutError::Cancelled { aut, .. } => aut,
+            SproutError::Threshold { partial, .. } => partial,
+        }
+    }
+
+    /// Returns the statistics of the run that failed.
+    pub fn statistics(&self) -> &SproutStatistics {
+        match self {
+            SproutError::Timeout { stats, .. }
+            | SproutError::StepLimit { stats, .. }
+            | SproutError::Cancelled { stats, .. }
+            | SproutError::Threshold { stats, .. } => stats,
+        }
+    }
 }
 
 impl<A: ConsistencyCheck<WithInitial<DTS>>> Debug for SproutError<A> {
@@ -31,14 +205,17 @@ impl<A: ConsistencyCheck<WithInitial<DTS>>> Debug for SproutError<A> {
         use SproutError::*;
         write!(
             f,
-            "{}",
+            "{} after {} steps",
             match self {
-                Timeout { aut } => "reached timeout".to_string(),
-                Threshold { thres, aut } => format!(
+                Timeout { .. } => "reached timeout".to_string(),
+                StepLimit { .. } => "reached step limit".to_string(),
+                Cancelled { .. } => "was cancelled".to_string(),
+                Threshold { thres, aut, .. } => format!(
                     "exceeded threshold {thres} with automaton of size {}",
                     aut.size()
                 ),
-            }
+            },
+            self.statistics().steps
         )
     }
 }
@@ -48,9 +225,20 @@ pub type SproutResult<A: ConsistencyCheck<WithInitial<DTS>>> = Result<A::Aut, Sp
 
 /// gives a deterministic acc_type omega automaton that is consistent with the given sample
 /// implements the sprout passive learning algorithm for omega automata from <https://arxiv.org/pdf/2108.03735.pdf>
+#[allow(clippy::result_large_err)]
 pub fn sprout<A: ConsistencyCheck<WithInitial<DTS>>>(
     sample: OmegaSample,
     acc_type: A,
+) -> SproutResult<A> {
+    sprout_with_options(sample, acc_type, SproutOptions::default())
+}
+
+/// Runs [`sprout`] within the budget that is configured by the given [`SproutOptions`].
+#[allow(clippy::result_large_err)]
+pub fn sprout_with_options<A: ConsistencyCheck<WithInitial<DTS>>>(
+    sample: OmegaSample,
+    acc_type: A,
+    mut options: SproutOptions<'_>,
 ) -> SproutResult<A> {
     let time_start = std::time::Instant::now();
 
@@ -58,48 +246,80 @@ pub fn sprout<A: ConsistencyCheck<WithInitial<DTS>>>(
     let mut ts = Automaton::new_with_initial_color(sample.alphabet().clone(), Void);
 
     // compute threshold
-    let (lb, le) = sample
-        .words()
-        .map(|w| (w.spoke().len(), w.cycle().len()))
-        .fold((0, 0), |(a0, a1), (b0, b1)| (a0.max(b0), a1.max(b1)));
-    let thresh = (lb + le.pow(2) + 1) as isize;
+    let thresh = (options.threshold)(&sample);
     info!("starting sprout with threshold {thresh}");
 
     // while there are positive sample words that are escaping
-    let mut pos_sets = vec![];
-    let mut neg_sets = vec![];
-    let mut mut_sample = sample.clone();
+    let mut runs = SproutRuns::new(&ts, sample.clone(), options.incremental);
+    let mut steps = 0;
+    let stats = |ts: &SproutTs, steps: usize| SproutStatistics {
+        steps,
+        size: ts.size(),
+        threshold: thresh,
+        elapsed: time_start.elapsed(),
+    };
 
     'outer: loop {
-        trace!(
-            "attempting to find an escape prefix with sets\npos {pos_sets:?}, neg {neg_sets:?}\n{mut_sample:?}\n{ts:?}"
-        );
-        let Some(escape_prefix) = ts.omega_escape_prefixes(mut_sample.positive_words()).min()
+        trace!("attempting to find an escape prefix with runs\n{runs:?}\n{ts:?}");
+        let Some(escape_prefix) = ts
+            .omega_escape_prefixes(runs.sample().positive_words())
+            .min()
         else {
             break;
         };
 
         trace!("found escape prefix {escape_prefix:?}");
-        // WARN TODO should find a way to either pass or globally set timeout
-        if time_start.elapsed() >= std::time::Duration::from_secs(60 * 30) {
+        if let Some(progress) = options.progress.as_mut() {
+            progress(SproutProgress {
+                steps,
+                size: ts.size(),
+                escaping: runs.sample(),
+            });
+        }
+        if options
+            .cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            warn!("task was cancelled with automaton of size {}", ts.size());
+            let stats = stats(&ts, steps);
+            return Err(SproutError::Cancelled { aut: ts, stats });
+        }
+        if options
+            .timeout
+            .is_some_and(|timeout| time_start.elapsed() >= timeout)
+        {
             error!(
                 "task exceeded timeout, aborting with automaton of size {}",
                 ts.size()
             );
-            return Err(SproutError::Timeout { aut: ts });
+            let stats = stats(&ts, steps);
+            return Err(SproutError::Timeout { aut: ts, stats });
+        }
+        if options.step_limit.is_some_and(|limit| steps >= limit) {
+            error!(
+                "task reached step limit with an automaton of size {}",
+                ts.size()
+            );
+            let stats = stats(&ts, steps);
+            return Err(SproutError::StepLimit { aut: ts, stats });
         }
         // check thresh
-        if (escape_prefix.len() as isize) - 2 > thresh {
+        if escape_prefix.len() > thresh + 2 {
             error!(
                 "task exceeded threshold with an automaton of size {}",
                 ts.size()
             );
             // compute default automaton
+            let stats = stats(&ts, steps);
             return Err(SproutError::Threshold {
-                thres: thresh as usize,
+                thres: thresh,
                 aut: acc_type.default_automaton(&sample),
+                partial: ts,
+                stats,
             });
         }
+        steps += 1;
 
         let source = ts.reached_state_index(&escape_prefix).unwrap();
         let sym = escape_prefix.escape_symbol();
@@ -107,14 +327,8 @@ pub fn sprout<A: ConsistencyCheck<WithInitial<DTS>>>(
         for q in ts.state_indices_vec() {
             // try adding transition
             ts.add_edge((source, sym, q));
-            // continue if consistent
-            let (is_consistent, pos_sets_new, neg_sets_new) =
-                acc_type.consistent(&ts, &mut_sample, pos_sets.clone(), neg_sets.clone());
-            if is_consistent {
-                // update already known infinity sets
-                pos_sets = pos_sets_new;
-                neg_sets = neg_sets_new;
-                mut_sample.remove_non_escaping(&ts);
+            // continue if consistent, this also updates the known infinity sets
+            if runs.try_edge(&ts, &acc_type, source, sym) {
                 continue 'outer;
             } else {
                 ts.remove_edges_from_matching(source, sym);
@@ -123,13 +337,15 @@ pub fn sprout<A: ConsistencyCheck<WithInitial<DTS>>>(
         // if none consistent add new state
         let new_state = ts.add_state(Void);
         ts.add_edge((source, sym, new_state));
+        runs.force_edge(&ts, source, sym);
     }
 
     info!(
         "completed sprout algorithm after {} microseconds",
         time_start.elapsed().as_micros()
     );
-    Ok(acc_type.consistent_automaton(&ts, &mut_sample, pos_sets, neg_sets))
+    let (escaping, pos_sets, neg_sets) = runs.into_parts();
+    Ok(acc_type.consistent_automaton(&ts, &escaping, pos_sets, neg_sets))
 }
 
 impl OmegaSample {
@@ -223,7 +439,9 @@ mod tests {
             .default_color(Void)
             .into_dba(0);
 
-        let Err(SproutError::Threshold { thres: t, aut: res }) = sprout(sample, BuchiCondition)
+        let Err(SproutError::Threshold {
+            thres: t, aut: res, ..
+        }) = sprout(sample, BuchiCondition)
         else {
             panic!("expected to hit threshold");
         };
@@ -284,11 +502,134 @@ mod tests {
             .into_dpa(0);
         dpa.complete_with_colors(Void, 1);
 
-        let Err(SproutError::Threshold { thres: t, aut: res }) =
-            sprout(sample, MinEvenParityCondition)
+        let Err(SproutError::Threshold {
+            thres: t, aut: res, ..
+        }) = sprout(sample, MinEvenParityCondition)
         else {
             panic!("expected threshold to be exceeded");
         };
         assert_eq!(res, dpa);
     }
+
+    #[test]
+    fn sprout_options() {
+        let sigma = CharAlphabet::of_size(2);
+        let sample = OmegaSample::new_omega_from_pos_neg(
+            sigma,
+            [upw!("a"), upw!("aab")],
+            [upw!("b"), upw!("abb")],
+        );
+
+        let mut sizes = vec![];
+        let options = SproutOptions::default().with_progress(|progress| {
+            assert!(progress.escaping.positive_words().next().is_some());
+            sizes.push(progress.size);
+        });
+        let res = sprout_with_options(sample.clone(), MinEvenParityCondition, options).unwrap();
+        assert_eq!(sizes.len(), 3);
+        assert!(sizes.is_sorted());
+        assert!(sizes.iter().all(|size| *size <= res.size()));
+
+        let options = SproutOptions::default().with_step_limit(Some(2));
+        let Err(err @ SproutError::StepLimit { .. }) =
+            sprout_with_options(sample.clone(), MinEvenParityCondition, options)
+        else {
+            panic!("expected to reach step limit");
+        };
+        assert_eq!(err.statistics().steps, 2);
+        assert_eq!(err.partial().size(), err.statistics().size);
+
+        let token = CancellationToken::new();
+        token.clone().cancel();
+        let options = SproutOptions::default().with_cancellation(token);
+        let Err(err @ SproutError::Cancelled { .. }) =
+            sprout_with_options(sample.clone(), MinEvenParityCondition, options)
+        else {
+            panic!("expected cancellation");
+        };
+        assert_eq!(err.statistics().steps, 0);
+        assert_eq!(err.partial().size(), 1);
+
+        let options = SproutOptions::default().with_timeout(Some(std::time::Duration::ZERO));
+        assert!(matches!(
+            sprout_with_options(sample.clone(), MinEvenParityCondition, options),
+            Err(SproutError::Timeout { .. })
+        ));
+
+        let options = SproutOptions::default().with_threshold(|_| 0);
+        let Err(err @ SproutError::Threshold { thres: 0, .. }) =
+            sprout_with_options(sample, MinEvenParityCondition, options)
+        else {
+            panic!("expected to exceed threshold");
+        };
+        assert!(err.statistics().steps > 0);
+    }
+
+    #[test]
+    fn sprout_incremental() {
+        use crate::passive::sample::SampleGenerator;
+        use automata::core::word::ReducedOmegaWord;
+
+        let sigma = CharAlphabet::of_size(2);
+        let dbas = [
+            // infinitely many a's
+            DTS::builder()
+                .with_transitions([(0, 'a', true, 0), (0, 'b', false, 0)])
+                .default_color(Void)
+                .into_dba(0),
+            // infinitely many occurrences of ab
+            DTS::builder()
+                .with_transitions([
+                    (0, 'a', false, 1),
+                    (0, 'b', false, 0),
+                    (1, 'a', false, 1),
+                    (1, 'b', true, 0),
+                ])
+                .default_color(Void)
+                .into_dba(0),
+        ];
+        let dpas = [
+            // infinitely often, an a is read after an odd number of consecutive b's
+            DTS::builder()
+                .with_transitions([
+                    (0, 'a', 1, 0),
+                    (0, 'b', 1, 1),
+                    (1, 'a', 0, 0),
+                    (1, 'b', 1, 0),
+                ])
+                .default_color(Void)
+                .into_dpa(0),
+            // finitely many b's after the first a
+            DTS::builder()
+                .with_transitions([
+                    (0, 'a', 2, 1),
+                    (0, 'b', 1, 0),
+                    (1, 'a', 2, 1),
+                    (1, 'b', 1, 1),
+                ])
+                .default_color(Void)
+                .into_dpa(0),
+        ];
+        let mut generator = SampleGenerator::new(46);
+        for (dba, dpa) in dbas.iter().zip(&dpas) {
+            let words: Vec<ReducedOmegaWord<char>> = generator
+                .words(&sigma, &(0..=4, 1..=4), 30)
+                .into_iter()
+                .collect();
+            let (pos, neg): (Vec<_>, Vec<_>) = words.iter().cloned().partition(|w| dba.accepts(w));
+            let sample = OmegaSample::new_omega_from_pos_neg(sigma.clone(), pos, neg);
+            let full = SproutOptions::default().with_incremental(false);
+            assert_eq!(
+                sprout(sample.clone(), BuchiCondition).ok(),
+                sprout_with_options(sample, BuchiCondition, full).ok()
+            );
+            let (pos, neg): (Vec<_>, Vec<_>) = words.into_iter().partition(|w| dpa.accepts(w));
+            let sample = OmegaSample::new_omega_from_pos_neg(sigma.clone(), pos, neg);
+            let full = SproutOptions::default().with_incremental(false);
+            assert_eq!(
+                sprout(sample.clone(), MinEvenParityCondition).ok(),
+                sprout_with_options(sample, MinEvenParityCondition, full).ok()
+            );
+        }
+    }
 }
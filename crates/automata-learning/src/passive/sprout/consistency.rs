@@ -1,10 +1,13 @@
 use itertools::{Either, Itertools, all};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::iter;
 use std::ops::Not;
 
 use crate::prefixtree::prefix_tree;
-use automata::automaton::{BuchiCondition, DBA, DPA, MinEvenParityCondition};
+use automata::automaton::{
+    BuchiCondition, CoBuchiCondition, DBA, DCBA, DMA, DPA, DRA, MinEvenParityCondition,
+    MullerCondition, RabinCondition, RabinPair,
+};
 use automata::core::Void;
 use automata::core::alphabet::{Alphabet, CharAlphabet};
 use automata::core::math::OrderedSet;
@@ -18,21 +21,39 @@ use automata::{
 
 use super::OmegaSample;
 
-type EdgeSet = OrderedSet<(u32, char)>;
+pub(super) type EdgeSet = OrderedSet<(u32, char)>;
 
 /// Used to define consistency checks on various types of omega acceptance conditions
 /// required by the sprout algorithm for passively learning omega automata
 pub trait ConsistencyCheck<T> {
     /// the type of the automaton to be returned
     type Aut: TransitionSystem;
-    /// Checks if the given transition system is consistent with the sample
+    /// Checks if there is an acceptance condition on the given transition system that
+    /// accepts all infinity sets in `pos_sets` and rejects all infinity sets in `neg_sets`
+    fn consistent_sets(&self, ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool;
+    /// Checks if the given transition system is consistent with the sample. The infinity
+    /// sets of the sample words are returned together with the given ones
     fn consistent(
         &self,
         ts: &T,
         sample: &OmegaSample,
         pos_sets: Vec<EdgeSet>,
         neg_sets: Vec<EdgeSet>,
-    ) -> (bool, Vec<EdgeSet>, Vec<EdgeSet>);
+    ) -> (bool, Vec<EdgeSet>, Vec<EdgeSet>)
+    where
+        T: TransitionSystem<Alphabet = CharAlphabet, StateIndex = u32> + Deterministic + Pointed,
+        <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
+    {
+        if let Some([mut pos_sets_new, mut neg_sets_new]) = to_infinity_sets(ts, sample) {
+            pos_sets_new.extend(pos_sets);
+            neg_sets_new.extend(neg_sets);
+            let is_consistent = self.consistent_sets(ts, &pos_sets_new, &neg_sets_new);
+            (is_consistent, pos_sets_new, neg_sets_new)
+        } else {
+            // bad pair was found when running sample words on transition system
+            (false, pos_sets, neg_sets)
+        }
+    }
     /// If the transition system is consistent with the sample,
     /// returns an automaton with underlying transition system ts
     /// that is consistent with the sample
@@ -54,26 +75,8 @@ where
     <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
 {
     type Aut = DBA;
-    fn consistent(
-        &self,
-        ts: &T,
-        sample: &OmegaSample,
-        pos_sets: Vec<EdgeSet>,
-        neg_sets: Vec<EdgeSet>,
-    ) -> (bool, Vec<EdgeSet>, Vec<EdgeSet>) {
-        if let Some([mut pos_sets_new, mut neg_sets_new]) = to_infinity_sets(ts, sample) {
-            // check if the infinity set of a positive word is subset of
-            // the union of all infinity sets of negative words (see paper for details)
-            neg_sets_new.extend(neg_sets);
-            let neg_union: EdgeSet = neg_sets_new.iter().flatten().cloned().collect();
-            pos_sets_new.extend(pos_sets);
-
-            let is_consistent = pos_sets_new.iter().any(|s| s.is_subset(&neg_union)).not();
-            (is_consistent, pos_sets_new, neg_sets_new)
-        } else {
-            // bad pair was found when running sample words on transition system
-            (false, pos_sets, neg_sets)
-        }
+    fn consistent_sets(&self, _ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool {
+        buchi_rejecting_transitions(pos_sets, neg_sets).is_some()
     }
 
     fn consistent_automaton(
@@ -130,48 +133,34 @@ where
 {
     type Aut = DPA;
 
-    fn consistent(
-        &self,
-        ts: &T,
-        sample: &OmegaSample,
-        pos_sets: Vec<EdgeSet>,
-        neg_sets: Vec<EdgeSet>,
-    ) -> (bool, Vec<EdgeSet>, Vec<EdgeSet>) {
-        if let Some([mut pos_sets_new, mut neg_sets_new]) = to_infinity_sets(ts, sample) {
-            pos_sets_new.extend(pos_sets);
-            neg_sets_new.extend(neg_sets);
-            // check how set with all transitions should be handled
-            let all_transitions: EdgeSet = ts
-                .state_indices()
-                .cartesian_product(ts.alphabet().universe())
-                .collect();
-            let is_consistent = match (
-                pos_sets_new.contains(&all_transitions),
-                neg_sets_new.contains(&all_transitions),
-            ) {
-                (true, false) => {
-                    // set of all transitions is accepting
-                    has_zielonka_path(&pos_sets_new, &neg_sets_new, &all_transitions, true)
-                }
-                (false, true) => {
-                    // set of all transitions is non-accepting
-                    has_zielonka_path(&pos_sets_new, &neg_sets_new, &all_transitions, false)
-                }
-                (false, false) => {
-                    // class of set of all transitions not clear, check both options
-                    has_zielonka_path(&pos_sets_new, &neg_sets_new, &all_transitions, false)
-                        || has_zielonka_path(&pos_sets_new, &neg_sets_new, &all_transitions, true)
-                }
-                (true, true) => {
-                    // set of all transitions is both accepting and non-accepting
-                    // no Zielonka path possible
-                    false
-                }
-            };
-            (is_consistent, pos_sets_new, neg_sets_new)
-        } else {
-            // bad pair was found when running sample words on transition system
-            (false, pos_sets, neg_sets)
+    fn consistent_sets(&self, ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool {
+        // check how set with all transitions should be handled
+        let all_transitions: EdgeSet = ts
+            .state_indices()
+            .cartesian_product(ts.alphabet().universe())
+            .collect();
+        match (
+            pos_sets.contains(&all_transitions),
+            neg_sets.contains(&all_transitions),
+        ) {
+            (true, false) => {
+                // set of all transitions is accepting
+                has_zielonka_path(pos_sets, neg_sets, &all_transitions, true)
+            }
+            (false, true) => {
+                // set of all transitions is non-accepting
+                has_zielonka_path(pos_sets, neg_sets, &all_transitions, false)
+            }
+            (false, false) => {
+                // class of set of all transitions not clear, check both options
+                has_zielonka_path(pos_sets, neg_sets, &all_transitions, false)
+                    || has_zielonka_path(pos_sets, neg_sets, &all_transitions, true)
+            }
+            (true, true) => {
+                // set of all transitions is both accepting and non-accepting
+                // no Zielonka path possible
+                false
+            }
         }
     }
 
@@ -280,11 +269,233 @@ where
     }
 }
 
+impl<T> ConsistencyCheck<T> for CoBuchiCondition
+where
+    T: TransitionSystem<Alphabet = CharAlphabet, StateIndex = u32> + Deterministic + Pointed,
+    <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
+{
+    type Aut = DCBA;
+    fn consistent_sets(&self, _ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool {
+        // dual to the Büchi case: check if the infinity set of a negative word is
+        // subset of the union of all infinity sets of positive words
+        let pos_union: EdgeSet = pos_sets.iter().flatten().cloned().collect();
+        neg_sets.iter().any(|s| s.is_subset(&pos_union)).not()
+    }
+
+    fn consistent_automaton(
+        &self,
+        ts: &T,
+        sample: &OmegaSample,
+        pos_sets: Vec<EdgeSet>,
+        neg_sets: Vec<EdgeSet>,
+    ) -> Self::Aut {
+        // check consistency
+        assert!(self.consistent(ts, sample, pos_sets.clone(), neg_sets).0);
+
+        // derive acceptance condition: rejecting transitions
+        // -> all transitions besides the union of positive infinity sets
+        let [mut pos_sets_new, _] =
+            to_infinity_sets(ts, sample).expect("ts cannot be consistent with sample");
+        pos_sets_new.extend(pos_sets);
+
+        let pos_union: EdgeSet = pos_sets_new.iter().flatten().cloned().collect();
+
+        let all_transitions: EdgeSet = ts
+            .state_indices()
+            .cartesian_product(ts.alphabet().universe())
+            .collect();
+
+        let rejecting: EdgeSet = all_transitions.difference(&pos_union).cloned().collect();
+
+        // make DCBA
+        let (dts, initial) = ts
+            .map_edge_colors_full(move |a, b, _, _| rejecting.contains(&(a, *b)))
+            .erase_state_colors()
+            .collect_dts_and_initial();
+        let mut dcba = DCBA::from_parts(dts, initial);
+
+        // complete with rejecting sink state
+        dcba.complete_with_colors(Void, true);
+        dcba
+    }
+
+    fn default_automaton(&self, sample: &OmegaSample) -> Self::Aut {
+        let (dts, initial) = prefix_tree(sample.alphabet().clone(), sample.positive_words())
+            .map_edge_colors(|_| false)
+            .erase_state_colors()
+            .with_initial(0)
+            .collect_dts_and_initial();
+        let mut dcba = DCBA::from_parts(dts, initial);
+        dcba.complete_with_colors(Void, true);
+        dcba
+    }
+}
+
+impl<T> ConsistencyCheck<T> for RabinCondition<usize>
+where
+    T: TransitionSystem<Alphabet = CharAlphabet, StateIndex = u32> + Deterministic + Pointed,
+    <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
+{
+    type Aut = DRA;
+
+    fn consistent_sets(&self, _ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool {
+        // the rejecting sets of a Rabin condition are closed under union, so
+        // no positive set may be covered by the negative sets it contains
+        pos_sets
+            .iter()
+            .all(|p| rabin_inf_set(p, neg_sets).next().is_some())
+    }
+
+    fn consistent_automaton(
+        &self,
+        ts: &T,
+        sample: &OmegaSample,
+        pos_sets: Vec<EdgeSet>,
+        neg_sets: Vec<EdgeSet>,
+    ) -> Self::Aut {
+        // check consistency
+        assert!(
+            self.consistent(ts, sample, pos_sets.clone(), neg_sets.clone())
+                .0
+        );
+
+        let [mut pos_sets_new, mut neg_sets_new] =
+            to_infinity_sets(ts, sample).expect("ts cannot be consistent with sample");
+        pos_sets_new.extend(pos_sets);
+        neg_sets_new.extend(neg_sets);
+
+        // every transition gets its own color, the sink transitions share a fresh one
+        let colors: HashMap<(u32, char), usize> = ts
+            .state_indices()
+            .cartesian_product(ts.alphabet().universe())
+            .enumerate()
+            .map(|(i, t)| (t, i))
+            .collect();
+        let sink_color = colors.len();
+
+        // one pair for each positive set p: fin contains all transitions outside of p
+        // and inf contains the transitions of p not covered by negative subsets of p
+        let condition: RabinCondition<usize> = pos_sets_new
+            .iter()
+            .map(|p| {
+                let fin = colors
+                    .iter()
+                    .filter(|(t, _)| !p.contains(t))
+                    .map(|(_, c)| *c)
+                    .chain(iter::once(sink_color));
+                let inf = rabin_inf_set(p, &neg_sets_new).map(|t| colors[t]);
+                RabinPair::from_iters(fin, inf)
+            })
+            .into();
+
+        let (dts, initial) = ts
+            .map_edge_colors_full(move |a, b, _, _| colors[&(a, *b)])
+            .erase_state_colors()
+            .collect_dts_and_initial();
+        let mut dra = DRA::from_parts_with_acceptance(dts, initial, condition);
+
+        // complete with sink state
+        dra.complete_with_colors(Void, sink_color);
+        dra
+    }
+
+    fn default_automaton(&self, sample: &OmegaSample) -> Self::Aut {
+        let (dts, initial) = prefix_tree(sample.alphabet().clone(), sample.positive_words())
+            .map_edge_colors(|_| 0)
+            .erase_state_colors()
+            .with_initial(0)
+            .collect_dts_and_initial();
+        let mut dra =
+            DRA::from_parts_with_acceptance(dts, initial, [RabinPair::from_iters([1], [0])].into());
+        dra.complete_with_colors(Void, 1);
+        dra
+    }
+}
+
+impl<T> ConsistencyCheck<T> for MullerCondition<usize>
+where
+    T: TransitionSystem<Alphabet = CharAlphabet, StateIndex = u32> + Deterministic + Pointed,
+    <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
+{
+    type Aut = DMA;
+
+    fn consistent_sets(&self, _ts: &T, pos_sets: &[EdgeSet], neg_sets: &[EdgeSet]) -> bool {
+        // a Muller condition can classify every set individually,
+        // so it suffices that no set is both positive and negative
+        pos_sets.iter().all(|p| !neg_sets.contains(p))
+    }
+
+    fn consistent_automaton(
+        &self,
+        ts: &T,
+        sample: &OmegaSample,
+        pos_sets: Vec<EdgeSet>,
+        neg_sets: Vec<EdgeSet>,
+    ) -> Self::Aut {
+        // check consistency
+        assert!(self.consistent(ts, sample, pos_sets.clone(), neg_sets).0);
+
+        let [mut pos_sets_new, _] =
+            to_infinity_sets(ts, sample).expect("ts cannot be consistent with sample");
+        pos_sets_new.extend(pos_sets);
+
+        // every transition gets its own color, the sink transitions share a fresh one
+        let colors: HashMap<(u32, char), usize> = ts
+            .state_indices()
+            .cartesian_product(ts.alphabet().universe())
+            .enumerate()
+            .map(|(i, t)| (t, i))
+            .collect();
+        let sink_color = colors.len();
+
+        // accepting sets are precisely the positive infinity sets
+        let condition = MullerCondition::from_iter_iter(
+            pos_sets_new
+                .iter()
+                .unique()
+                .map(|p| p.iter().map(|t| colors[t]).collect_vec()),
+        );
+
+        let (dts, initial) = ts
+            .map_edge_colors_full(move |a, b, _, _| colors[&(a, *b)])
+            .erase_state_colors()
+            .collect_dts_and_initial();
+        let mut dma = DMA::from_parts_with_acceptance(dts, initial, condition);
+
+        // complete with sink state
+        dma.complete_with_colors(Void, sink_color);
+        dma
+    }
+
+    fn default_automaton(&self, sample: &OmegaSample) -> Self::Aut {
+        let (dts, initial) = prefix_tree(sample.alphabet().clone(), sample.positive_words())
+            .map_edge_colors(|_| 0)
+            .erase_state_colors()
+            .with_initial(0)
+            .collect_dts_and_initial();
+        let mut dma =
+            DMA::from_parts_with_acceptance(dts, initial, MullerCondition::from_iter_iter([[0]]));
+        dma.complete_with_colors(Void, 1);
+        dma
+    }
+}
+
+/// Returns the transitions of the positive set `pos` that are not contained in any
+/// negative set which is itself a subset of `pos`. These are used as the `inf` part of
+/// the Rabin pair for `pos`, which is only possible if at least one such transition exists.
+fn rabin_inf_set<'a>(
+    pos: &'a EdgeSet,
+    neg_sets: &'a [EdgeSet],
+) -> impl Iterator<Item = &'a (u32, char)> + 'a {
+    pos.iter()
+        .filter(move |t| !neg_sets.iter().any(|n| n.is_subset(pos) && n.contains(t)))
+}
+
 /// Check if it is possible to construct a valid zielonka path from the given classified sets.
 /// `class` is the classification to use for the set of all transitions.
 fn has_zielonka_path(
-    pos_sets: &Vec<EdgeSet>,
-    neg_sets: &Vec<EdgeSet>,
+    pos_sets: &[EdgeSet],
+    neg_sets: &[EdgeSet],
     all_transitions: &EdgeSet,
     mut class: bool,
 ) -> bool {
@@ -358,23 +569,44 @@ fn zielonka_path(
 }
 
 use run::InfiniteRunOutput::*;
+/// Checks whether there is a Büchi condition that accepts all infinity sets in `pos_sets` and
+/// rejects all infinity sets in `neg_sets`. This is the case if no infinity set of a positive
+/// word is a subset of the union of all infinity sets of negative words (see paper for details),
+/// and if so, that union is returned, as it consists of the transitions that must not be accepting.
+pub(crate) fn buchi_rejecting_transitions<E: Ord + Clone>(
+    pos_sets: &[OrderedSet<E>],
+    neg_sets: &[OrderedSet<E>],
+) -> Option<OrderedSet<E>> {
+    let neg_union: OrderedSet<E> = neg_sets.iter().flatten().cloned().collect();
+    pos_sets
+        .iter()
+        .any(|s| s.is_subset(&neg_union))
+        .not()
+        .then_some(neg_union)
+}
+
 /// Run positive and negative sample words on the given transition system.
-/// If there is a pair of words escaping with the same escape string from the same state, return None.
-/// Otherwise return non-escaping runs of positive and negative example words
-fn to_infinity_sets<T>(ts: T, sample: &OmegaSample) -> Option<[Vec<EdgeSet>; 2]>
+/// If there is a pair of words escaping with the same escape symbol and the same remaining suffix
+/// from the same state, return None. Otherwise return the infinity sets of the non-escaping runs
+/// of positive and negative example words, i.e. the transitions taken infinitely often
+#[allow(clippy::type_complexity)]
+pub(crate) fn to_infinity_sets<A, T>(
+    ts: T,
+    sample: &OmegaSample<A>,
+) -> Option<[Vec<OrderedSet<(u32, A::Symbol)>>; 2]>
 where
-    T: TransitionSystem<Alphabet = CharAlphabet, StateIndex = u32> + Deterministic + Pointed,
-    <T as TransitionSystem>::EdgeColor: Eq + std::hash::Hash,
+    A: Alphabet,
+    T: TransitionSystem<Alphabet = A, StateIndex = u32> + Deterministic + Pointed,
 {
     let time_start = std::time::Instant::now();
     // run transition system on sample words and
     // separate in escaping and non-escaping (successful) runs
-    let (pos_successful, pos_escaping): (Vec<_>, Vec<_>) = sample
+    let (pos_successful, pos_escaping): (Vec<_>, HashSet<_>) = sample
         .positive_words()
         .map(|w| (ts.omega_run::<_, run::Triggers<_>>(w), w))
         .partition_map(|(r, w)| match r {
             (Successful(v)) => Either::Left(v),
-            (Failed(q, ep)) => Either::Right((q, ep.suffix())),
+            (Failed(q, ep)) => Either::Right((q, ep.escape_symbol(), ep.suffix())),
         });
 
     let mut neg_successful = Vec::default();
@@ -385,7 +617,7 @@ where
         match output {
             Successful(v) => neg_successful.push(v),
             Failed(q, ep) => {
-                if pos_escaping.contains(&(q, ep.suffix())) {
+                if pos_escaping.contains(&(q, ep.escape_symbol(), ep.suffix())) {
                     return None;
                 }
             }
@@ -438,7 +670,10 @@ where
 mod tests {
     use super::*;
     use crate::passive::OmegaSample;
-    use automata::automaton::{BuchiCondition, MinEvenParityCondition, WithInitial};
+    use automata::automaton::{
+        BuchiCondition, CoBuchiCondition, MinEvenParityCondition, MullerCondition, RabinCondition,
+        WithInitial,
+    };
     use automata::core::alphabet::CharAlphabet;
     use automata::core::{Void, upw};
     use automata::{
@@ -772,4 +1007,208 @@ mod tests {
             dpa
         );
     }
+
+    #[test]
+    fn cobuchi_consistency() {
+        // build transition systems
+        let ts = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+        let ts2 = DTS::builder()
+            .with_transitions([(0, 'b', Void, 0), (0, 'a', Void, 1), (1, 'b', Void, 1)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build samples
+        let sample1 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("a")], [upw!("b")]);
+        let sample2 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("b")]);
+        let sample3 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("b")], [upw!("ab")]);
+
+        assert!(CoBuchiCondition.consistent(&ts, &sample1, vec![], vec![]).0);
+        // the negative infinity set is covered by the positive one
+        assert!(!CoBuchiCondition.consistent(&ts, &sample2, vec![], vec![]).0);
+        assert!(BuchiCondition.consistent(&ts, &sample2, vec![], vec![]).0);
+        assert!(
+            CoBuchiCondition
+                .consistent(&ts2, &sample3, vec![], vec![])
+                .0
+        );
+    }
+
+    #[test]
+    fn cobuchi_consistent_automaton() {
+        // build transition system
+        let ts = DTS::builder()
+            .with_transitions([(0, 'b', Void, 0), (0, 'a', Void, 1), (1, 'b', Void, 1)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build sample
+        let sample = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("b")], [upw!("ab")]);
+        let sample2: OmegaSample = OmegaSample::new_for_alphabet(sigma());
+
+        // build automaton
+        let dcba = DTS::builder()
+            .with_transitions([
+                (0, 'a', true, 1),
+                (0, 'b', false, 0),
+                (1, 'a', true, 2),
+                (1, 'b', true, 1),
+                (2, 'a', true, 2),
+                (2, 'b', true, 2),
+            ])
+            .default_color(Void)
+            .into_dts_with_initial(0)
+            .collect_dts_and_initial();
+        let dcba = DCBA::from_parts(dcba.0, dcba.1);
+
+        let res = CoBuchiCondition.consistent_automaton(&ts, &sample, vec![], vec![]);
+        let pos_sets: Vec<EdgeSet> = vec![vec![(0, 'b')].into_iter().collect()];
+        let neg_sets: Vec<EdgeSet> = vec![vec![(1, 'b')].into_iter().collect()];
+        let res2 = CoBuchiCondition.consistent_automaton(&ts, &sample2, pos_sets, neg_sets);
+
+        assert_eq!(res, dcba);
+        assert_eq!(res2, dcba);
+        assert!(res.accepts(upw!("aab", "b")).not());
+        assert!(res.accepts(upw!("bbb")));
+    }
+
+    #[test]
+    fn rabin_consistency() {
+        // build transition systems
+        let ts = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build samples
+        let sample1 =
+            OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("a"), upw!("b")], [upw!("ab")]);
+        let sample2 =
+            OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("a"), upw!("b")]);
+        let sample3 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("a")]);
+        let sample4 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("aab")]);
+
+        let rabin = RabinCondition::default();
+        // not possible with a parity condition
+        assert!(rabin.consistent(&ts, &sample1, vec![], vec![]).0);
+        assert!(
+            !MinEvenParityCondition
+                .consistent(&ts, &sample1, vec![], vec![])
+                .0
+        );
+        // the positive set is the union of two negative sets
+        assert!(!rabin.consistent(&ts, &sample2, vec![], vec![]).0);
+        assert!(rabin.consistent(&ts, &sample3, vec![], vec![]).0);
+        // positive and negative words induce the same infinity set
+        assert!(!rabin.consistent(&ts, &sample4, vec![], vec![]).0);
+    }
+
+    #[test]
+    fn rabin_consistent_automaton() {
+        // build transition systems
+        let ts = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+        let ts2 = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build samples
+        let sample =
+            OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("a"), upw!("b")], [upw!("ab")]);
+        let sample2 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("a")], [upw!("b")]);
+
+        let res = RabinCondition::default().consistent_automaton(&ts, &sample, vec![], vec![]);
+        let res2 = RabinCondition::default().consistent_automaton(&ts2, &sample2, vec![], vec![]);
+
+        for (dra, sample) in [(res, sample), (res2, sample2)] {
+            assert!(sample.positive_words().all(|w| dra.accepts(w)));
+            assert!(sample.negative_words().all(|w| !dra.accepts(w)));
+        }
+    }
+
+    #[test]
+    fn muller_consistency() {
+        // build transition systems
+        let ts = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build samples
+        let sample1 =
+            OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("a"), upw!("b")]);
+        let sample2 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("aab")]);
+
+        let muller = MullerCondition::default();
+        // neither Rabin nor parity can separate these
+        assert!(muller.consistent(&ts, &sample1, vec![], vec![]).0);
+        assert!(
+            !RabinCondition::default()
+                .consistent(&ts, &sample1, vec![], vec![])
+                .0
+        );
+        // positive and negative words induce the same infinity set
+        assert!(!muller.consistent(&ts, &sample2, vec![], vec![]).0);
+    }
+
+    #[test]
+    fn muller_consistent_automaton() {
+        // build transition systems
+        let ts = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0), (0, 'b', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+        let ts2 = DTS::builder()
+            .with_transitions([(0, 'a', Void, 0)])
+            .default_color(Void)
+            .into_dts_with_initial(0);
+
+        // build samples
+        let sample =
+            OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("ab")], [upw!("a"), upw!("b")]);
+        let sample2 = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("a")], [upw!("b")]);
+
+        let res = MullerCondition::default().consistent_automaton(&ts, &sample, vec![], vec![]);
+        let res2 = MullerCondition::default().consistent_automaton(&ts2, &sample2, vec![], vec![]);
+
+        for (dma, sample) in [(res, sample), (res2, sample2)] {
+            assert!(sample.positive_words().all(|w| dma.accepts(w)));
+            assert!(sample.negative_words().all(|w| !dma.accepts(w)));
+        }
+    }
+
+    #[test]
+    fn default_automata() {
+        let sample = OmegaSample::new_omega_from_pos_neg(sigma(), [upw!("abb")], [upw!("ab")]);
+        type Ts = WithInitial<DTS>;
+
+        let dcba = <CoBuchiCondition as ConsistencyCheck<Ts>>::default_automaton(
+            &CoBuchiCondition,
+            &sample,
+        );
+        let dra = <RabinCondition<usize> as ConsistencyCheck<Ts>>::default_automaton(
+            &RabinCondition::default(),
+            &sample,
+        );
+        let dma = <MullerCondition<usize> as ConsistencyCheck<Ts>>::default_automaton(
+            &MullerCondition::default(),
+            &sample,
+        );
+
+        for w in [upw!("abb"), upw!("a", "bba")] {
+            assert!(dcba.accepts(&w));
+            assert!(dra.accepts(&w));
+            assert!(dma.accepts(&w));
+        }
+        for w in [upw!("ab"), upw!("b"), upw!("a")] {
+            assert!(!dcba.accepts(&w));
+            assert!(!dra.accepts(&w));
+            assert!(!dma.accepts(&w));
+        }
+    }
 }
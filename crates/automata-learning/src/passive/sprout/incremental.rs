@@ -0,0 +1,307 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+use automata::core::math;
+use automata::core::word::ReducedOmegaWord;
+use automata::ts::run::{self, InfiniteRunOutput};
+use automata::ts::{Deterministic, StateIndex};
+
+use super::{ConsistencyCheck, EdgeSet, OmegaSample, SproutTs};
+
+/// The state and symbol with which a run leaves the transition system.
+type EscapePoint = (u32, char);
+
+/// Sample words that escape from the same state with the same symbol and the same remaining
+/// suffix. Their runs coincide on every extension of the transition system, so they are moved
+/// together.
+#[derive(Debug, Clone, Default)]
+struct Escaping {
+    positive: Vec<ReducedOmegaWord<char>>,
+    negative: Vec<ReducedOmegaWord<char>>,
+}
+
+impl Escaping {
+    fn labels(&self) -> Labels {
+        (!self.positive.is_empty(), !self.negative.is_empty())
+    }
+
+    /// A bad pair consists of a positive and a negative word with the same escape.
+    fn is_bad(&self) -> bool {
+        self.labels() == (true, true)
+    }
+
+    fn merge(&mut self, other: Escaping) {
+        self.positive.extend(other.positive);
+        self.negative.extend(other.negative);
+    }
+}
+
+/// Whether a positive and whether a negative word belongs to a group of [`Escaping`] words.
+type Labels = (bool, bool);
+
+/// The effect of adding a single transition, which is computed by resuming the partial runs of
+/// the sample words that escape through it. Groups are identified by their index.
+#[derive(Debug)]
+struct Advance {
+    point: EscapePoint,
+    escaping: Vec<(EscapePoint, ReducedOmegaWord<char>, usize, Labels)>,
+    successful: Vec<(EdgeSet, usize, Labels)>,
+}
+
+/// Keeps the partial runs of all sample words that are still escaping from the transition
+/// system that [`super::sprout`] is building, together with the infinity sets of all words
+/// whose runs are successful. When a transition is added, only the words that escape through
+/// it are re-evaluated instead of the whole sample.
+///
+/// If `incremental` is `false`, every check runs the whole remaining sample, which is only
+/// useful for comparing against the incremental version.
+#[derive(Debug)]
+pub(super) struct SproutRuns {
+    incremental: bool,
+    sample: OmegaSample,
+    pos_sets: Vec<EdgeSet>,
+    neg_sets: Vec<EdgeSet>,
+    escapes: HashMap<EscapePoint, math::Map<ReducedOmegaWord<char>, Escaping>>,
+    bad_pairs: usize,
+}
+
+impl SproutRuns {
+    /// Runs all words of `sample` on `ts`.
+    pub(super) fn new(ts: &SproutTs, sample: OmegaSample, incremental: bool) -> Self {
+        let mut out = Self {
+            incremental,
+            sample,
+            pos_sets: vec![],
+            neg_sets: vec![],
+            escapes: HashMap::new(),
+            bad_pairs: 0,
+        };
+        if !incremental {
+            return out;
+        }
+
+        let mut successful = vec![];
+        for (word, positive) in out.sample.entries() {
+            let mut group = Escaping::default();
+            if positive {
+                group.positive.push(word.clone());
+            } else {
+                group.negative.push(word.clone());
+            }
+            match ts.omega_run::<_, run::Triggers<_>>(word) {
+                InfiniteRunOutput::Successful(set) => successful.push((set, group)),
+                InfiniteRunOutput::Failed(q, ep) => {
+                    let point = (q, ep.escape_symbol());
+                    let entry = out
+                        .escapes
+                        .entry(point)
+                        .or_default()
+                        .entry(ep.suffix())
+                        .or_default();
+                    let was_bad = entry.is_bad();
+                    entry.merge(group);
+                    if !was_bad && entry.is_bad() {
+                        out.bad_pairs += 1;
+                    }
+                }
+            }
+        }
+        let removed = out.record_successful(successful);
+        out.retain_words(&removed);
+        out
+    }
+
+    /// The sample words that are still escaping.
+    pub(super) fn sample(&self) -> &OmegaSample {
+        &self.sample
+    }
+
+    /// Decomposes `self` into the escaping sample words and the infinity sets of the positive
+    /// and negative words that are no longer escaping.
+    pub(super) fn into_parts(self) -> (OmegaSample, Vec<EdgeSet>, Vec<EdgeSet>) {
+        (self.sample, self.pos_sets, self.neg_sets)
+    }
+
+    /// Checks whether `ts`, to which the transition from `source` on `sym` has just been added,
+    /// is consistent with the sample. If so, the runs are updated accordingly and `true` is
+    /// returned, otherwise `self` is left unchanged.
+    pub(super) fn try_edge<A>(
+        &mut self,
+        ts: &SproutTs,
+        acc_type: &A,
+        source: u32,
+        sym: char,
+    ) -> bool
+    where
+        A: ConsistencyCheck<SproutTs>,
+    {
+        if !self.incremental {
+            let (is_consistent, pos_sets, neg_sets) = acc_type.consistent(
+                ts,
+                &self.sample,
+                self.pos_sets.clone(),
+                self.neg_sets.clone(),
+            );
+            if is_consistent {
+                self.pos_sets = pos_sets;
+                self.neg_sets = neg_sets;
+                self.sample.remove_non_escaping(ts);
+            }
+            return is_consistent;
+        }
+
+        let advance = self.advance(ts, source, sym);
+        if self.bad_pairs_after(&advance) > 0 {
+            return false;
+        }
+
+        // temporarily add the new infinity sets for the check
+        let (pos_len, neg_len) = (self.pos_sets.len(), self.neg_sets.len());
+        for (set, _, (positive, negative)) in &advance.successful {
+            if *positive {
+                self.pos_sets.push(set.clone());
+            }
+            if *negative {
+                self.neg_sets.push(set.clone());
+            }
+        }
+        let is_consistent = acc_type.consistent_sets(ts, &self.pos_sets, &self.neg_sets);
+        self.pos_sets.truncate(pos_len);
+        self.neg_sets.truncate(neg_len);
+
+        if is_consistent {
+            self.remove_non_escaping(advance);
+        }
+        is_consistent
+    }
+
+    /// Records that the transition from `source` on `sym` has been added to `ts` without
+    /// checking consistency, which is the case if it leads to a new state.
+    pub(super) fn force_edge(&mut self, ts: &SproutTs, source: u32, sym: char) {
+        if self.incremental {
+            let advance = self.advance(ts, source, sym);
+            self.remove_non_escaping(advance);
+        }
+    }
+
+    /// Resumes the runs of all words that escape from `source` on `sym` in the target of the
+    /// corresponding transition, which must exist in `ts`.
+    fn advance(&self, ts: &SproutTs, source: u32, sym: char) -> Advance {
+        let target: StateIndex<SproutTs> = ts
+            .successor_index(source, sym)
+            .expect("transition must have been added");
+
+        let mut advance = Advance {
+            point: (source, sym),
+            escaping: vec![],
+            successful: vec![],
+        };
+        let Some(groups) = self.escapes.get(&(source, sym)) else {
+            return advance;
+        };
+        for (i, (suffix, group)) in groups.iter().enumerate() {
+            match ts.omega_run_from::<_, run::Triggers<_>>(target, suffix) {
+                InfiniteRunOutput::Successful(set) => {
+                    advance.successful.push((set, i, group.labels()));
+                }
+                InfiniteRunOutput::Failed(q, ep) => {
+                    let point = (q, ep.escape_symbol());
+                    advance
+                        .escaping
+                        .push((point, ep.suffix(), i, group.labels()));
+                }
+            }
+        }
+        advance
+    }
+
+    /// Computes the number of bad pairs that would exist after applying `advance`.
+    fn bad_pairs_after(&self, advance: &Advance) -> usize {
+        let removed = self
+            .escapes
+            .get(&advance.point)
+            .map(|groups| groups.values().filter(|g| g.is_bad()).count())
+            .unwrap_or(0);
+
+        // the runs that still escape may be merged with each other and with existing ones
+        let existing = |point: &EscapePoint, suffix: &ReducedOmegaWord<char>| {
+            self.escapes
+                .get(point)
+                .and_then(|groups| groups.get(suffix))
+                .map(Escaping::labels)
+        };
+        let mut merged: HashMap<(EscapePoint, &ReducedOmegaWord<char>), Labels> = HashMap::new();
+        for (point, suffix, _, (positive, negative)) in &advance.escaping {
+            let labels = merged
+                .entry((*point, suffix))
+                .or_insert_with(|| existing(point, suffix).unwrap_or_default());
+            labels.0 |= positive;
+            labels.1 |= negative;
+        }
+        let added = merged
+            .iter()
+            .filter(|((point, suffix), labels)| {
+                **labels == (true, true) && existing(point, suffix) != Some((true, true))
+            })
+            .count();
+
+        self.bad_pairs - removed + added
+    }
+
+    /// Applies `advance` by moving the resumed runs to their new escape points and removing all
+    /// words that are no longer escaping from the sample, while keeping their infinity sets.
+    fn remove_non_escaping(&mut self, advance: Advance) {
+        self.bad_pairs = self.bad_pairs_after(&advance);
+        let mut groups = self
+            .escapes
+            .remove(&advance.point)
+            .unwrap_or_default()
+            .into_values()
+            .map(Some)
+            .collect_vec();
+        for (point, suffix, i, _) in advance.escaping {
+            let group = groups[i].take().expect("group must exist");
+            self.escapes
+                .entry(point)
+                .or_default()
+                .entry(suffix)
+                .or_default()
+                .merge(group);
+        }
+        let successful = advance
+            .successful
+            .into_iter()
+            .map(|(set, i, _)| (set, groups[i].take().expect("group must exist")))
+            .collect();
+        let removed = self.record_successful(successful);
+        self.retain_words(&removed);
+    }
+
+    /// Stores the infinity sets of the given successful runs and returns the corresponding words.
+    fn record_successful(
+        &mut self,
+        successful: Vec<(EdgeSet, Escaping)>,
+    ) -> HashSet<ReducedOmegaWord<char>> {
+        let mut removed = HashSet::new();
+        for (set, group) in successful {
+            if !group.positive.is_empty() {
+                self.pos_sets.push(set.clone());
+            }
+            if !group.negative.is_empty() {
+                self.neg_sets.push(set);
+            }
+            removed.extend(group.positive);
+            removed.extend(group.negative);
+        }
+        removed
+    }
+
+    fn retain_words(&mut self, removed: &HashSet<ReducedOmegaWord<char>>) {
+        if removed.is_empty() {
+            return;
+        }
+        self.sample.positive.retain(|w| !removed.contains(w));
+        self.sample.negative.retain(|w| !removed.contains(w));
+    }
+}
@@ -11,6 +11,32 @@ use tracing::trace;
 pub fn prefix_tree<A: Alphabet, W: Into<ReducedOmegaWord<A::Symbol>>, I: IntoIterator<Item = W>>(
     alphabet: A,
     words: I,
+) -> RightCongruence<A, Vec<A::Symbol>> {
+    build_prefix_tree(alphabet, words, true)
+}
+
+/// Builds a prefix tree like [`prefix_tree`], but no loop closes in the root. So every state on
+/// a loop has a non-empty access word, which is needed when the states are identified with the
+/// periodic words that they loop on.
+pub(crate) fn periodic_prefix_tree<
+    A: Alphabet,
+    W: Into<ReducedOmegaWord<A::Symbol>>,
+    I: IntoIterator<Item = W>,
+>(
+    alphabet: A,
+    words: I,
+) -> RightCongruence<A, Vec<A::Symbol>> {
+    build_prefix_tree(alphabet, words, false)
+}
+
+fn build_prefix_tree<
+    A: Alphabet,
+    W: Into<ReducedOmegaWord<A::Symbol>>,
+    I: IntoIterator<Item = W>,
+>(
+    alphabet: A,
+    words: I,
+    loop_in_root: bool,
 ) -> RightCongruence<A, Vec<A::Symbol>> {
     let words: Vec<ReducedOmegaWord<_>> = words.into_iter().map(|word| word.into()).collect_vec();
     debug_assert!(words.iter().all(|word| !word.raw_word().is_empty()));
@@ -39,12 +65,16 @@ pub fn prefix_tree<A: Alphabet, W: Into<ReducedOmegaWord<A::Symbol>>, I: IntoIte
     let mut tree = RightCongruence::new_with_initial_color(alphabet.clone(), vec![]);
     let root = tree.initial();
 
+    // without any words, the prefix tree consists only of the root
+    if words.is_empty() {
+        return tree;
+    }
     let mut queue = VecDeque::from_iter([(root, vec![], words.to_vec())]);
 
     while let Some((state, access, words)) = queue.pop_front() {
         debug_assert!(!words.is_empty());
         debug_assert!(words.iter().all(|word| !word.raw_word().is_empty()));
-        if words.len() == 1 && words[0].loop_index() == 0 {
+        if words.len() == 1 && words[0].loop_index() == 0 && (loop_in_root || state != root) {
             build_accepting_loop(&alphabet, &mut tree, state, access, words[0].cycle());
         } else {
             let mut map: math::Map<_, math::Set<_>> = math::Map::default();
@@ -80,6 +110,18 @@ pub fn prefix_tree<A: Alphabet, W: Into<ReducedOmegaWord<A::Symbol>>, I: IntoIte
 #[cfg(test)]
 mod tests {
     use super::prefix_tree;
+    use automata::TransitionSystem;
+    use automata::core::alphabet::CharAlphabet;
+    use automata::core::word::ReducedOmegaWord;
+
+    #[test]
+    fn empty_prefix_tree() {
+        let pta = prefix_tree(
+            CharAlphabet::of_size(2),
+            Vec::<ReducedOmegaWord<char>>::new(),
+        );
+        assert_eq!(pta.size(), 1);
+    }
 
     #[test]
     #[ignore]
@@ -15,14 +15,15 @@ mod mealy;
 pub use mealy::{IntoMealyMachine, MealyLike, MealyMachine, MealySemantics};
 
 mod reachability;
-pub use reachability::{DFA, IntoDFA, ReachabilityCondition};
+pub use reachability::{DFA, IntoDFA, IntoNFA, NFA, ReachabilityCondition};
 
 mod omega;
 pub use omega::{
-    AcceptanceMask, BuchiCondition, DBA, DMA, DPA, DRA, DeterministicOmegaAutomaton, IntoDBA,
-    IntoDMA, IntoDPA, IntoDRA, MaxEvenParityCondition, MaxOddParityCondition,
-    MinEvenParityCondition, MinOddParityCondition, MullerCondition, NondeterministicOmegaAutomaton,
-    OmegaAcceptanceCondition, OmegaAutomaton, RabinCondition, RabinPair,
+    AcceptanceMask, BuchiCondition, CoBuchiCondition, DBA, DCBA, DMA, DPA, DRA,
+    DeterministicOmegaAutomaton, IntoDBA, IntoDCBA, IntoDMA, IntoDPA, IntoDRA,
+    MaxEvenParityCondition, MaxOddParityCondition, MinEvenParityCondition, MinOddParityCondition,
+    MullerCondition, NondeterministicOmegaAutomaton, OmegaAcceptanceCondition, OmegaAutomaton,
+    RabinCondition, RabinPair,
 };
 
 mod with_initial;
@@ -35,6 +36,15 @@ pub use semantics::Semantics;
 
 mod deterministic;
 
+mod language_operations;
+
+mod homomorphism;
+
+mod projection;
+
+mod alignment;
+pub use alignment::AlphabetExtension;
+
 mod priority_mapping;
 use crate::ts::predecessors::PredecessorIterable;
 use crate::ts::{
@@ -290,7 +300,8 @@ where
 {
 }
 
-impl<A, Z, Q, C, D, const OMEGA: bool> PredecessorIterable for Automaton<A, Z, Q, C, D, OMEGA>
+impl<A, Z, Q, C, D, const OMEGA: bool, const DET: bool> PredecessorIterable
+    for Automaton<A, Z, Q, C, D, OMEGA, DET>
 where
     A: Alphabet,
     D: TransitionSystem<Alphabet = A, StateColor = Q, EdgeColor = C> + PredecessorIterable,
@@ -312,7 +323,8 @@ where
     }
 }
 
-impl<A, Z, Q, C, D, const OMEGA: bool> Pointed for Automaton<A, Z, Q, C, D, OMEGA>
+impl<A, Z, Q, C, D, const OMEGA: bool, const DET: bool> Pointed
+    for Automaton<A, Z, Q, C, D, OMEGA, DET>
 where
     A: Alphabet,
     D: TransitionSystem<Alphabet = A, StateColor = Q, EdgeColor = C>,
@@ -396,7 +408,8 @@ where
     }
 }
 
-impl<A, Z, Q, C, D, const OMEGA: bool> TransitionSystem for Automaton<A, Z, Q, C, D, OMEGA>
+impl<A, Z, Q, C, D, const OMEGA: bool, const DET: bool> TransitionSystem
+    for Automaton<A, Z, Q, C, D, OMEGA, DET>
 where
     A: Alphabet,
     D: TransitionSystem<Alphabet = A, StateColor = Q, EdgeColor = C>,
@@ -0,0 +1,313 @@
+use crate::automaton::{DFA, DPA, IntoDFA, IntoDPA};
+use crate::core::{
+    Color, Int, Void,
+    alphabet::{Alphabet, CharAlphabet},
+    math,
+};
+use crate::representation::CollectTs;
+use crate::ts::{DefaultIdType, Deterministic, ForAlphabet, IsEdge, Sproutable};
+use crate::{Congruence, DTS, Pointed, TransitionSystem};
+
+/// Determines how an automaton treats the symbols that are added to its alphabet when it is
+/// aligned with a larger alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphabetExtension {
+    /// Every added symbol leads to a fresh rejecting sink state.
+    #[default]
+    Sink,
+    /// Every added symbol leads back to the state it is read in.
+    SelfLoop,
+}
+
+/// Copies the part of `ts` that is labeled with symbols of `alphabet` into a new [`DTS`] over
+/// `alphabet`. Edges on symbols that are not in `alphabet` are dropped. Symbols that are in
+/// `alphabet` but not in the alphabet of `ts` are handled according to `extension`. Existing edges
+/// are recolored with `edge_color`, while all added edges obtain the color `added_edge`. The
+/// state color of each state is computed with `state_color`, which receives `None` for the sink.
+///
+/// Returns the new transition system and the index of `initial` in it.
+fn align<T, Q, C>(
+    ts: &T,
+    initial: T::StateIndex,
+    alphabet: &CharAlphabet,
+    extension: AlphabetExtension,
+    state_color: impl Fn(Option<T::StateColor>) -> Q,
+    edge_color: impl Fn(T::EdgeColor) -> C,
+    added_edge: C,
+) -> (DTS<CharAlphabet, Q, C>, DefaultIdType)
+where
+    T: Deterministic<Alphabet = CharAlphabet>,
+    Q: Color,
+    C: Color,
+{
+    let mut out = DTS::for_alphabet(alphabet.clone());
+    let map: math::Map<_, _> = ts
+        .state_indices()
+        .map(|q| (q, out.add_state(state_color(ts.state_color(q)))))
+        .collect();
+    let added: Vec<_> = alphabet
+        .universe()
+        .filter(|sym| !ts.alphabet().contains(*sym))
+        .collect();
+
+    let mut sink = None;
+    for (q, source) in &map {
+        for edge in ts.edges_from(*q).expect("state must exist") {
+            let sym = *edge.expression();
+            if alphabet.contains(sym) {
+                out.add_edge((*source, sym, edge_color(edge.color()), map[&edge.target()]));
+            }
+        }
+        for sym in &added {
+            let target = match extension {
+                AlphabetExtension::SelfLoop => *source,
+                AlphabetExtension::Sink => {
+                    *sink.get_or_insert_with(|| out.add_state(state_color(None)))
+                }
+            };
+            out.add_edge((*source, *sym, added_edge.clone(), target));
+        }
+    }
+    if let Some(sink) = sink {
+        for sym in alphabet.universe() {
+            out.add_edge((sink, sym, added_edge.clone(), sink));
+        }
+    }
+    (out, map[&initial])
+}
+
+impl<D> IntoDFA<D>
+where
+    D: Deterministic<Alphabet = CharAlphabet, StateColor = bool>,
+{
+    /// Aligns `self` with the given `alphabet`, meaning all transitions on symbols that do not
+    /// occur in `alphabet` are removed, while the symbols of `alphabet` that are unknown to `self`
+    /// are handled according to `extension`.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::{AlphabetExtension, DFA};
+    /// use automata::core::alphabet::CharAlphabet;
+    ///
+    /// // accepts all words over {a, b} that end with `a`
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([false, true])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 1), (1, 'b', 0)])
+    ///     .into_dfa(0);
+    /// let alphabet = CharAlphabet::of_size(3);
+    ///
+    /// let with_sink = dfa.align_to(&alphabet, AlphabetExtension::Sink);
+    /// assert!(!with_sink.accepts("aca"));
+    /// let with_loops = dfa.align_to(&alphabet, AlphabetExtension::SelfLoop);
+    /// assert!(with_loops.accepts("aca"));
+    /// assert!(with_loops.accepts("ac"));
+    /// ```
+    pub fn align_to(&self, alphabet: &CharAlphabet, extension: AlphabetExtension) -> DFA {
+        let (ts, initial) = align(
+            self,
+            self.initial(),
+            alphabet,
+            extension,
+            |c| c.unwrap_or(false),
+            |_| Void,
+            Void,
+        );
+        DFA::from_parts(ts, initial)
+    }
+
+    /// Extends `self` to the given `alphabet`, which must contain every symbol of the alphabet
+    /// of `self`. The new symbols are handled according to `extension`.
+    pub fn extend_alphabet(&self, alphabet: &CharAlphabet, extension: AlphabetExtension) -> DFA {
+        assert!(
+            self.alphabet().is_subset_of(alphabet),
+            "can only extend to a larger alphabet"
+        );
+        self.align_to(alphabet, extension)
+    }
+
+    /// Restricts `self` to the given `alphabet`, which must be contained in the alphabet of `self`.
+    /// All transitions on other symbols are removed.
+    pub fn restrict_alphabet(&self, alphabet: &CharAlphabet) -> DFA {
+        assert!(
+            alphabet.is_subset_of(self.alphabet()),
+            "can only restrict to a smaller alphabet"
+        );
+        self.align_to(alphabet, AlphabetExtension::Sink)
+    }
+
+    /// Checks whether `self` and `other` accept the same language after both have been aligned
+    /// with the union of their alphabets according to `extension`. See [`Self::equivalent`].
+    pub fn equivalent_aligned<E>(&self, other: E, extension: AlphabetExtension) -> bool
+    where
+        E: Congruence<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let (left, right) = self.aligned_with(other, extension);
+        left.equivalent(right)
+    }
+
+    /// Computes the union of `self` and `other` after both have been aligned with the union of
+    /// their alphabets according to `extension`. See [`Self::union`].
+    pub fn union_aligned<E>(&self, other: E, extension: AlphabetExtension) -> DFA
+    where
+        E: Congruence<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let (left, right) = self.aligned_with(other, extension);
+        left.union(right).collect_dfa()
+    }
+
+    /// Computes the intersection of `self` and `other` after both have been aligned with the union
+    /// of their alphabets according to `extension`. See [`Self::intersection`].
+    pub fn intersection_aligned<E>(&self, other: E, extension: AlphabetExtension) -> DFA
+    where
+        E: Congruence<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let (left, right) = self.aligned_with(other, extension);
+        left.intersection(right).collect_dfa()
+    }
+
+    fn aligned_with<E>(&self, other: E, extension: AlphabetExtension) -> (DFA, DFA)
+    where
+        E: Congruence<Alphabet = CharAlphabet, StateColor = bool>,
+    {
+        let alphabet = self.alphabet().union(other.alphabet());
+        let initial = other.initial();
+        let other = IntoDFA::from_parts(other, initial);
+        (
+            self.align_to(&alphabet, extension),
+            other.align_to(&alphabet, extension),
+        )
+    }
+}
+
+impl<D> IntoDPA<D>
+where
+    D: Deterministic<Alphabet = CharAlphabet, EdgeColor = Int>,
+{
+    /// Aligns `self` with the given `alphabet`, meaning all transitions on symbols that do not
+    /// occur in `alphabet` are removed, while the symbols of `alphabet` that are unknown to `self`
+    /// are handled according to `extension`. Both the edges into the sink and the added self-loops
+    /// are colored with the least odd priority that is at least as large as every priority that
+    /// occurs in `self`. Thus, the self-loops do not influence whether a word that infinitely often
+    /// reads a symbol of the original alphabet is accepted.
+    pub fn align_to(&self, alphabet: &CharAlphabet, extension: AlphabetExtension) -> DPA {
+        let high = self.colors().max().unwrap_or(0);
+        let odd = high + (1 - high % 2);
+        let (ts, initial) = align(
+            self,
+            self.initial(),
+            alphabet,
+            extension,
+            |_| Void,
+            |c| c,
+            odd,
+        );
+        DPA::from_parts(ts, initial)
+    }
+
+    /// Extends `self` to the given `alphabet`, which must contain every symbol of the alphabet
+    /// of `self`. The new symbols are handled according to `extension`, see [`Self::align_to`].
+    pub fn extend_alphabet(&self, alphabet: &CharAlphabet, extension: AlphabetExtension) -> DPA {
+        assert!(
+            self.alphabet().is_subset_of(alphabet),
+            "can only extend to a larger alphabet"
+        );
+        self.align_to(alphabet, extension)
+    }
+
+    /// Restricts `self` to the given `alphabet`, which must be contained in the alphabet of `self`.
+    /// All transitions on other symbols are removed.
+    pub fn restrict_alphabet(&self, alphabet: &CharAlphabet) -> DPA {
+        assert!(
+            alphabet.is_subset_of(self.alphabet()),
+            "can only restrict to a smaller alphabet"
+        );
+        self.align_to(alphabet, AlphabetExtension::Sink)
+    }
+
+    /// Checks whether `self` and `other` are language-equivalent after both have been aligned with
+    /// the union of their alphabets according to `extension`. See [`Self::language_equivalent`].
+    pub fn language_equivalent_aligned<O>(
+        &self,
+        other: &IntoDPA<O>,
+        extension: AlphabetExtension,
+    ) -> bool
+    where
+        O: Deterministic<Alphabet = CharAlphabet, EdgeColor = Int>,
+    {
+        let alphabet = self.alphabet().union(other.alphabet());
+        self.align_to(&alphabet, extension)
+            .language_equivalent(&other.align_to(&alphabet, extension))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AlphabetExtension;
+    use crate::TransitionSystem;
+    use crate::automaton::DFA;
+    use crate::core::alphabet::CharAlphabet;
+    use crate::ts::TSBuilder;
+
+    #[test]
+    fn align_dfas() {
+        // words over {a, b} with an even number of `a`s
+        let small = DFA::builder()
+            .with_state_colors([true, false])
+            .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 0), (1, 'b', 1)])
+            .into_dfa(0);
+        // words over {a, b, c} with an even number of `a`s
+        let large = DFA::builder()
+            .with_state_colors([true, false])
+            .with_edges([
+                (0, 'a', 1),
+                (0, 'b', 0),
+                (0, 'c', 0),
+                (1, 'a', 0),
+                (1, 'b', 1),
+                (1, 'c', 1),
+            ])
+            .into_dfa(0);
+
+        assert!(small.equivalent_aligned(&large, AlphabetExtension::SelfLoop));
+        assert!(!small.equivalent_aligned(&large, AlphabetExtension::Sink));
+        assert!(
+            large
+                .restrict_alphabet(&CharAlphabet::of_size(2))
+                .equivalent(&small)
+        );
+
+        let extended = small.extend_alphabet(&CharAlphabet::of_size(3), AlphabetExtension::Sink);
+        assert_eq!(extended.size(), 3);
+        assert!(extended.is_complete());
+        assert!(
+            extended
+                .intersection_aligned(&large, AlphabetExtension::Sink)
+                .equivalent(&extended)
+        );
+        assert!(
+            extended
+                .union_aligned(&large, AlphabetExtension::Sink)
+                .equivalent(&large)
+        );
+    }
+
+    #[test]
+    fn align_dpas() {
+        // infinitely many `a`s over {a, b}
+        let small = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        // infinitely many `a`s over {a, b, c}
+        let large = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0), (0, 'c', 1, 0)])
+            .into_dpa(0);
+
+        assert!(small.language_equivalent_aligned(&large, AlphabetExtension::SelfLoop));
+        assert!(!small.language_equivalent_aligned(&large, AlphabetExtension::Sink));
+        assert!(
+            large
+                .restrict_alphabet(&CharAlphabet::of_size(2))
+                .language_equivalent(&small)
+        );
+    }
+}
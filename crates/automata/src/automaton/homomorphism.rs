@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+
+use crate::automaton::{
+    AcceptanceMask, DFA, DPA, IntoDFA, IntoDPA, IntoNFA, NFA, NondeterministicOmegaAutomaton,
+    OmegaAcceptanceCondition,
+};
+use crate::core::{
+    Color, Int, Void,
+    alphabet::{Alphabet, Expression, Homomorphism},
+    math,
+};
+use crate::ts::{DefaultIdType, Deterministic, ForAlphabet, IsEdge, Sproutable, StateIndex};
+use crate::{DTS, NTS, Pointed, TS, TransitionSystem};
+
+/// Explores the deterministic transition system that is induced by the given `successor` function,
+/// starting from `initial`. Each explored state `x` obtains the color `color(x)` and for each symbol
+/// of `alphabet` on which `successor` yields a color and a target, a corresponding edge is added.
+/// Returns the resulting transition system, whose initial state is always `0`.
+fn explore_deterministic<X, B, Q, C>(
+    alphabet: &B,
+    initial: X,
+    color: impl Fn(X) -> Q,
+    successor: impl Fn(X, B::Symbol) -> Option<(C, X)>,
+) -> DTS<B, Q, C>
+where
+    X: Ord + Copy,
+    B: Alphabet,
+    Q: Color,
+    C: Color,
+{
+    let mut ts = DTS::for_alphabet(alphabet.clone());
+    let mut map: math::OrderedMap<X, DefaultIdType> = math::OrderedMap::default();
+    map.insert(initial, ts.add_state(color(initial)));
+    let mut queue = VecDeque::from([initial]);
+
+    while let Some(x) = queue.pop_front() {
+        let source = map[&x];
+        for sym in alphabet.universe() {
+            let Some((c, y)) = successor(x, sym) else {
+                continue;
+            };
+            let target = match map.get(&y) {
+                Some(target) => *target,
+                None => {
+                    let target = ts.add_state(color(y));
+                    map.insert(y, target);
+                    queue.push_back(y);
+                    target
+                }
+            };
+            ts.add_edge((source, alphabet.make_expression(sym), c, target));
+        }
+    }
+    ts
+}
+
+/// Builds the homomorphic image of the part of `ts` that is reachable from `initial`. Each edge
+/// is replaced by a chain of edges that reads the image of its symbol, where every edge in the
+/// chain obtains the color `color` yields for the original edge. Images that are empty lead
+/// to epsilon transitions which are subsequently eliminated, where a state obtains the
+/// join (computed by `join`) of all state colors that are reachable via epsilon transitions.
+///
+/// The newly created intermediate states obtain the state color `intermediate`.
+fn homomorphic_image_nts<T, B, Q, C>(
+    ts: &T,
+    initial: StateIndex<T>,
+    h: &Homomorphism<T::Alphabet, B>,
+    intermediate: Q,
+    join: impl Fn(Q, Q) -> Q,
+    color: impl Fn(T::EdgeColor) -> C,
+) -> NTS<B, Q, C>
+where
+    T: TransitionSystem<StateColor = Q>,
+    B: Alphabet,
+    Q: Color,
+    C: Color + Ord,
+{
+    // the indices are assigned in the order in which states are reached, so the map has to
+    // preserve insertion order for the colors to line up with the indices
+    let map: math::Map<_, _> = ts
+        .reachable_state_indices_from(initial)
+        .enumerate()
+        .map(|(i, q)| (q, i))
+        .collect();
+    let mut colors: Vec<Q> = map
+        .keys()
+        .map(|q| ts.state_color(*q).expect("state must exist"))
+        .collect();
+    let mut epsilon: Vec<Vec<usize>> = vec![vec![]; map.len()];
+    let mut edges = math::OrderedSet::default();
+
+    for (&q, &source) in &map {
+        for edge in ts.edges_from(q).expect("state must exist") {
+            let target = map[&edge.target()];
+            let c = color(edge.color());
+            for sym in edge.expression().symbols() {
+                let Some((last, init)) = h.image(sym).split_last() else {
+                    epsilon[source].push(target);
+                    continue;
+                };
+                let mut current = source;
+                for b in init {
+                    colors.push(intermediate.clone());
+                    epsilon.push(vec![]);
+                    edges.insert((current, *b, c.clone(), colors.len() - 1));
+                    current = colors.len() - 1;
+                }
+                edges.insert((current, *last, c.clone(), target));
+            }
+        }
+    }
+
+    let mut outgoing = vec![vec![]; colors.len()];
+    for (p, b, c, q) in edges {
+        outgoing[p].push((b, c, q));
+    }
+
+    let mut nts = NTS::for_alphabet(h.codomain().clone());
+    let mut closed_edges = math::OrderedSet::default();
+    for p in 0..colors.len() {
+        let mut closure = math::OrderedSet::from_iter([p]);
+        let mut stack = vec![p];
+        while let Some(q) = stack.pop() {
+            for &r in &epsilon[q] {
+                if closure.insert(r) {
+                    stack.push(r);
+                }
+            }
+        }
+        let mut it = closure.iter();
+        let first = colors[*it.next().expect("contains p")].clone();
+        let color = it.fold(first, |acc, q| join(acc, colors[*q].clone()));
+        nts.add_state(color);
+        for q in closure {
+            for (b, c, r) in &outgoing[q] {
+                closed_edges.insert((p, *b, c.clone(), *r));
+            }
+        }
+    }
+    for (p, b, c, q) in closed_edges {
+        nts.add_edge((
+            p as DefaultIdType,
+            h.codomain().make_expression(b),
+            c,
+            q as DefaultIdType,
+        ));
+    }
+    nts
+}
+
+impl<D> IntoDFA<D>
+where
+    D: Deterministic<StateColor = bool>,
+{
+    /// Builds an [`NFA`] that accepts the homomorphic image of the language accepted by `self`
+    /// under `h`, i.e. the set of all words `h(w)` where `w` is accepted by `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::DFA;
+    /// use automata::core::alphabet::{CharAlphabet, Homomorphism};
+    ///
+    /// // accepts all words with an even number of `a`s
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([true, false])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 0), (1, 'b', 1)])
+    ///     .into_dfa(0);
+    /// let h = Homomorphism::new(
+    ///     CharAlphabet::of_size(2),
+    ///     CharAlphabet::of_size(3),
+    ///     [('a', "ca"), ('b', "")],
+    /// );
+    /// let image = dfa.homomorphic_image(&h);
+    /// assert!(image.accepts("caca"));
+    /// assert!(!image.accepts("ca"));
+    /// assert!(!image.accepts("cab"));
+    /// ```
+    pub fn homomorphic_image<B: Alphabet>(&self, h: &Homomorphism<D::Alphabet, B>) -> NFA<B> {
+        NFA::from_parts(
+            homomorphic_image_nts(self, self.initial(), h, false, |a, b| a || b, |_| Void),
+            0,
+        )
+    }
+
+    /// Builds a [`DFA`] that accepts the inverse homomorphic image of the language accepted by
+    /// `self` under `h`, i.e. the set of all words `w` such that `h(w)` is accepted by `self`.
+    /// This is done by letting each symbol `a` lead to the state that `self` reaches on `h(a)`.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::DFA;
+    /// use automata::core::alphabet::{CharAlphabet, Homomorphism};
+    ///
+    /// // accepts all words with an even number of `a`s
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([true, false])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 0), (1, 'b', 1)])
+    ///     .into_dfa(0);
+    /// let h = Homomorphism::new(
+    ///     CharAlphabet::of_size(2),
+    ///     CharAlphabet::of_size(2),
+    ///     [('a', "ab"), ('b', "aa")],
+    /// );
+    /// let preimage = dfa.inverse_homomorphic_image(&h);
+    /// assert!(preimage.accepts("aab"));
+    /// assert!(!preimage.accepts("ab"));
+    /// ```
+    pub fn inverse_homomorphic_image<B: Alphabet>(
+        &self,
+        h: &Homomorphism<B, D::Alphabet>,
+    ) -> DFA<B> {
+        let ts = explore_deterministic(
+            h.domain(),
+            self.initial(),
+            |q| self.is_accepting(q),
+            |q, a| Some((Void, self.reached_state_index_from(q, h.image(a))?)),
+        );
+        DFA::from_parts(ts, 0)
+    }
+}
+
+impl<D> IntoNFA<D>
+where
+    D: TransitionSystem<StateColor = bool>,
+{
+    /// Builds an [`NFA`] that accepts the homomorphic image of the language accepted by `self`
+    /// under `h`, i.e. the set of all words `h(w)` where `w` is accepted by `self`.
+    pub fn homomorphic_image<B: Alphabet>(&self, h: &Homomorphism<D::Alphabet, B>) -> NFA<B> {
+        NFA::from_parts(
+            homomorphic_image_nts(self, self.initial(), h, false, |a, b| a || b, |_| Void),
+            0,
+        )
+    }
+
+    /// Builds an [`NFA`] that accepts the inverse homomorphic image of the language accepted by
+    /// `self` under `h`, i.e. the set of all words `w` such that `h(w)` is accepted by `self`.
+    /// Each state `q` has a transition on `a` to every state that is reachable from `q` by
+    /// reading `h(a)`. The result can be turned into a [`DFA`] with [`Self::determinize`].
+    pub fn inverse_homomorphic_image<B: Alphabet>(
+        &self,
+        h: &Homomorphism<B, D::Alphabet>,
+    ) -> NFA<B> {
+        let map: math::OrderedMap<_, _> = self
+            .state_indices()
+            .enumerate()
+            .map(|(i, q)| (q, i as DefaultIdType))
+            .collect();
+        let mut nts = NTS::for_alphabet(h.domain().clone());
+        for q in map.keys() {
+            nts.add_state(self.state_color(*q).expect("state must exist"));
+        }
+        for (&q, &source) in &map {
+            for a in h.domain().universe() {
+                for p in self.reached_states_from([q], h.image(a)) {
+                    nts.add_edge((source, h.domain().make_expression(a), map[&p]));
+                }
+            }
+        }
+        NFA::from_parts(nts, map[&self.initial()])
+    }
+}
+
+impl<D> IntoDPA<D>
+where
+    D: Deterministic<EdgeColor = Int>,
+{
+    /// Builds a nondeterministic parity automaton that accepts the homomorphic image of the language
+    /// accepted by `self` under `h`, i.e. the set of all words `h(w)` where `w` is accepted by
+    /// `self`. Every edge in the chain that replaces an original edge keeps its priority.
+    ///
+    /// Panics if `h` maps some symbol to the empty word, as the image of an infinite word
+    /// could then be finite.
+    pub fn homomorphic_image<B: Alphabet>(
+        &self,
+        h: &Homomorphism<D::Alphabet, B>,
+    ) -> NondeterministicOmegaAutomaton<B> {
+        assert!(
+            h.is_non_erasing(),
+            "homomorphic image of omega language is only defined for non-erasing homomorphisms"
+        );
+        let (low, high) = self.low_and_high_priority();
+        let nts: TS<B, Int, AcceptanceMask, false> = homomorphic_image_nts(
+            &self.map_state_colors(|_| 0 as Int),
+            self.initial(),
+            h,
+            0,
+            |a, _| a,
+            |c| c,
+        )
+        .linked_map(|_, q| q, |_, e, c: Int, _| (e, AcceptanceMask::from(c)));
+        NondeterministicOmegaAutomaton::new(nts, 0, OmegaAcceptanceCondition::Parity(low, high))
+    }
+
+    /// Builds a [`DPA`] that accepts the inverse homomorphic image of the language accepted by
+    /// `self` under `h`, i.e. the set of all words `w` such that `h(w)` is accepted by `self`.
+    /// Each symbol `a` leads to the state that `self` reaches on `h(a)`, and the corresponding
+    /// edge is colored with the least priority that is seen on the way.
+    ///
+    /// Panics if `h` maps some symbol to the empty word, as the image of an infinite word
+    /// could then be finite.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::core::{alphabet::{CharAlphabet, Homomorphism}, upw};
+    /// use automata::ts::TSBuilder;
+    ///
+    /// // accepts all words with infinitely many `a`s
+    /// let dpa = TSBuilder::without_state_colors()
+    ///     .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+    ///     .into_dpa(0);
+    /// let h = Homomorphism::new(
+    ///     CharAlphabet::of_size(2),
+    ///     CharAlphabet::of_size(2),
+    ///     [('a', "bb"), ('b', "ba")],
+    /// );
+    /// let preimage = dpa.inverse_homomorphic_image(&h);
+    /// assert!(preimage.accepts(upw!("a", "b")));
+    /// assert!(!preimage.accepts(upw!("b", "a")));
+    /// ```
+    pub fn inverse_homomorphic_image<B: Alphabet>(
+        &self,
+        h: &Homomorphism<B, D::Alphabet>,
+    ) -> DPA<B> {
+        assert!(
+            h.is_non_erasing(),
+            "inverse homomorphic image of omega language is only defined for non-erasing homomorphisms"
+        );
+        let ts = explore_deterministic(
+            h.domain(),
+            self.initial(),
+            |_| Void,
+            |q, a| {
+                h.image(a)
+                    .iter()
+                    .try_fold((Int::MAX, q), |(priority, current), sym| {
+                        let edge = self.edge(current, *sym)?;
+                        Some((priority.min(edge.color()), edge.target()))
+                    })
+            },
+        );
+        DPA::from_parts(ts, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TransitionSystem;
+    use crate::automaton::DFA;
+    use crate::core::alphabet::{CharAlphabet, Homomorphism};
+    use crate::ts::TSBuilder;
+    use automata_core::upw;
+
+    #[test]
+    fn image_colors_follow_reachability_order() {
+        // states are reached in the order 0, 2, 1 and only 1 is accepting
+        let dfa = DFA::builder()
+            .with_state_colors([false, true, false, false])
+            .with_edges([(0, 'a', 2), (2, 'a', 1)])
+            .into_dfa(0);
+        let h = Homomorphism::new(
+            CharAlphabet::of_size(1),
+            CharAlphabet::of_size(1),
+            [('a', "a")],
+        );
+        assert!(dfa.accepts("aa"));
+        let image = dfa.homomorphic_image(&h);
+        assert!(image.accepts("aa"));
+        assert!(!image.accepts("a"));
+        assert!(!image.accepts(""));
+    }
+
+    #[test]
+    fn image_and_preimage_of_dfa() {
+        // words over {a, b} that contain `ab`
+        let dfa = DFA::builder()
+            .with_state_colors([false, false, true])
+            .with_edges([
+                (0, 'a', 1),
+                (0, 'b', 0),
+                (1, 'a', 1),
+                (1, 'b', 2),
+                (2, 'a', 2),
+                (2, 'b', 2),
+            ])
+            .into_dfa(0);
+        let h = Homomorphism::new(
+            CharAlphabet::of_size(3),
+            CharAlphabet::of_size(2),
+            [('a', "a"), ('b', ""), ('c', "bb")],
+        );
+
+        let preimage = dfa.inverse_homomorphic_image(&h);
+        for w in ["ac", "abbc", "cac", "bcacb"] {
+            assert!(preimage.accepts(w), "{w} should be accepted");
+        }
+        for w in ["", "ca", "bbb", "ccaa"] {
+            assert!(!preimage.accepts(w), "{w} should be rejected");
+        }
+
+        let image = preimage.homomorphic_image(&h);
+        assert!(image.accepts("abb"));
+        assert!(image.accepts("bbaabbbb"));
+        assert!(!image.accepts("ab"));
+        assert!(!image.accepts("bba"));
+
+        let determinized = image.determinize();
+        assert!(determinized.accepts("abb"));
+        assert!(!determinized.accepts("bba"));
+        assert!(determinized.is_complete());
+
+        let back = image.inverse_homomorphic_image(&h).determinize();
+        assert!(back.equivalent(&preimage));
+    }
+
+    #[test]
+    fn image_of_dpa() {
+        // infinitely many `a`s
+        let dpa = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        let h = Homomorphism::letter_to_letter(
+            CharAlphabet::of_size(2),
+            CharAlphabet::of_size(2),
+            |c| if c == 'a' { 'b' } else { 'a' },
+        );
+        let preimage = dpa.inverse_homomorphic_image(&h);
+        assert!(preimage.accepts(upw!("b")));
+        assert!(!preimage.accepts(upw!("b", "a")));
+
+        let image = dpa.homomorphic_image(&h);
+        assert_eq!(image.size(), 1);
+        assert!(
+            image
+                .into_deterministic()
+                .into_dpa()
+                .language_equivalent(&preimage)
+        );
+    }
+}
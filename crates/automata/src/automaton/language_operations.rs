@@ -0,0 +1,322 @@
+use crate::automaton::{DFA, IntoDFA};
+use crate::core::math;
+use crate::representation::CollectTs;
+use crate::ts::operations::{Product, ProductIndex, Reversed};
+use crate::ts::predecessors::PredecessorIterable;
+use crate::ts::{Deterministic, IsEdge, StateIndex};
+use crate::{Congruence, Pointed, TransitionSystem};
+
+/// Computes the set of all states in `ts` from which one of the given `targets` can be reached.
+/// This is done by a simple search through the [`Reversed`] transition system.
+fn coreachable<T: PredecessorIterable>(
+    ts: T,
+    targets: impl IntoIterator<Item = T::StateIndex>,
+) -> math::OrderedSet<T::StateIndex> {
+    let reversed = Reversed(ts);
+    let mut seen: math::OrderedSet<_> = targets.into_iter().collect();
+    let mut queue: Vec<_> = seen.iter().copied().collect();
+    while let Some(q) = queue.pop() {
+        for edge in reversed.edges_from(q).expect("state must exist") {
+            if seen.insert(edge.target()) {
+                queue.push(edge.target());
+            }
+        }
+    }
+    seen
+}
+
+/// Restricts the given `dfa` to its reachable part and subsequently minimizes it.
+fn trim_and_minimize<D>(dfa: IntoDFA<D>) -> DFA<D::Alphabet>
+where
+    D: Deterministic<StateColor = bool>,
+{
+    let (ts, initial) = dfa.trim_collect_pointed();
+    DFA::from_parts(ts.erase_edge_colors().collect_dts(), initial).minimize()
+}
+
+impl<D> IntoDFA<D>
+where
+    D: Deterministic<StateColor = bool>,
+{
+    /// Computes the right quotient of the language accepted by `self` with respect to the language
+    /// of `other`. This is the set of all words `u` such that there exists some `v` accepted by
+    /// `other` for which `uv` is accepted by `self`. The result is a minimal [`DFA`].
+    ///
+    /// The transition structure of `self` is kept, only the accepting states change. A state `q`
+    /// becomes accepting if and only if from the pair of `q` and the initial state of `other`,
+    /// a pair of accepting states can be reached in the product of the two automata.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::DFA;
+    ///
+    /// // accepts words ending in `ab`
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([false, false, true])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 1), (1, 'b', 2), (2, 'a', 1), (2, 'b', 0)])
+    ///     .into_dfa(0);
+    /// // accepts only the word `b`
+    /// let b = DFA::builder()
+    ///     .with_state_colors([false, true, false])
+    ///     .with_edges([(0, 'a', 2), (0, 'b', 1), (1, 'a', 2), (1, 'b', 2), (2, 'a', 2), (2, 'b', 2)])
+    ///     .into_dfa(0);
+    /// let quotient = dfa.right_quotient(&b);
+    /// assert!(quotient.accepts("a"));
+    /// assert!(quotient.accepts("bba"));
+    /// assert!(!quotient.accepts("ab"));
+    /// ```
+    pub fn right_quotient<E>(&self, other: E) -> DFA<D::Alphabet>
+    where
+        E: Congruence<Alphabet = D::Alphabet, StateColor = bool>,
+    {
+        let other_initial = other.initial();
+        let product = self.ts_product(other).collect_dts_preserving();
+        let targets = product
+            .state_indices_with_color()
+            .filter_map(|(idx, (_, (l, r)))| if l && r { Some(idx) } else { None })
+            .collect::<Vec<_>>();
+        let good = coreachable(&product, targets);
+
+        let accepting =
+            product
+                .state_indices_with_color()
+                .filter_map(|(idx, (ProductIndex(l, r), _))| {
+                    if r == other_initial && good.contains(&idx) {
+                        Some(l)
+                    } else {
+                        None
+                    }
+                });
+        trim_and_minimize(DFA::from_ts(self, accepting))
+    }
+
+    /// Computes the left quotient of the language accepted by `self` with respect to the language
+    /// of `other`. This is the set of all words `v` such that there exists some `u` accepted by
+    /// `other` for which `uv` is accepted by `self`. The result is a minimal [`DFA`].
+    ///
+    /// We first collect all states of `self` that are reached by a word accepted by `other`, which
+    /// is done by exploring the product of both automata. Starting from the set of these states,
+    /// a [`crate::ts::operations::SubsetConstruction`] is performed, in which a set of states is
+    /// accepting if it contains at least one accepting state.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::DFA;
+    ///
+    /// // accepts words ending in `ab`
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([false, false, true])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 0), (1, 'a', 1), (1, 'b', 2), (2, 'a', 1), (2, 'b', 0)])
+    ///     .into_dfa(0);
+    /// // accepts only the word `a`
+    /// let a = DFA::builder()
+    ///     .with_state_colors([false, true, false])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 2), (1, 'a', 2), (1, 'b', 2), (2, 'a', 2), (2, 'b', 2)])
+    ///     .into_dfa(0);
+    /// let quotient = dfa.left_quotient(&a);
+    /// assert!(quotient.accepts("b"));
+    /// assert!(quotient.accepts("aab"));
+    /// assert!(!quotient.accepts(""));
+    /// ```
+    pub fn left_quotient<E>(&self, other: E) -> DFA<D::Alphabet>
+    where
+        E: Congruence<Alphabet = D::Alphabet, StateColor = bool>,
+    {
+        let start = ProductIndex(self.initial(), other.initial());
+        let product = self.ts_product(&other);
+        let reached = product
+            .reachable_state_indices_from(start)
+            .filter_map(|ProductIndex(l, r)| {
+                if other.state_color(r).expect("state must exist") {
+                    Some(l)
+                } else {
+                    None
+                }
+            })
+            .collect::<math::OrderedSet<_>>();
+        self.subset_from_accepting_any(reached)
+    }
+
+    /// Computes the prefix closure of the language accepted by `self`, i.e. the set of all words `u`
+    /// for which there exists some `v` such that `uv` is accepted. The result is a minimal [`DFA`].
+    ///
+    /// This is done by making every state accepting, from which an accepting state can be reached.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::{automaton::DFA, TransitionSystem};
+    ///
+    /// // accepts only the word `ab`
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([false, false, true, false])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 3), (1, 'a', 3), (1, 'b', 2), (2, 'a', 3), (2, 'b', 3), (3, 'a', 3), (3, 'b', 3)])
+    ///     .into_dfa(0);
+    /// let prefixes = dfa.prefix_closure();
+    /// assert!(prefixes.accepts(""));
+    /// assert!(prefixes.accepts("a"));
+    /// assert!(prefixes.accepts("ab"));
+    /// assert!(!prefixes.accepts("b"));
+    /// assert_eq!(prefixes.size(), 4);
+    /// ```
+    pub fn prefix_closure(&self) -> DFA<D::Alphabet> {
+        let collected = self.collect_dts_preserving();
+        let targets = collected
+            .state_indices_with_color()
+            .filter_map(|(idx, (_, accepting))| if accepting { Some(idx) } else { None })
+            .collect::<Vec<_>>();
+        let good = coreachable(&collected, targets);
+        let accepting = good
+            .into_iter()
+            .map(|idx| collected.state_color(idx).expect("state must exist").0);
+        trim_and_minimize(DFA::from_ts(self, accepting))
+    }
+
+    /// Computes the suffix closure of the language accepted by `self`, i.e. the set of all words `v`
+    /// for which there exists some `u` such that `uv` is accepted. The result is a minimal [`DFA`].
+    ///
+    /// This is the left quotient with respect to the universal language, so we perform a
+    /// [`crate::ts::operations::SubsetConstruction`] starting from the set of all reachable states.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::automaton::DFA;
+    ///
+    /// // accepts only the word `ab`
+    /// let dfa = DFA::builder()
+    ///     .with_state_colors([false, false, true, false])
+    ///     .with_edges([(0, 'a', 1), (0, 'b', 3), (1, 'a', 3), (1, 'b', 2), (2, 'a', 3), (2, 'b', 3), (3, 'a', 3), (3, 'b', 3)])
+    ///     .into_dfa(0);
+    /// let suffixes = dfa.suffix_closure();
+    /// assert!(suffixes.accepts(""));
+    /// assert!(suffixes.accepts("b"));
+    /// assert!(suffixes.accepts("ab"));
+    /// assert!(!suffixes.accepts("a"));
+    /// ```
+    pub fn suffix_closure(&self) -> DFA<D::Alphabet> {
+        let reachable = self
+            .reachable_state_indices()
+            .collect::<math::OrderedSet<_>>();
+        self.subset_from_accepting_any(reachable)
+    }
+
+    /// Computes the infix closure of the language accepted by `self`, i.e. the set of all words `v`
+    /// for which there exist `u` and `w` such that `uvw` is accepted. This is obtained as the
+    /// [suffix closure](`Self::suffix_closure`) of the [prefix closure](`Self::prefix_closure`).
+    /// The result is a minimal [`DFA`].
+    pub fn infix_closure(&self) -> DFA<D::Alphabet> {
+        self.prefix_closure().suffix_closure()
+    }
+
+    /// Performs a subset construction starting in the given set of `states`, where a set is
+    /// accepting if it contains at least one accepting state.
+    fn subset_from_accepting_any(
+        &self,
+        states: math::OrderedSet<StateIndex<D>>,
+    ) -> DFA<D::Alphabet> {
+        let subsets = self
+            .subset_construction_from(states)
+            .map_state_colors(|colors: Vec<bool>| colors.into_iter().any(|b| b));
+        trim_and_minimize(subsets.with_initial(0).collect_dfa())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TransitionSystem;
+    use crate::automaton::DFA;
+
+    fn ends_with_ab() -> DFA {
+        DFA::builder()
+            .with_state_colors([false, false, true])
+            .with_edges([
+                (0, 'a', 1),
+                (0, 'b', 0),
+                (1, 'a', 1),
+                (1, 'b', 2),
+                (2, 'a', 1),
+                (2, 'b', 0),
+            ])
+            .into_dfa(0)
+    }
+
+    fn contains_bb() -> DFA {
+        DFA::builder()
+            .with_state_colors([false, false, true])
+            .with_edges([
+                (0, 'a', 0),
+                (0, 'b', 1),
+                (1, 'a', 0),
+                (1, 'b', 2),
+                (2, 'a', 2),
+                (2, 'b', 2),
+            ])
+            .into_dfa(0)
+    }
+
+    fn all_words(max_len: usize) -> Vec<String> {
+        let mut out = vec![String::new()];
+        let mut last = vec![String::new()];
+        for _ in 0..max_len {
+            last = last
+                .into_iter()
+                .flat_map(|w| ['a', 'b'].map(|c| format!("{w}{c}")))
+                .collect();
+            out.extend(last.iter().cloned());
+        }
+        out
+    }
+
+    #[test]
+    fn quotients_agree_with_definition() {
+        let l = ends_with_ab();
+        let k = contains_bb();
+        let words = all_words(6);
+
+        let right = l.right_quotient(&k);
+        let left = l.left_quotient(&k);
+        for u in all_words(4) {
+            let expected_right = words
+                .iter()
+                .filter(|v| k.accepts(v.as_str()))
+                .any(|v| l.accepts(format!("{u}{v}").as_str()));
+            assert_eq!(right.accepts(u.as_str()), expected_right, "right on {u}");
+
+            let expected_left = words
+                .iter()
+                .filter(|v| k.accepts(v.as_str()))
+                .any(|v| l.accepts(format!("{v}{u}").as_str()));
+            assert_eq!(left.accepts(u.as_str()), expected_left, "left on {u}");
+        }
+    }
+
+    #[test]
+    fn closures() {
+        let l = ends_with_ab();
+        let prefixes = l.prefix_closure();
+        let suffixes = l.suffix_closure();
+        let infixes = l.infix_closure();
+
+        assert_eq!(prefixes.size(), 1);
+        assert!(prefixes.accepts("bbbaaa"));
+
+        assert!(suffixes.accepts("b"));
+        assert!(suffixes.accepts(""));
+        assert!(!suffixes.accepts("a"));
+        assert!(!suffixes.accepts("ba"));
+        assert!(suffixes.equivalent(l.suffix_closure().suffix_closure()));
+
+        assert_eq!(infixes.size(), 1);
+        assert!(infixes.accepts("abba"));
+
+        let k = contains_bb();
+        assert!(
+            k.prefix_closure().equivalent(
+                DFA::builder()
+                    .with_state_colors([true])
+                    .with_edges([(0, 'a', 0), (0, 'b', 0)])
+                    .into_dfa(0)
+            )
+        );
+        assert!(k.suffix_closure().equivalent(&k));
+    }
+}
@@ -56,6 +56,23 @@ where
         self.witness_inequivalence(other).is_none()
     }
 
+    /// Computes a shortest homing word, i.e. a word such that the state which is reached
+    /// after reading it can be determined from the produced outputs, regardless of the state in
+    /// which the machine starts. Returns `None` if no such word exists. As this performs a
+    /// breadth-first search over sets of blocks of states, it should only be used for small
+    /// machines, otherwise [`Self::homing_word`] is preferable. Panics if `self` is not complete.
+    pub fn shortest_homing_word(&self) -> Option<Vec<SymbolOf<Self>>> {
+        crate::ts::synchronizing::shortest_homing_word(self)
+    }
+
+    /// Computes a homing word (see [`Self::shortest_homing_word`]) in polynomial time. This is done
+    /// by repeatedly appending a shortest word that either separates two states which are not yet
+    /// distinguished or leads both of them to the same state. Returns `None` if no homing word
+    /// exists. Panics if `self` is not complete.
+    pub fn homing_word(&self) -> Option<Vec<SymbolOf<Self>>> {
+        crate::ts::synchronizing::greedy_homing_word(self)
+    }
+
     /// Returns a vector over all colors that can be emitted.
     pub fn color_range(&self) -> impl Iterator<Item = C::EdgeColor> + '_
     where
@@ -49,6 +49,12 @@ impl AcceptanceMask {
     }
 }
 
+impl From<Int> for AcceptanceMask {
+    fn from(value: Int) -> Self {
+        Self(BitSet::from_iter([value as usize]))
+    }
+}
+
 impl Show for AcceptanceMask {
     fn show(&self) -> String {
         self.iter().map(|i| format!("{{{i}}}")).join(", ")
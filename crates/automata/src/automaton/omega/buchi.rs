@@ -37,6 +37,35 @@ pub type DBA<A = CharAlphabet, Q = Void, D = DTS<A, Q, bool>> =
 /// Helper trait for creating a [`DBA`] from a given transition system.
 pub type IntoDBA<T> = DBA<<T as TransitionSystem>::Alphabet, StateColor<T>, T>;
 
+/// Defines the [`Semantics`] of a deterministic co-Büchi automaton (DCBA), which is
+/// dual to the [`BuchiCondition`]. It considers the set of transitions which is taken
+/// infinitely often and accepts if and only if none of them is colored with `true`. In
+/// other words, transitions labeled with `true` are rejecting and may only be taken
+/// finitely often in an accepting run.
+///
+/// For the automaton that makes use of it, see [`DCBA`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Copy)]
+pub struct CoBuchiCondition;
+
+impl<T: Deterministic<EdgeColor = bool>> Semantics<T, true> for CoBuchiCondition {
+    type Observer = run::GreatestEdgeColor<T>;
+    type Output = bool;
+    fn evaluate(&self, observed: <Self::Observer as run::Observer<T>>::Current) -> Self::Output {
+        !observed
+    }
+}
+
+/// A deterministic co-Büchi automaton (DCBA) is a deterministic automaton with co-Büchi
+/// acceptance condition. It accepts a word if its infinite run takes the rejecting
+/// transitions (i.e. those that are labeled with `true`) only finitely often.
+///
+/// It corresponds to a deterministic parity automaton [`super::DPA`] with min even
+/// and priorities 1 (for rejecting transitions) and 2.
+pub type DCBA<A = CharAlphabet, Q = Void, D = DTS<A, Q, bool>> =
+    InfiniteWordAutomaton<A, CoBuchiCondition, Q, bool, true, D>;
+/// Helper trait for creating a [`DCBA`] from a given transition system.
+pub type IntoDCBA<T> = DCBA<<T as TransitionSystem>::Alphabet, StateColor<T>, T>;
+
 impl<C> IntoDBA<C>
 where
     C: Deterministic<EdgeColor = bool>,
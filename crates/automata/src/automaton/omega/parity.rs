@@ -318,27 +318,36 @@ where
         l: Int,
     ) -> Option<ReducedOmegaWord<SymbolOf<Self>>> {
         trace!("attempting to witness colors {k} and {l}");
+        // the cycle has to stay within the restricted product, but it may be reached through
+        // any prefix, so we consider the states that are reachable in the full product
+        let full = self.ts_product(other);
+        let representatives = full.minimal_representatives_iter().collect_vec();
+
         let t1 = self.edge_color_restricted(k, Int::MAX);
         let t2 = other.edge_color_restricted(l, Int::MAX);
         let prod = t1.ts_product(t2);
-        let sccs = prod.sccs();
+        let sccs = prod.reachable_sccs_from(representatives.iter().map(|rep| rep.state_index()));
         for (_, scc) in sccs.iter() {
             if scc.is_transient() {
                 continue;
             }
-            let (a, b) = scc
-                .interior_edge_colors()
-                .iter()
-                .min()
-                .expect("we know this is not transient");
-            if *a == k && *b == l {
-                let Some(rep) = scc.minimal_representative() else {
+            let colors = scc.interior_edge_colors();
+            let a = colors.iter().map(|(a, _)| *a).min();
+            let b = colors.iter().map(|(_, b)| *b).min();
+            if a == Some(k) && b == Some(l) {
+                let Some(rep) = representatives
+                    .iter()
+                    .find(|rep| scc.contains(&rep.state_index()))
+                else {
                     continue;
                 };
                 let cycle = scc
                     .maximal_loop_from(rep.state_index())
                     .expect("This thing is non-transient");
-                return Some(ReducedOmegaWord::ultimately_periodic(rep.into_vec(), cycle));
+                return Some(ReducedOmegaWord::ultimately_periodic(
+                    rep.clone().into_inner(),
+                    cycle,
+                ));
             }
         }
         None
@@ -745,6 +754,48 @@ mod tests {
         assert_eq!(cong.size(), 1);
     }
 
+    #[test]
+    fn witness_inequivalence_behind_low_priority() {
+        // from 0, reading b^ω leads into the rejecting loop on 1 only through edges with
+        // priority 0, whereas from 3 it is accepted
+        let dpa = TSBuilder::without_state_colors()
+            .with_transitions([
+                (0, 'a', 1, 1),
+                (0, 'b', 0, 2),
+                (1, 'a', 0, 3),
+                (1, 'b', 1, 1),
+                (2, 'a', 3, 2),
+                (2, 'b', 0, 1),
+                (3, 'a', 3, 2),
+                (3, 'b', 0, 3),
+            ])
+            .into_dpa(0);
+        let witness = dpa.separate(0, 3).expect("states are not equivalent");
+        assert_ne!(
+            (&dpa).with_initial(0).collect_dpa().accepts(&witness),
+            (&dpa).with_initial(3).collect_dpa().accepts(&witness)
+        );
+        assert_eq!(dpa.prefix_congruence().size(), 4);
+    }
+
+    #[test]
+    fn witness_colors_of_mixed_cycle() {
+        // only a cycle on both a and b sees the least priority 0 in both automata, although
+        // neither of its edges has priority 0 in both
+        let left = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 0, 0), (0, 'b', 1, 0)])
+            .into_dpa(0);
+        let right = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 1, 0), (0, 'b', 0, 0)])
+            .into_dpa(0);
+        let witness = left
+            .witness_colors(0, &right, 0)
+            .expect("a cycle on ab sees priority 0 in both");
+        assert!(left.accepts(&witness));
+        assert!(right.accepts(&witness));
+        assert!(left.witness_colors(1, &right, 1).is_none());
+    }
+
     #[test]
     fn bug_normalized() {
         let dpa = TSBuilder::without_state_colors()
@@ -0,0 +1,243 @@
+use crate::automaton::{DFA, IntoDFA, IntoNFA, NFA, OmegaAutomaton};
+use crate::core::{
+    Color, Void,
+    alphabet::{PropAlphabet, PropExpression},
+    math,
+};
+use crate::ts::{DefaultIdType, Deterministic, ForAlphabet, IsEdge, Sproutable};
+use crate::{Pointed, TS, TransitionSystem};
+use std::fmt::Display;
+
+/// Resolves the given names of atomic propositions to their indices in `alphabet`, panicking
+/// if one of them does not exist.
+fn resolve_aps<I>(alphabet: &PropAlphabet, aps: I) -> Vec<usize>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    aps.into_iter()
+        .map(|ap| {
+            alphabet
+                .ap_index(ap.as_ref())
+                .unwrap_or_else(|| panic!("unknown atomic proposition {}", ap.as_ref()))
+        })
+        .collect()
+}
+
+/// Copies `ts` into a new [`TS`] over `alphabet`, where every edge expression is replaced by
+/// the result of applying `f` to it. Edges that share source, color and target are merged
+/// by taking the disjunction of their (relabeled) expressions. Returns the new transition system
+/// together with the index of `initial` in it.
+fn relabel<T, Q, C, const DET: bool>(
+    ts: &T,
+    initial: T::StateIndex,
+    alphabet: PropAlphabet,
+    f: impl Fn(&PropExpression) -> PropExpression,
+) -> (TS<PropAlphabet, Q, C, DET>, DefaultIdType)
+where
+    T: TransitionSystem<Alphabet = PropAlphabet, StateColor = Q, EdgeColor = C>,
+    Q: Color,
+    C: Color,
+{
+    let mut out = TS::for_alphabet(alphabet);
+    let map: math::Map<_, _> = ts
+        .state_indices()
+        .map(|q| {
+            (
+                q,
+                out.add_state(ts.state_color(q).expect("state must exist")),
+            )
+        })
+        .collect();
+
+    let mut edges: math::Map<(DefaultIdType, C, DefaultIdType), PropExpression> =
+        math::Map::default();
+    for (q, source) in &map {
+        for edge in ts.edges_from(*q).expect("state must exist") {
+            let expression = f(edge.expression());
+            edges
+                .entry((*source, edge.color(), map[&edge.target()]))
+                .and_modify(|e| *e |= expression.clone())
+                .or_insert(expression);
+        }
+    }
+    for ((p, c, q), expression) in edges {
+        out.add_edge((p, expression, c, q));
+    }
+    (out, map[&initial])
+}
+
+impl<const DET: bool> OmegaAutomaton<PropAlphabet, DET> {
+    /// Hides the atomic propositions with the given names by existentially quantifying them
+    /// out of every edge label. The resulting automaton is over the alphabet that consists of
+    /// the remaining atomic propositions and it is in general no longer deterministic.
+    ///
+    /// Panics if one of the names is unknown or if all atomic propositions would be removed.
+    pub fn project_aps<I>(&self, aps: I) -> OmegaAutomaton<PropAlphabet, false>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let aps = resolve_aps(self.alphabet(), aps);
+        let (ts, initial) = relabel(
+            self,
+            self.initial(),
+            self.alphabet().project_aps(&aps),
+            |e| e.project(&aps),
+        );
+        OmegaAutomaton::new(ts, initial, *self.acceptance())
+    }
+
+    /// Introduces fresh atomic propositions with the given names, which are not constrained
+    /// by any edge label. As the fresh atomic propositions are ignored, the automaton stays
+    /// deterministic if it was before.
+    ///
+    /// Panics if one of the names is already in use.
+    pub fn extend_aps<I>(&self, aps: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let alphabet = self.alphabet().extend_aps(aps);
+        let count = alphabet.aps() - self.alphabet().aps();
+        let (ts, initial) = relabel(self, self.initial(), alphabet, |e| e.extend(count));
+        OmegaAutomaton::new(ts, initial, *self.acceptance())
+    }
+}
+
+impl<D> IntoDFA<D>
+where
+    D: Deterministic<Alphabet = PropAlphabet, StateColor = bool>,
+{
+    /// Hides the atomic propositions with the given names by existentially quantifying them
+    /// out of every edge label, which yields an [`NFA`] over the remaining atomic propositions.
+    ///
+    /// Panics if one of the names is unknown or if all atomic propositions would be removed.
+    pub fn project_aps<I>(&self, aps: I) -> NFA<PropAlphabet>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let aps = resolve_aps(self.alphabet(), aps);
+        let (ts, initial) = relabel(
+            self,
+            self.initial(),
+            self.alphabet().project_aps(&aps),
+            |e| e.project(&aps),
+        );
+        NFA::from_parts(ts.linked_map_edges(|_, e, _, _| (e, Void)), initial)
+    }
+
+    /// Introduces fresh atomic propositions with the given names, which are not constrained
+    /// by any edge label.
+    ///
+    /// Panics if one of the names is already in use.
+    pub fn extend_aps<I>(&self, aps: I) -> DFA<PropAlphabet>
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let alphabet = self.alphabet().extend_aps(aps);
+        let count = alphabet.aps() - self.alphabet().aps();
+        let (ts, initial) = relabel(self, self.initial(), alphabet, |e| e.extend(count));
+        DFA::from_parts(ts.linked_map_edges(|_, e, _, _| (e, Void)), initial)
+    }
+}
+
+impl<D> IntoNFA<D>
+where
+    D: TransitionSystem<Alphabet = PropAlphabet, StateColor = bool>,
+{
+    /// Hides the atomic propositions with the given names by existentially quantifying them
+    /// out of every edge label.
+    ///
+    /// Panics if one of the names is unknown or if all atomic propositions would be removed.
+    pub fn project_aps<I>(&self, aps: I) -> NFA<PropAlphabet>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let aps = resolve_aps(self.alphabet(), aps);
+        let (ts, initial) = relabel(
+            self,
+            self.initial(),
+            self.alphabet().project_aps(&aps),
+            |e| e.project(&aps),
+        );
+        NFA::from_parts(ts.linked_map_edges(|_, e, _, _| (e, Void)), initial)
+    }
+
+    /// Introduces fresh atomic propositions with the given names, which are not constrained
+    /// by any edge label.
+    ///
+    /// Panics if one of the names is already in use.
+    pub fn extend_aps<I>(&self, aps: I) -> NFA<PropAlphabet>
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        let alphabet = self.alphabet().extend_aps(aps);
+        let count = alphabet.aps() - self.alphabet().aps();
+        let (ts, initial) = relabel(self, self.initial(), alphabet, |e| e.extend(count));
+        NFA::from_parts(ts.linked_map_edges(|_, e, _, _| (e, Void)), initial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::{DFA, OmegaAutomaton};
+    use crate::core::alphabet::{PropAlphabet, PropExpression, PropSymbol};
+    use crate::hoa::input::hoa_to_ts;
+    use crate::ts::{ForAlphabet, Sproutable};
+    use crate::{DTS, TransitionSystem};
+
+    #[test]
+    fn project_and_extend_dfa() {
+        // accepts all words that at some point satisfy `p & q`
+        let mut ts = DTS::for_alphabet(PropAlphabet::from_apnames(["p", "q"]));
+        let (q0, q1) = (ts.add_state(false), ts.add_state(true));
+        ts.add_edge((q0, PropExpression::new(2, "x_0 & x_1"), q1));
+        ts.add_edge((q0, PropExpression::new(2, "!(x_0 & x_1)"), q0));
+        ts.add_edge((q1, PropExpression::universal(2), q1));
+        let dfa = DFA::from_parts(ts, q0);
+
+        let nfa = dfa.project_aps(["q"]);
+        assert_eq!(nfa.alphabet().apnames(), ["p".to_string()]);
+        assert!(nfa.accepts(vec![PropSymbol::<u32>::from_bools(vec![true])]));
+        assert!(!nfa.accepts(vec![PropSymbol::<u32>::from_bools(vec![false]); 3]));
+
+        let extended = dfa.extend_aps(["r"]);
+        assert_eq!(extended.alphabet().apnames(), ["p", "q", "r"]);
+        assert!(extended.accepts(vec![PropSymbol::<u32>::from_bools(vec![true, true, true])]));
+        assert!(!extended.accepts(vec![PropSymbol::<u32>::from_bools(vec![true, false, true])]));
+        assert_eq!(extended.project_aps(["r"]).size(), 2);
+    }
+
+    #[test]
+    fn project_omega_automaton() {
+        let hoa = r#"HOA: v1
+        States: 2
+        Start: 0
+        AP: 2 "p" "q"
+        acc-name: parity min even 2
+        Acceptance: 2 Inf(0) | Fin(1)
+        --BODY--
+        State: 0
+        [0&1] 1 {0}
+        [!0 | !1] 0 {1}
+        State: 1
+        [t] 1 {0}
+        --END--
+        "#;
+        let aut: OmegaAutomaton<PropAlphabet> = hoa_to_ts::<true>(hoa).pop().unwrap();
+
+        let projected = aut.project_aps(["p"]);
+        assert_eq!(projected.alphabet().apnames(), ["q".to_string()]);
+        assert_eq!(projected.size(), 2);
+        assert_eq!(projected.edges_from(0).unwrap().count(), 2);
+
+        let extended = aut.extend_aps(["r", "s"]);
+        assert_eq!(extended.alphabet().aps(), 4);
+        assert!(extended.into_dpa().is_complete());
+    }
+}
@@ -2,9 +2,10 @@ use super::{FiniteWordAutomaton, Semantics, StatesWithColor};
 use crate::representation::CollectTs;
 use crate::ts::operations::{DefaultIfMissing, Product, ProductIndex};
 use crate::ts::run::ReachedStateColor;
-use crate::ts::{Deterministic, EdgeColor, StateIndex, SymbolOf, operations};
-use crate::{Congruence, DTS, Pointed, TransitionSystem};
-use automata_core::alphabet::CharAlphabet;
+use crate::ts::{Deterministic, EdgeColor, IsEdge, StateIndex, SymbolOf, operations};
+use crate::{Congruence, DTS, NTS, Pointed, TransitionSystem};
+use automata_core::alphabet::{CharAlphabet, Expression};
+use automata_core::word::FiniteWord;
 use automata_core::{Void, math};
 
 /// Defines the [`Semantics`] that are used by a deterministic finite automaton
@@ -185,6 +186,73 @@ where
     }
 }
 
+/// A nondeterministic finite automaton (NFA) uses the same acceptance condition as a [`DFA`], but
+/// its underlying transition system may have multiple transitions on the same symbol leaving a
+/// state. A finite word is accepted if some run on it reaches an accepting state.
+pub type NFA<A = CharAlphabet, C = Void, D = NTS<A, bool, C>> =
+    FiniteWordAutomaton<A, ReachabilityCondition, bool, C, false, D>;
+
+/// Helper type for creating an [`NFA`] from a given transition system.
+pub type IntoNFA<T> = NFA<<T as TransitionSystem>::Alphabet, EdgeColor<T>, T>;
+
+impl<D> IntoNFA<D>
+where
+    D: TransitionSystem<StateColor = bool>,
+{
+    /// Returns the set of states that can be reached by reading the given `word` from any of
+    /// the given `states`.
+    pub fn reached_states_from<W, I>(&self, states: I, word: W) -> math::OrderedSet<StateIndex<D>>
+    where
+        W: FiniteWord<Symbol = SymbolOf<D>>,
+        I: IntoIterator<Item = StateIndex<D>>,
+    {
+        word.symbols()
+            .fold(states.into_iter().collect(), |current, sym| {
+                current
+                    .into_iter()
+                    .flat_map(|q| {
+                        self.ts()
+                            .edges_from(q)
+                            .expect("state must exist")
+                            .filter(move |e| e.expression().matched_by(sym))
+                            .map(|e| e.target())
+                    })
+                    .collect()
+            })
+    }
+
+    /// Returns whether the automaton accepts the given finite word, meaning there exists a run
+    /// on it that reaches an accepting state.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::{automaton::NFA, ts::TSBuilder};
+    ///
+    /// let nts = TSBuilder::<bool, _, false>::without_edge_colors()
+    ///     .with_state_colors([false, true])
+    ///     .with_edges([(0, 'a', 0), (0, 'b', 0), (0, 'a', 1)])
+    ///     .into_nts();
+    /// let nfa: NFA = NFA::from_parts(nts, 0);
+    /// assert!(nfa.accepts("ba"));
+    /// assert!(!nfa.accepts("ab"));
+    /// ```
+    pub fn accepts<W: FiniteWord<Symbol = SymbolOf<D>>>(&self, word: W) -> bool {
+        self.reached_states_from([self.initial()], word)
+            .into_iter()
+            .any(|q| self.state_color(q).expect("state must exist"))
+    }
+
+    /// Builds a [`DFA`] accepting the same language as `self` by means of a
+    /// [`operations::SubsetConstruction`]. Only the reachable sets of states are kept.
+    pub fn determinize(&self) -> DFA<D::Alphabet> {
+        self.ts()
+            .subset_construction_from([self.initial()])
+            .map_state_colors(|colors: Vec<bool>| colors.into_iter().any(|b| b))
+            .with_initial(0)
+            .collect_dfa()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::automaton::DFA;
@@ -86,11 +86,13 @@ impl<T: TransitionSystem> PartialOrd for MinimalRepresentative<T> {
     }
 }
 impl<T: TransitionSystem> Ord for MinimalRepresentative<T> {
+    /// Representatives are ordered length-lexicographically by their words.
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.0.cmp(&other.0) {
-            std::cmp::Ordering::Equal => self.1.cmp(&other.1),
-            ord => ord,
-        }
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.cmp(&other.0))
+            .then_with(|| self.1.cmp(&other.1))
     }
 }
 impl<T: TransitionSystem> Eq for MinimalRepresentative<T> {}
@@ -35,6 +35,10 @@ pub struct Family<T: Congruence, X> {
 }
 
 impl<T: Congruence, X> Family<T, X> {
+    /// Returns a reference to the leading congruence.
+    pub fn leading(&self) -> &T {
+        &self.leading
+    }
     pub fn get<W>(&self, word: W) -> Option<&X>
     where
         W: FiniteWord<Symbol = SymbolOf<T>>,
@@ -1,6 +1,6 @@
 #![allow(unused)]
 use crate::automaton::{DBA, DFA, DPA, MealyMachine, MooreMachine};
-use crate::representation::IntoTs;
+use crate::representation::{CollectTs, IntoTs};
 use crate::ts::{DefaultIdType, Deterministic, ForAlphabet, Shrinkable, Sproutable, StateIndex};
 use crate::{DTS, TransitionSystem};
 use automata_core::alphabet::{Alphabet, CharAlphabet};
@@ -70,9 +70,23 @@ pub fn generate_random_dfa(symbols: usize, probability: f64) -> DFA {
 /// Calls [`generate_random_ts_sized`] and uses the returned transition system to build a
 /// [`MealyMachine`] with colors `0..=max_color`.
 pub fn generate_random_mealy(symbols: usize, max_color: usize, size: usize) -> MealyMachine {
-    let (ts, initial) = generate_random_ts_sized(symbols, size);
+    generate_random_mealy_with_rng(&mut thread_rng(), symbols, max_color, size)
+}
+
+/// Works as [`generate_random_mealy`], but draws all random choices from the given `rng`, which
+/// makes the result reproducible when a seeded generator is used.
+pub fn generate_random_mealy_with_rng<R: Rng>(
+    rng: &mut R,
+    symbols: usize,
+    max_color: usize,
+    size: usize,
+) -> MealyMachine {
+    let (ts, initial) = generate_random_ts_sized_with_rng(rng, symbols, size);
+    // the colors are collected right away, so each one is drawn exactly once
+    let rng = std::cell::RefCell::new(rng);
     let mut mm = ts
-        .map_edge_colors(|_| thread_rng().gen_range(0..=max_color) as Int)
+        .map_edge_colors(|_| rng.borrow_mut().gen_range(0..=max_color) as Int)
+        .collect_dts()
         .with_initial(initial)
         .into_mealy()
         .minimize()
@@ -84,9 +98,22 @@ pub fn generate_random_mealy(symbols: usize, max_color: usize, size: usize) -> M
 /// Calls [`generate_random_ts_sized`] and uses the returned transition system to build a
 /// [`MooreMachine`] with colors `0..=max_color`.
 pub fn generate_random_moore(symbols: usize, max_color: usize, size: usize) -> MooreMachine {
-    let (ts, initial) = generate_random_ts_sized(symbols, size);
+    generate_random_moore_with_rng(&mut thread_rng(), symbols, max_color, size)
+}
+
+/// Works as [`generate_random_moore`], but draws all random choices from the given `rng`.
+pub fn generate_random_moore_with_rng<R: Rng>(
+    rng: &mut R,
+    symbols: usize,
+    max_color: usize,
+    size: usize,
+) -> MooreMachine {
+    let (ts, initial) = generate_random_ts_sized_with_rng(rng, symbols, size);
+    // the colors are collected right away, so each one is drawn exactly once
+    let rng = std::cell::RefCell::new(rng);
     let mut mm = ts
-        .map_state_colors(|_| thread_rng().gen_range(0..=max_color) as Int)
+        .map_state_colors(|_| rng.borrow_mut().gen_range(0..=max_color) as Int)
+        .collect_dts()
         .with_initial(initial)
         .into_moore()
         .minimize()
@@ -103,6 +130,15 @@ pub fn generate_random_moore(symbols: usize, max_color: usize, size: usize) -> M
 ///
 /// Note that depending on which state is chosen as the initial state, there may be unreachable states.
 pub fn generate_random_ts_sized(symbols: usize, size: usize) -> (DTS, StateIndex<DTS>) {
+    generate_random_ts_sized_with_rng(&mut thread_rng(), symbols, size)
+}
+
+/// Works as [`generate_random_ts_sized`], but draws the transitions from the given `rng`.
+pub fn generate_random_ts_sized_with_rng<R: Rng>(
+    rng: &mut R,
+    symbols: usize,
+    size: usize,
+) -> (DTS, StateIndex<DTS>) {
     assert!(size > 0);
     let alphabet = CharAlphabet::of_size(symbols);
     let mut dts = DTS::for_alphabet(alphabet.clone());
@@ -111,7 +147,6 @@ pub fn generate_random_ts_sized(symbols: usize, size: usize) -> (DTS, StateIndex
         dts.add_state(Void);
     }
     // add edges
-    let mut rng = thread_rng();
     for q in dts.state_indices_vec() {
         for sym in alphabet.universe() {
             let target = rng.gen_range(0..(dts.size() as DefaultIdType));
@@ -353,14 +388,17 @@ pub(crate) fn print_random_ts_benchmark(
 #[cfg(test)]
 mod tests {
     use super::{
-        generate_random_dba, generate_random_dfa, generate_random_dpa, generate_random_omega_words,
-        generate_random_ts_sized, generate_random_words, print_random_ts_benchmark,
+        generate_random_dba, generate_random_dfa, generate_random_dpa,
+        generate_random_mealy_with_rng, generate_random_omega_words, generate_random_ts_sized,
+        generate_random_words, print_random_ts_benchmark,
     };
     use crate::{
         TransitionSystem,
         dot::Dottable,
         random::{CharAlphabet, draw_priority},
     };
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
     use std::collections::HashMap;
 
     #[test]
@@ -376,6 +414,14 @@ mod tests {
         assert_eq!(dts.size(), 4);
     }
 
+    #[test]
+    fn random_mealy_seeded() {
+        let first = generate_random_mealy_with_rng(&mut StdRng::seed_from_u64(7), 3, 4, 12);
+        let second = generate_random_mealy_with_rng(&mut StdRng::seed_from_u64(7), 3, 4, 12);
+        assert_eq!(first.size(), second.size());
+        assert!(first.bisimilar(&second));
+    }
+
     #[test]
     fn random_dba_sized() {
         let dba = generate_random_dba(2, 10, 0.99);
@@ -55,6 +55,8 @@ pub mod run;
 /// This module defines traits for dealing with predecessors in a transition system.
 pub mod predecessors;
 
+pub(crate) mod synchronizing;
+
 mod word_as_ts;
 pub use crate::ts::predecessors::PredecessorIterable;
 pub use word_as_ts::WordTs;
@@ -549,6 +549,47 @@ pub trait Deterministic: TransitionSystem {
         self.size() == self.minimal_representatives_iter().count()
     }
 
+    /// Computes a shortest synchronizing word, i.e. a word that leads every state of `self` to the
+    /// same state. Returns `None` if no such word exists. This performs a breadth-first search in the
+    /// subset construction starting from the set of all states, so it may take time that is
+    /// exponential in the size of `self`. For larger instances, [`Self::synchronizing_word`]
+    /// should be preferred. Panics if `self` is not complete.
+    ///
+    /// # Example
+    /// ```
+    /// use automata::ts::{Deterministic, TSBuilder};
+    ///
+    /// let ts = TSBuilder::without_colors()
+    ///     .with_edges([(0, 'a', 1), (1, 'a', 1), (0, 'b', 0), (1, 'b', 0)])
+    ///     .into_dts();
+    /// assert_eq!(ts.shortest_synchronizing_word(), Some(vec!['a']));
+    /// ```
+    fn shortest_synchronizing_word(&self) -> Option<Vec<SymbolOf<Self>>>
+    where
+        Self: Sized,
+    {
+        super::synchronizing::shortest_synchronizing_word(self)
+    }
+
+    /// Computes a synchronizing word, i.e. a word that leads every state of `self` to the same state,
+    /// using the greedy heuristic of Eppstein. The returned word need not be a shortest one, but it is
+    /// computed in polynomial time. Returns `None` if no synchronizing word exists. Panics if `self`
+    /// is not complete.
+    fn synchronizing_word(&self) -> Option<Vec<SymbolOf<Self>>>
+    where
+        Self: Sized,
+    {
+        super::synchronizing::greedy_synchronizing_word(self)
+    }
+
+    /// Returns true if and only if `self` has a synchronizing word, see [`Self::synchronizing_word`].
+    fn is_synchronizing(&self) -> bool
+    where
+        Self: Sized,
+    {
+        self.synchronizing_word().is_some()
+    }
+
     /// Attempts to extract the escape prefix of running the given omega word.
     #[inline(always)]
     fn omega_escape_prefix<W>(&self, word: W) -> Option<run::EscapePrefix<W>>
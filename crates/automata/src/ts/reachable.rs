@@ -20,7 +20,7 @@ where
     Ts: TransitionSystem,
 {
     pub fn new(ts: &'a Ts, origin: Ts::StateIndex) -> Self {
-        let seen = OrderedSet::from_iter([origin]);
+        let seen = OrderedSet::default();
         let queue = [(MinimalRepresentative::new(vec![], origin), origin)]
             .into_iter()
             .collect();
@@ -35,11 +35,15 @@ where
     type Item = MinimalRepresentative<Ts>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((access, q)) = self.queue.pop_first() {
+        // a state may be queued with several words, only the least of them is its representative
+        while let Some((access, q)) = self.queue.pop_first() {
+            if !self.seen.insert(q) {
+                continue;
+            }
             if let Some(it) = self.ts.edges_from(q) {
                 for edge in it {
                     let p = edge.target();
-                    if self.seen.insert(p) {
+                    if !self.seen.contains(&p) {
                         for sym in edge.expression().symbols() {
                             let mut new_access = access.clone();
                             new_access.push(sym);
@@ -184,4 +188,47 @@ mod tests {
 
         assert_eq!(dfa.reachable_state_indices_from(2).collect_vec(), vec![2]);
     }
+
+    #[test]
+    fn minimal_representatives_are_length_lexicographic() {
+        // state 4 is reached by aaa and by the shorter ba, which is lexicographically greater
+        let dfa = DFA::builder()
+            .with_state_colors([false, false, false, false, true])
+            .with_edges([
+                (0, 'a', 1),
+                (0, 'b', 2),
+                (1, 'a', 3),
+                (2, 'a', 4),
+                (3, 'a', 4),
+            ])
+            .into_dfa(0);
+
+        assert_eq!(
+            dfa.minimal_representatives_iter_from(0).collect::<Vec<_>>(),
+            vec![
+                MinimalRepresentative::new("".collect_vec(), 0u32),
+                MinimalRepresentative::new("a".collect_vec(), 1),
+                MinimalRepresentative::new("b".collect_vec(), 2),
+                MinimalRepresentative::new("aa".collect_vec(), 3),
+                MinimalRepresentative::new("ba".collect_vec(), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn minimal_representatives_use_least_symbol() {
+        // both edges lead to 1, the one on b is inserted first
+        let dfa = DFA::builder()
+            .with_state_colors([false, true])
+            .with_edges([(0, 'b', 1), (0, 'a', 1), (1, 'a', 1), (1, 'b', 1)])
+            .into_dfa(0);
+
+        assert_eq!(
+            dfa.minimal_representatives_iter_from(0).collect::<Vec<_>>(),
+            vec![
+                MinimalRepresentative::new("".collect_vec(), 0u32),
+                MinimalRepresentative::new("a".collect_vec(), 1),
+            ]
+        );
+    }
 }
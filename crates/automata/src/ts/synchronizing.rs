@@ -0,0 +1,327 @@
+//! Computation of synchronizing and homing words for complete deterministic transition systems.
+//!
+//! A synchronizing word leads every state to one and the same state. A homing word is weaker,
+//! it only guarantees that the state which is reached can be determined from the sequence of edge
+//! colors (i.e. the outputs) that is produced while reading it.
+//!
+//! For both we provide an exact breadth-first search, which yields a shortest such word but
+//! may take time exponential in the number of states, and a greedy heuristic that runs in
+//! polynomial time. The heuristic for synchronizing words is the one introduced by Eppstein in
+//! "Reset Sequences for Monotonic Automata". Both heuristics find a word if and only if one exists.
+
+use std::collections::VecDeque;
+
+use crate::TransitionSystem;
+use crate::core::{alphabet::Alphabet, math};
+use crate::ts::{Deterministic, EdgeColor, IsEdge, StateIndex, SymbolOf};
+
+/// A pair of states `(p, q)` with `p < q`.
+type Pair = (usize, usize);
+
+/// Explicit representation of a complete deterministic transition system, where states and symbols
+/// are identified by their position.
+struct Table<D: Deterministic> {
+    symbols: Vec<SymbolOf<D>>,
+    /// `successors[q][a]` is the target and color of the edge leaving state `q` on symbol `a`.
+    successors: Vec<Vec<(usize, EdgeColor<D>)>>,
+}
+
+impl<D: Deterministic> Table<D> {
+    fn new(ts: &D) -> Self {
+        assert!(
+            ts.is_complete(),
+            "synchronizing and homing words only make sense for complete transition systems"
+        );
+        let states: math::Map<StateIndex<D>, usize> = ts
+            .state_indices()
+            .enumerate()
+            .map(|(i, q)| (q, i))
+            .collect();
+        let symbols: Vec<_> = ts.alphabet().universe().collect();
+        let successors = states
+            .keys()
+            .map(|q| {
+                symbols
+                    .iter()
+                    .map(|a| {
+                        let edge = ts.edge(*q, *a).expect("transition system is complete");
+                        (states[&edge.target()], edge.color())
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            symbols,
+            successors,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.successors.len()
+    }
+
+    fn pair(p: usize, q: usize) -> Pair {
+        (p.min(q), p.max(q))
+    }
+
+    /// Runs a backward breadth-first search on the pair graph. The `resolves` function determines
+    /// whether reading the given symbol from the given pair of states resolves that pair. Returns
+    /// for each pair that can be resolved, the first symbol of a shortest word that resolves it,
+    /// together with the length of that word.
+    fn resolving_symbols(
+        &self,
+        resolves: impl Fn(usize, usize, usize) -> bool,
+    ) -> math::Map<Pair, (usize, usize)> {
+        let mut predecessors: math::Map<Pair, Vec<(Pair, usize)>> = math::Map::default();
+        let mut out = math::Map::default();
+        let mut queue = VecDeque::new();
+
+        for p in 0..self.size() {
+            for q in (p + 1)..self.size() {
+                for a in 0..self.symbols.len() {
+                    if resolves(p, q, a) {
+                        if !out.contains_key(&(p, q)) {
+                            out.insert((p, q), (a, 1));
+                            queue.push_back((p, q));
+                        }
+                    } else {
+                        let target = Self::pair(self.successors[p][a].0, self.successors[q][a].0);
+                        predecessors.entry(target).or_default().push(((p, q), a));
+                    }
+                }
+            }
+        }
+
+        while let Some(pair) = queue.pop_front() {
+            let distance = out[&pair].1;
+            for (source, a) in predecessors.get(&pair).into_iter().flatten() {
+                if !out.contains_key(source) {
+                    out.insert(*source, (*a, distance + 1));
+                    queue.push_back(*source);
+                }
+            }
+        }
+        out
+    }
+
+    /// Reconstructs the word that resolves the pair `(p, q)` from the table computed by
+    /// [`Self::resolving_symbols`].
+    fn resolving_word(
+        &self,
+        table: &math::Map<Pair, (usize, usize)>,
+        resolves: impl Fn(usize, usize, usize) -> bool,
+        (mut p, mut q): (usize, usize),
+    ) -> Vec<usize> {
+        let mut word = vec![];
+        loop {
+            let (a, _) = table[&Self::pair(p, q)];
+            word.push(a);
+            if resolves(p, q, a) {
+                return word;
+            }
+            (p, q) = (self.successors[p][a].0, self.successors[q][a].0);
+        }
+    }
+
+    /// Splits every block of states according to the colors produced when reading the symbol
+    /// with index `a` and replaces each state by its successor. Blocks that consist of a single
+    /// state are dropped, as they are resolved.
+    fn split(&self, blocks: &[math::OrderedSet<usize>], a: usize) -> Vec<math::OrderedSet<usize>> {
+        let mut out = math::OrderedSet::default();
+        for block in blocks {
+            let mut split: math::Map<&EdgeColor<D>, math::OrderedSet<usize>> = math::Map::default();
+            for q in block {
+                let (target, color) = &self.successors[*q][a];
+                split.entry(color).or_default().insert(*target);
+            }
+            out.extend(split.into_values().filter(|b| b.len() > 1));
+        }
+        out.into_iter().collect()
+    }
+
+    fn symbols_of(&self, word: Vec<usize>) -> Vec<SymbolOf<D>> {
+        word.into_iter().map(|a| self.symbols[a]).collect()
+    }
+}
+
+/// Computes a shortest synchronizing word by a breadth-first search in the subset construction,
+/// starting from the set of all states.
+pub(crate) fn shortest_synchronizing_word<D>(ts: &D) -> Option<Vec<SymbolOf<D>>>
+where
+    D: Deterministic,
+{
+    assert!(
+        ts.is_complete(),
+        "synchronizing words only make sense for complete transition systems"
+    );
+    let subsets = ts.subset_construction_from(ts.state_indices());
+    subsets.minimal_representatives_iter().find_map(|rep| {
+        let colors = subsets
+            .state_color(rep.state_index())
+            .expect("state must exist");
+        (colors.len() <= 1).then(|| rep.decompose().0)
+    })
+}
+
+/// Computes a synchronizing word using the greedy heuristic of Eppstein, which repeatedly applies a
+/// shortest word that merges some pair of the remaining states.
+pub(crate) fn greedy_synchronizing_word<D>(ts: &D) -> Option<Vec<SymbolOf<D>>>
+where
+    D: Deterministic,
+{
+    let table = Table::new(ts);
+    let merges =
+        |p: usize, q: usize, a: usize| table.successors[p][a].0 == table.successors[q][a].0;
+    let merging = table.resolving_symbols(merges);
+
+    let mut current: math::OrderedSet<usize> = (0..table.size()).collect();
+    let mut word = vec![];
+    while current.len() > 1 {
+        let (_, pair) = current
+            .iter()
+            .flat_map(|p| current.range(p + 1..).map(move |q| (*p, *q)))
+            .map(|pair| merging.get(&pair).map(|(_, d)| (*d, pair)))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .min()
+            .expect("there are at least two states");
+        for a in table.resolving_word(&merging, merges, pair) {
+            current = current.iter().map(|q| table.successors[*q][a].0).collect();
+            word.push(a);
+        }
+    }
+    Some(table.symbols_of(word))
+}
+
+/// Computes a shortest homing word by a breadth-first search over the possible states of knowledge,
+/// each of which is a set of blocks of states that cannot yet be distinguished.
+pub(crate) fn shortest_homing_word<D>(ts: &D) -> Option<Vec<SymbolOf<D>>>
+where
+    D: Deterministic,
+{
+    let table = Table::new(ts);
+    let initial: Vec<math::OrderedSet<usize>> = if table.size() > 1 {
+        vec![(0..table.size()).collect()]
+    } else {
+        vec![]
+    };
+    let mut seen = math::Map::from_iter([(initial.clone(), None)]);
+    let mut queue = VecDeque::from([initial]);
+
+    while let Some(blocks) = queue.pop_front() {
+        if blocks.is_empty() {
+            let mut word = vec![];
+            let mut current = &blocks;
+            while let Some((previous, a)) = &seen[current] {
+                word.push(*a);
+                current = previous;
+            }
+            word.reverse();
+            return Some(table.symbols_of(word));
+        }
+        for a in 0..table.symbols.len() {
+            let next = table.split(&blocks, a);
+            if !seen.contains_key(&next) {
+                seen.insert(next.clone(), Some((blocks.clone(), a)));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Computes a homing word by repeatedly applying a shortest word that either distinguishes some
+/// pair of states from the same block through the produced colors or merges them.
+pub(crate) fn greedy_homing_word<D>(ts: &D) -> Option<Vec<SymbolOf<D>>>
+where
+    D: Deterministic,
+{
+    let table = Table::new(ts);
+    let resolves = |p: usize, q: usize, a: usize| {
+        let ((p, c), (q, d)) = (&table.successors[p][a], &table.successors[q][a]);
+        p == q || c != d
+    };
+    let resolving = table.resolving_symbols(resolves);
+
+    let mut blocks: Vec<math::OrderedSet<usize>> = if table.size() > 1 {
+        vec![(0..table.size()).collect()]
+    } else {
+        vec![]
+    };
+    let mut word = vec![];
+    while let Some(block) = blocks.first() {
+        let mut it = block.iter();
+        let pair = (*it.next().unwrap(), *it.next().unwrap());
+        resolving.get(&pair)?;
+        for a in table.resolving_word(&resolving, resolves, pair) {
+            blocks = table.split(&blocks, a);
+            word.push(a);
+        }
+    }
+    Some(table.symbols_of(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::MealyMachine;
+    use crate::ts::{Deterministic, TSBuilder};
+
+    #[test]
+    fn cerny_automaton() {
+        // the Černý automaton with four states, whose shortest synchronizing word has length 9
+        let ts = TSBuilder::without_colors()
+            .with_edges([
+                (0, 'a', 1),
+                (1, 'a', 2),
+                (2, 'a', 3),
+                (3, 'a', 0),
+                (0, 'b', 1),
+                (1, 'b', 1),
+                (2, 'b', 2),
+                (3, 'b', 3),
+            ])
+            .into_dts();
+        let shortest = ts.shortest_synchronizing_word().unwrap();
+        assert_eq!(shortest.len(), 9);
+        let greedy = ts.synchronizing_word().unwrap();
+        assert!(greedy.len() >= 9);
+        for word in [shortest, greedy] {
+            let targets: Vec<_> = (0..4)
+                .map(|q| ts.reached_state_index_from(q, &word).unwrap())
+                .collect();
+            assert!(targets.iter().all(|q| *q == targets[0]));
+        }
+
+        let permutation = TSBuilder::without_colors()
+            .with_edges([(0, 'a', 1), (1, 'a', 0), (0, 'b', 0), (1, 'b', 1)])
+            .into_dts();
+        assert!(!permutation.is_synchronizing());
+        assert_eq!(permutation.shortest_synchronizing_word(), None);
+    }
+
+    #[test]
+    fn homing_words() {
+        // a counter modulo three, which outputs 1 when it wraps around on `a` and 0 otherwise
+        let mm: MealyMachine = TSBuilder::without_state_colors()
+            .with_transitions([
+                (0, 'a', 0, 1),
+                (1, 'a', 0, 2),
+                (2, 'a', 1, 0),
+                (0, 'b', 0, 0),
+                (1, 'b', 0, 1),
+                (2, 'b', 0, 2),
+            ])
+            .into_mealy(0);
+        assert!(!mm.is_synchronizing());
+
+        let shortest = mm.shortest_homing_word().unwrap();
+        assert_eq!(shortest.len(), 2);
+        assert!(mm.homing_word().is_some());
+
+        let silent: MealyMachine = TSBuilder::without_state_colors()
+            .with_transitions([(0, 'a', 0, 1), (1, 'a', 0, 0)])
+            .into_mealy(0);
+        assert_eq!(silent.shortest_homing_word(), None);
+        assert_eq!(silent.homing_word(), None);
+    }
+}